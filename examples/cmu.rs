@@ -3,7 +3,6 @@
 #![no_main]
 #![no_std]
 
-use cortex_m::asm::nop;
 use cortex_m_rt::entry;
 use efm32pg1b_hal::cmu::{CmuExt, DbgClockSource, HfClockPrescaler, HfClockSource};
 use efm32pg1b_hal::pac;
@@ -25,16 +24,14 @@ fn main() -> ! {
     let selected_hf_clk = cmu.hfclkstatus().read().selected().variant();
     defmt::println!("{}", selected_hf_clk);
 
+    let clocks = p.cmu.split();
+
     // Safety startup delay, in case the clock test goes wrong
     defmt::println!("Safe start");
-    for _ in 0..1_000_000 {
-        nop();
-    }
+    clocks.delay_us(500_000);
     defmt::println!("Safe end");
 
-    let clocks = p
-        .cmu
-        .split()
+    let clocks = clocks
         .with_hf_clk(HfClockSource::HfRco, HfClockPrescaler::Div10)
         .with_dbg_clk(DbgClockSource::HfClk);
 
@@ -18,7 +18,9 @@ fn main() -> ! {
     gpio.port_f.set_drive_strength(DriveStrength::Strong);
     gpio.port_f.set_drive_strength_alt(DriveStrength::Strong);
 
-    // Calling this is fine since the debug pins use the `Primary` not the `Alternate` port `F` ctrl configs
+    // Calling this is fine since the debug pins use the `Primary` not the `Alternate` port `F` ctrl configs. `LED 1`
+    // can still be toggled, because `toggle()` and `is_set_high()` read the output latch instead of the input buffer.
+    // Ports where all the pins are outputs can disable both input buffers with `set_output_only()` instead
     gpio.port_f.set_din_dis_alt(DataInCtrl::Disabled);
 
     // // LED 0, BTN 0
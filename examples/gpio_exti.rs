@@ -4,7 +4,7 @@
 #![no_std]
 
 use core::cell::RefCell;
-use cortex_m::asm::nop;
+use cortex_m::asm::wfi;
 use cortex_m_rt::entry;
 use critical_section::Mutex;
 use defmt::info;
@@ -54,19 +54,19 @@ fn main() -> ! {
     });
 
     // ---- Btn 0 ----
+    // the handler can also be (re)registered after binding, together with the edge which triggers it
     let mut btn0 = gpio
         .pf6
         .into_mode::<InFloat>()
-        .into_exti_bound_pin(gpio.exti4ctrl, |exti| {
-            critical_section::with(|cs| {
-                let mut led = LED0.borrow(cs).borrow_mut();
-                if let Some(led) = led.as_mut() {
-                    exti_toggle(exti, led);
-                }
-            });
+        .into_exti_bound_pin(gpio.exti4ctrl, |_| {});
+    btn0.on_edge(ExtiEdge::Falling, |exti| {
+        critical_section::with(|cs| {
+            let mut led = LED0.borrow(cs).borrow_mut();
+            if let Some(led) = led.as_mut() {
+                exti_toggle(exti, led);
+            }
         });
-    btn0.exti_ctrl_ref_mut().edge_select(ExtiEdge::Falling);
-    btn0.exti_ctrl_ref_mut().enable();
+    });
 
     // ---- Led 1 ----
     critical_section::with(|cs| {
@@ -106,8 +106,9 @@ fn main() -> ! {
         );
     }
 
+    // Sleep between button presses: the handlers are called from the `GPIO_EVEN`/`GPIO_ODD` interrupts
     loop {
-        nop();
+        wfi();
     }
 }
 
@@ -0,0 +1,104 @@
+//! Build with `cargo build --example spi_shared --features="defmt qfn48"`
+//!
+//! Drive the Memory LCD of the `UG154: EFM32 Pearl Gecko Starter Kit` and a second SPI sensor (connected to the
+//! expansion header, and selected with `PC9`) on the same `USART1` bus.
+
+#![no_main]
+#![no_std]
+
+use core::cell::RefCell;
+use cortex_m_rt::entry;
+use defmt_rtt as _;
+use efm32pg1b_hal::{
+    prelude::*,
+    timer::TimerDivider,
+    usart::spi::shared::{BusCs, RefCellBus, RefCellDevice},
+};
+use embedded_hal::spi::{Operation, SpiDevice};
+use ls013b7dh03::{prelude::*, WIDTH};
+use panic_probe as _;
+
+/// Minimal driver of a sensor which reads a register when the MSB of the register address is set
+struct Sensor<SPI> {
+    spi: SPI,
+}
+
+impl<SPI> Sensor<SPI>
+where
+    SPI: SpiDevice,
+{
+    /// Identification register
+    const WHO_AM_I: u8 = 0x0F;
+
+    fn new(spi: SPI) -> Self {
+        Self { spi }
+    }
+
+    fn who_am_i(&mut self) -> Result<u8, SPI::Error> {
+        let mut id = [0u8];
+
+        self.spi
+            .transaction(&mut [Operation::Write(&[Self::WHO_AM_I | 0x80]), Operation::Read(&mut id)])?;
+
+        Ok(id[0])
+    }
+}
+
+#[entry]
+fn main() -> ! {
+    let p = pac::Peripherals::take().unwrap();
+    let clocks = p.cmu.split().with_hf_clk(HfClockSource::HfRco, HfClockPrescaler::Div4);
+    let gpio = Gpio::new(p.gpio);
+
+    // Let this App take control of display (this is a `UG154: EFM32 Pearl Gecko Starter Kit` paticularity)
+    let _ = gpio.pd15.into_mode::<OutPp>().set_high();
+
+    // The sensor is selected with the CS pin owned by the bus
+    let mut spi = Usart::new(p.usart1).into_spi_bus_with_cs(
+        gpio.pc8.into_mode::<OutPp>(),
+        gpio.pc6.into_mode::<OutPp>(),
+        gpio.pc7.into_mode::<InFilt>(),
+        gpio.pc9.into_mode::<OutPp>(),
+        SPIMODE,
+    );
+    let _spi_br = spi.set_baudrate(1.MHz(), &clocks);
+    let bus = RefCell::new(spi);
+
+    // The display driver controls its own CS pin, so it only needs the bus
+    let mut buffer = [0u8; BUF_SIZE];
+    let mut disp = Ls013b7dh03::new(
+        RefCellBus::new(&bus),
+        gpio.pd14.into_mode::<OutPp>(),
+        gpio.pf4.into_mode::<OutPp>(),
+        &mut buffer,
+    );
+
+    let mut sensor = Sensor::new(RefCellDevice::new(&bus, BusCs, &clocks).unwrap());
+
+    let (tim0ch0, tim0ch1, _tim0ch2, _tim0ch3) = p.timer0.into_timer(TimerDivider::Div1024).into_channels();
+
+    // COM inversion of the display
+    let mut com_inv = tim0ch1.into_pwm(gpio.pd13.into_mode::<OutPp>());
+    let _ = com_inv.set_duty_cycle(10);
+
+    let mut delay = tim0ch0.into_delay(&clocks);
+    let mut fill = true;
+
+    loop {
+        match sensor.who_am_i() {
+            Ok(id) => defmt::info!("sensor id: {=u8:#x}", id),
+            Err(e) => defmt::error!("sensor error: {}", e),
+        }
+
+        for y in 0..HEIGHT as u8 {
+            for x in 0..WIDTH as u8 {
+                let _ = disp.write(x, y, fill);
+            }
+        }
+
+        disp.flush();
+        fill = !fill;
+
+        delay.delay_ms(1000);
+    }
+}
@@ -0,0 +1,209 @@
+//! Bit-banged (software) peripherals
+//!
+//! Fallback drivers on arbitrary GPIO pins, for pin combinations which cannot be routed to the hardware peripherals.
+//! They are much slower than the hardware peripherals, and they keep the CPU busy for the whole transfer.
+//!
+//! ```rust,no_run
+//! let clk = gpio.pd9.into_mode::<OutPp>();
+//! let mosi = gpio.pd10.into_mode::<OutPp>();
+//! let miso = gpio.pd11.into_mode::<InPu>();
+//!
+//! let mut spi = Spi::new(clk, mosi, miso, spi::MODE_0, 100.kHz(), &clocks).unwrap();
+//!
+//! let mut buf = [0x9Fu8, 0, 0, 0];
+//! spi.transfer_in_place(&mut buf).unwrap();
+//! ```
+
+use crate::{cmu::Clocks, gpio::GpioError};
+use embedded_hal::{
+    digital::{InputPin, OutputPin, PinState},
+    spi::{Error, ErrorKind, ErrorType, Mode, Phase, Polarity, SpiBus},
+};
+use fugit::HertzU32;
+
+/// Software SPI master, which implements the `SpiBus` trait by toggling GPIO pins
+///
+/// The bytes are shifted out most significant bit first, with the clock polarity and phase given by the SPI `Mode`.
+/// There is no chip select pin: the device must be selected by the caller (or by an `SpiDevice` implementation).
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Spi<CLK, MOSI, MISO> {
+    pin_clk: CLK,
+    pin_mosi: MOSI,
+    pin_miso: MISO,
+    /// Clock polarity (`CPOL`)
+    idle_high: bool,
+    /// Clock phase (`CPHA`)
+    capture_on_second_edge: bool,
+    half_period_cycles: u32,
+}
+
+impl<CLK, MOSI, MISO> Spi<CLK, MOSI, MISO>
+where
+    CLK: OutputPin<Error = GpioError>,
+    MOSI: OutputPin<Error = GpioError>,
+    MISO: InputPin<Error = GpioError>,
+{
+    /// Byte which is sent while reading
+    const FILLER_BYTE: u8 = 0x00;
+
+    /// Create a software SPI on the given pins, and drive the clock pin to its idle level.
+    ///
+    /// Each half of the clock period is at least `HFCORECLK / (2 * baudrate)` core clock cycles long (see
+    /// [`Clocks::delay_cycles()`]). The time spent toggling the pins is added to it, so the actual baudrate is lower
+    /// than the requested one, especially for high baudrates. A baudrate of `0`, or above `HFCORECLK / 2` (i.e. with a
+    /// half period shorter than one core clock cycle), is rejected with [`BitbangError::InvalidBaudrate`].
+    pub fn new(
+        pin_clk: CLK,
+        pin_mosi: MOSI,
+        pin_miso: MISO,
+        mode: Mode,
+        baudrate: HertzU32,
+        clocks: &Clocks,
+    ) -> Result<Self, BitbangError> {
+        // Divided in two steps, since `2 * baudrate` may overflow
+        let half_period_cycles = match (clocks.hf_core_clk().raw() / 2).checked_div(baudrate.raw()) {
+            Some(cycles) if cycles > 0 => cycles,
+            _ => return Err(BitbangError::InvalidBaudrate(baudrate)),
+        };
+
+        let mut spi = Self {
+            pin_clk,
+            pin_mosi,
+            pin_miso,
+            idle_high: mode.polarity == Polarity::IdleHigh,
+            capture_on_second_edge: mode.phase == Phase::CaptureOnSecondTransition,
+            half_period_cycles,
+        };
+
+        spi.set_clk(false)?;
+
+        Ok(spi)
+    }
+
+    /// Release the pins
+    pub fn free(self) -> (CLK, MOSI, MISO) {
+        (self.pin_clk, self.pin_mosi, self.pin_miso)
+    }
+
+    /// Shift `byte` out on MOSI while shifting the returned byte in from MISO
+    fn transfer_byte(&mut self, byte: u8) -> Result<u8, BitbangError> {
+        let mut read = 0u8;
+
+        for bit in (0..8).rev() {
+            let out = PinState::from(byte & (1 << bit) != 0);
+
+            let sample = match self.capture_on_second_edge {
+                // The data is set up before the leading edge, and sampled on it
+                false => {
+                    self.pin_mosi.set_state(out)?;
+                    self.delay_half_period();
+                    self.set_clk(true)?;
+                    let sample = self.pin_miso.is_high()?;
+                    self.delay_half_period();
+                    self.set_clk(false)?;
+                    sample
+                }
+                // The data is set up on the leading edge, and sampled on the trailing edge
+                true => {
+                    self.set_clk(true)?;
+                    self.pin_mosi.set_state(out)?;
+                    self.delay_half_period();
+                    self.set_clk(false)?;
+                    let sample = self.pin_miso.is_high()?;
+                    self.delay_half_period();
+                    sample
+                }
+            };
+
+            read |= (sample as u8) << bit;
+        }
+
+        Ok(read)
+    }
+
+    /// Drive the clock pin to its active level (`true`), or to its idle level (`false`)
+    fn set_clk(&mut self, active: bool) -> Result<(), BitbangError> {
+        self.pin_clk.set_state(PinState::from(active != self.idle_high))?;
+
+        Ok(())
+    }
+
+    fn delay_half_period(&self) {
+        Clocks::delay_cycles(self.half_period_cycles);
+    }
+}
+
+impl<CLK, MOSI, MISO> ErrorType for Spi<CLK, MOSI, MISO> {
+    type Error = BitbangError;
+}
+
+impl<CLK, MOSI, MISO> SpiBus<u8> for Spi<CLK, MOSI, MISO>
+where
+    CLK: OutputPin<Error = GpioError>,
+    MOSI: OutputPin<Error = GpioError>,
+    MISO: InputPin<Error = GpioError>,
+{
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        for word in words {
+            *word = self.transfer_byte(Self::FILLER_BYTE)?;
+        }
+
+        Ok(())
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        for word in words {
+            self.transfer_byte(*word)?;
+        }
+
+        Ok(())
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        for i in 0..read.len().max(write.len()) {
+            let byte = self.transfer_byte(write.get(i).copied().unwrap_or(Self::FILLER_BYTE))?;
+
+            if let Some(word) = read.get_mut(i) {
+                *word = byte;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        for word in words {
+            *word = self.transfer_byte(*word)?;
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        // Each byte is complete when `transfer_byte()` returns
+        Ok(())
+    }
+}
+
+/// Bit-banged peripheral errors
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BitbangError {
+    /// Invalid baud rate
+    InvalidBaudrate(HertzU32),
+    /// One of the pins could not be read or written
+    Pin(GpioError),
+}
+
+impl From<GpioError> for BitbangError {
+    fn from(e: GpioError) -> Self {
+        BitbangError::Pin(e)
+    }
+}
+
+impl Error for BitbangError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
@@ -2,13 +2,14 @@
 //!
 
 use crate::gpio::{pin::mode::OutputMode, pin::Pin};
-use cortex_m::asm::nop;
+use cortex_m::asm::{delay, nop};
 use efm32pg1b_pac::{
     cmu::{hfclksel::HF, hfclkstatus::SELECTED},
     wdog0::ctrl::CLKSEL,
-    Cmu, Cryotimer, Wdog0,
+    Cmu, Cryotimer, Msc, Wdog0,
 };
-use fugit::HertzU32;
+use core::fmt;
+use fugit::{HertzU32, MicrosDurationU32};
 
 /// Default HF RCO frequency at Reset
 const DEFAULT_HF_RCO_FREQUENCY: HertzU32 = HertzU32::MHz(19);
@@ -16,12 +17,44 @@ const DEFAULT_HF_RCO_FREQUENCY: HertzU32 = HertzU32::MHz(19);
 /// Default AUX HF RCO frequency at Reset
 const DEFAULT_AUX_HF_RCO_FREQUENCY: HertzU32 = HertzU32::MHz(19);
 
+/// Base address of the Device Information page
+const DEVINFO_BASE: usize = 0x0FE0_81B0;
+
+/// Offset of `DEVINFO_AUXHFRCOCAL0` (the 4 MHz band) in the Device Information page. The calibration register of band
+/// `n` is at `DEVINFO_AUXHFRCOCAL0 + 4 * n`.
+const DEVINFO_AUXHFRCOCAL0_OFFSET: usize = 0xE0;
+
 /// Default LF RCO frequency at Reset
 const DEFAULT_LF_RCO_FREQUENCY: HertzU32 = HertzU32::kHz(32);
 
 /// Default Ultra LF RCO frequency at Reset
 const DEFAULT_ULF_RCO_FREQUENCY: HertzU32 = HertzU32::kHz(1);
 
+/// Minimum HF XO frequency
+const HF_XO_FREQUENCY_MIN: HertzU32 = HertzU32::MHz(38);
+
+/// Maximum HF XO frequency
+const HF_XO_FREQUENCY_MAX: HertzU32 = HertzU32::MHz(40);
+
+/// Maximum HF Clock frequency which can be used without flash wait states
+const MAX_FREQUENCY_0_WAIT_STATES: HertzU32 = HertzU32::MHz(25);
+
+/// Frequency of the LF XO crystal which is used as the reference by [`Clocks::lock_hfrco_to_lfxo()`]
+const LF_XO_REFERENCE_FREQUENCY: HertzU32 = HertzU32::Hz(32_768);
+
+/// Number of LF XO periods during which the HF RCO cycles are counted by [`Clocks::lock_hfrco_to_lfxo()`]. Each count
+/// is `32_768 / 512 = 64 Hz`, and the counter (20 bits) does not overflow below `2 GHz`.
+const HF_RCO_LOCK_REF_CYCLES: u32 = 512;
+
+/// Largest value of the `TUNING` field of `CMU_HFRCOCTRL`
+const HF_RCO_TUNING_MAX: u8 = 0x7F;
+
+/// Largest error of a locked HF RCO frequency, as a fraction of the target frequency (`1 / 200`, i.e. `0.5 %`)
+const HF_RCO_LOCK_TOLERANCE_DIV: u32 = 200;
+
+/// Maximum HF Clock frequency which can be used with the HF Clock LE divided by `2`
+const MAX_FREQUENCY_HF_CLK_LE_DIV2: HertzU32 = HertzU32::MHz(32);
+
 /// Extension trait to split the CMU peripheral into clocks
 pub trait CmuExt {
     /// The parts to split the CMU into
@@ -29,6 +62,9 @@ pub trait CmuExt {
 
     /// TODO:
     fn split(self) -> Self::Parts;
+
+    /// Start a [`ClockConfig`], which is validated and applied in a single step
+    fn configure(self) -> ClockConfig;
 }
 
 impl CmuExt for Cmu {
@@ -37,12 +73,311 @@ impl CmuExt for Cmu {
     fn split(self) -> Self::Parts {
         Clocks::calculate_hf_clocks(DEFAULT_HF_RCO_FREQUENCY)
     }
+
+    fn configure(self) -> ClockConfig {
+        ClockConfig::new()
+    }
 }
 
-/// TODO:
+/// Clock tree configuration
+///
+/// Unlike the `with_*()` methods of [`Clocks`], which program the registers as they are chained, a `ClockConfig` only
+/// accumulates the desired clock tree. The whole configuration is then validated and programmed in the correct order
+/// (including the flash wait states) by [`ClockConfig::apply()`].
+///
+/// ```rust,no_run
+/// let clocks = p
+///     .cmu
+///     .configure()
+///     .with_hf_clk(HfClockSource::HfXO(38_400.kHz()), HfClockPrescaler::Div1)
+///     .with_lfa_clk(LfClockSource::LfRco)
+///     .apply()
+///     .unwrap();
+/// ```
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ClockConfig {
+    hf_clk: Option<(HfClockSource, HfClockPrescaler)>,
+    hf_per_presc: Option<HfPerPrescaler>,
+    dbg_clk: Option<DbgClockSource>,
+    lfa_clk: Option<LfClockSource>,
+    lfb_clk: Option<LfBClockSource>,
+    lfe_clk: Option<LfClockSource>,
+    wdog_clk: Option<LfClockSource>,
+    cryo_clk: Option<LfClockSource>,
+    lfxo_cfg: Option<LfXoConfig>,
+    xo_timeout: Option<MicrosDurationU32>,
+}
+
+impl ClockConfig {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Select the High Frequency Clock source and prescaler
+    pub fn with_hf_clk(self, clk_src: HfClockSource, prescaler: HfClockPrescaler) -> Self {
+        Self {
+            hf_clk: Some((clk_src, prescaler)),
+            ..self
+        }
+    }
+
+    /// Select the HF Peripheral Clock prescaler
+    pub fn with_hf_per_prescaler(self, presc: HfPerPrescaler) -> Self {
+        Self {
+            hf_per_presc: Some(presc),
+            ..self
+        }
+    }
+
+    /// Select the Debug Clock source
+    pub fn with_dbg_clk(self, clk_src: DbgClockSource) -> Self {
+        Self {
+            dbg_clk: Some(clk_src),
+            ..self
+        }
+    }
+
+    /// Select the Low Frequency A Clock source
+    pub fn with_lfa_clk(self, clk_src: LfClockSource) -> Self {
+        Self {
+            lfa_clk: Some(clk_src),
+            ..self
+        }
+    }
+
+    /// Select the Low Frequency B Clock source
+    pub fn with_lfb_clk(self, clk_src: LfBClockSource) -> Self {
+        Self {
+            lfb_clk: Some(clk_src),
+            ..self
+        }
+    }
+
+    /// Select the Low Frequency E Clock source
+    pub fn with_lfe_clk(self, clk_src: LfClockSource) -> Self {
+        Self {
+            lfe_clk: Some(clk_src),
+            ..self
+        }
+    }
+
+    /// Select the Watch Dog Clock source
+    pub fn with_wdog_clk(self, clk_src: LfClockSource) -> Self {
+        Self {
+            wdog_clk: Some(clk_src),
+            ..self
+        }
+    }
+
+    /// Select the Cryo Timer Clock source
+    pub fn with_cryo_clk(self, clk_src: LfClockSource) -> Self {
+        Self {
+            cryo_clk: Some(clk_src),
+            ..self
+        }
+    }
+
+    /// Configure the LF XO (see [`Clocks::with_lfxo_config()`])
+    pub fn with_lfxo_config(self, cfg: LfXoConfig) -> Self {
+        Self {
+            lfxo_cfg: Some(cfg),
+            ..self
+        }
+    }
+
+    /// Wait at most `timeout` for each crystal oscillator (HF XO and LF XO) which is used by the configuration, so that
+    /// [`ClockConfig::apply()`] returns [`CmuError::OscillatorTimeout`] instead of hanging if a crystal is missing.
+    ///
+    /// By default, `apply()` waits forever, which is fine if the crystals are guaranteed to be there. See
+    /// [`Clocks::enable_oscillator()`].
+    pub fn with_xo_timeout(self, timeout: MicrosDurationU32) -> Self {
+        Self {
+            xo_timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Check the configuration without touching any register
+    pub fn validate(&self) -> Result<(), CmuError> {
+        let hf_clk = self.hf_clk_frequency()?;
+
+        for lf_src in [self.lfa_clk, self.lfe_clk, self.wdog_clk, self.cryo_clk]
+            .into_iter()
+            .flatten()
+        {
+            if let LfClockSource::LfXO(freq) = lf_src {
+                Self::validate_lfxo(freq)?;
+            }
+        }
+
+        match self.lfb_clk {
+            Some(LfBClockSource::LfXO(freq)) => Self::validate_lfxo(freq)?,
+            Some(LfBClockSource::HfClkLe(false)) if hf_clk > MAX_FREQUENCY_HF_CLK_LE_DIV2 => {
+                return Err(CmuError::HfClkLeTooFast(hf_clk / 2));
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Validate the configuration, and program the clock registers
+    pub fn apply(self) -> Result<Clocks, CmuError> {
+        self.validate()?;
+
+        let hf_clk = self.hf_clk_frequency()?;
+
+        let mut clocks = Clocks::calculate_hf_clocks(DEFAULT_HF_RCO_FREQUENCY);
+
+        if let Some(cfg) = self.lfxo_cfg {
+            clocks = clocks.with_lfxo_config(cfg);
+        }
+
+        // Start the crystal oscillators before anything is changed, so that nothing is left half configured if one
+        // of them fails
+        if let Some(timeout) = self.xo_timeout {
+            for osc in self.crystal_oscillators() {
+                clocks.enable_oscillator(osc, Some(timeout))?;
+            }
+        }
+
+        // Flash wait states must be increased _before_ the HF Clock frequency is increased
+        if hf_clk > MAX_FREQUENCY_0_WAIT_STATES {
+            set_flash_wait_states(hf_clk);
+        }
+
+        if let Some((clk_src, prescaler)) = self.hf_clk {
+            clocks = clocks.with_hf_clk(clk_src, prescaler);
+        }
+
+        // ... and they can only be decreased _after_ the HF Clock frequency was decreased
+        if hf_clk <= MAX_FREQUENCY_0_WAIT_STATES {
+            set_flash_wait_states(hf_clk);
+        }
+
+        if let Some(presc) = self.hf_per_presc {
+            clocks = clocks.set_hf_per_prescaler(presc);
+        }
+
+        if let Some(clk_src) = self.dbg_clk {
+            clocks = clocks.with_dbg_clk(clk_src);
+        }
+
+        if let Some(clk_src) = self.lfa_clk {
+            clocks = clocks.with_lfa_clk(clk_src);
+        }
+
+        if let Some(clk_src) = self.lfb_clk {
+            clocks = clocks.with_lfb_clk(clk_src);
+        }
+
+        if let Some(clk_src) = self.lfe_clk {
+            clocks = clocks.with_lfe_clk(clk_src);
+        }
+
+        if let Some(clk_src) = self.wdog_clk {
+            clocks = clocks.with_wdog_clk(clk_src);
+        }
+
+        if let Some(clk_src) = self.cryo_clk {
+            clocks = clocks.with_cryo_clk(clk_src);
+        }
+
+        Ok(clocks)
+    }
+
+    /// Calculate the High Frequency Clock which would result from this configuration
+    fn hf_clk_frequency(&self) -> Result<HertzU32, CmuError> {
+        match self.hf_clk {
+            Some((clk_src, prescaler)) => {
+                let src_freq = match clk_src {
+                    HfClockSource::HfXO(freq) => {
+                        if !(HF_XO_FREQUENCY_MIN..=HF_XO_FREQUENCY_MAX).contains(&freq) {
+                            return Err(CmuError::InvalidHfXoFrequency(freq));
+                        }
+                        freq
+                    }
+                    HfClockSource::HfRco => DEFAULT_HF_RCO_FREQUENCY,
+                    HfClockSource::LfXO(freq) => {
+                        Self::validate_lfxo(freq)?;
+                        freq
+                    }
+                    HfClockSource::LfRco => DEFAULT_LF_RCO_FREQUENCY,
+                };
+
+                Ok(src_freq / (prescaler as u32 + 1))
+            }
+            None => Ok(Clocks::calculate_hf_clocks(DEFAULT_HF_RCO_FREQUENCY).hf_bus_clk),
+        }
+    }
+
+    /// Crystal oscillators which are used by the configuration
+    fn crystal_oscillators(&self) -> impl Iterator<Item = Oscillator> {
+        let hf_xo = matches!(self.hf_clk, Some((HfClockSource::HfXO(_), _)));
+
+        let lf_xo = matches!(self.hf_clk, Some((HfClockSource::LfXO(_), _)))
+            || matches!(self.lfb_clk, Some(LfBClockSource::LfXO(_)))
+            || [self.lfa_clk, self.lfe_clk, self.wdog_clk, self.cryo_clk]
+                .into_iter()
+                .any(|src| matches!(src, Some(LfClockSource::LfXO(_))));
+
+        [(hf_xo, Oscillator::HfXO), (lf_xo, Oscillator::LfXO)]
+            .into_iter()
+            .filter_map(|(used, osc)| used.then_some(osc))
+    }
+
+    fn validate_lfxo(freq: HertzU32) -> Result<(), CmuError> {
+        match freq.raw() {
+            0 => Err(CmuError::InvalidLfXoFrequency(freq)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Set the flash wait states required by the given HF Clock frequency
+fn set_flash_wait_states(hf_clk: HertzU32) {
+    let msc = unsafe { Msc::steal() };
+
+    msc.readctrl().modify(|_, w| match hf_clk > MAX_FREQUENCY_0_WAIT_STATES {
+        true => w.mode().ws1(),
+        false => w.mode().ws0(),
+    });
+}
+
+/// CMU errors
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CmuError {
+    /// The declared HF XO frequency is outside of the supported range (38 MHz to 40 MHz)
+    InvalidHfXoFrequency(HertzU32),
+    /// The declared LF XO frequency is invalid
+    InvalidLfXoFrequency(HertzU32),
+    /// The HF Clock LE would exceed its maximum frequency, the `HfClkLe(true)` (divide by 4) source must be used
+    HfClkLeTooFast(HertzU32),
+    /// The LF XO tuning value (`0..=127`) or startup gain (`0..=3`) is out of range
+    InvalidLfXoConfig,
+    /// The LF XO configuration can only be changed while the LF XO is disabled
+    LfXoEnabled,
+    /// The HF Peripheral Clock divider is outside of the supported range (1 to 512)
+    InvalidHfPerPrescaler(u16),
+    /// The oscillator was not ready before the timeout, e.g. because the crystal is missing
+    OscillatorTimeout(Oscillator),
+    /// The HF RCO must be the selected HF Clock source
+    HfRcoNotSelected,
+    /// The target frequency is out of the tuning range of the current HF RCO band: the closest frequency which could
+    /// be reached is attached
+    HfRcoLockFailed(HertzU32),
+}
+
+/// Frozen clock frequencies
+///
+/// The [`Debug`](core::fmt::Debug) and `defmt::Format` implementations print the whole clock tree: the HF Clock source
+/// (read from `CMU_HFCLKSTATUS`), each HF prescaler, and the derived HF and LF clock frequencies.
 pub struct Clocks {
+    /// High Frequency Source Clock (i.e. the frequency of the selected HF Clock oscillator, before the prescaler)
+    hf_src_clk: HertzU32,
+
     /// High Frequency Peripheral Clock
     hf_per_clk: HertzU32,
 
@@ -55,6 +390,9 @@ pub struct Clocks {
     /// High Frequency  Bus Clock
     hf_bus_clk: HertzU32,
 
+    /// High Frequency Clock LE (HFBUSCLK divided by the `HFCLKLEPRESC` prescaler)
+    hf_clk_le: HertzU32,
+
     /// Low Frequency A Clock
     lfa_clk: Option<HertzU32>,
 
@@ -69,6 +407,12 @@ pub struct Clocks {
 
     /// Cryo Timer Clock
     cryo_clk: Option<HertzU32>,
+
+    /// Debug Clock (also clocks the flash erase/write logic when AUX HF RCO is selected)
+    dbg_clk: Option<HertzU32>,
+
+    /// LF XO configuration, applied when the LF XO is enabled
+    lfxo_cfg: Option<LfXoConfig>,
 }
 
 impl Clocks {
@@ -92,6 +436,14 @@ impl Clocks {
         self.hf_bus_clk
     }
 
+    /// High Frequency Clock LE, i.e. the HF Bus Clock divided by `2` or `4` (`HFCLKLEPRESC` field of `CMU_HFPRESC`).
+    ///
+    /// This is the LFB clock when [`LfBClockSource::HfClkLe`] is selected, and the divider is set by
+    /// [`Clocks::with_lfb_clk()`].
+    pub fn hf_clk_le(&self) -> HertzU32 {
+        self.hf_clk_le
+    }
+
     /// Low Frequency A Clock
     pub fn lfa_clk(&self) -> Option<HertzU32> {
         self.lfa_clk
@@ -117,7 +469,294 @@ impl Clocks {
         self.cryo_clk
     }
 
-    /// TODO:
+    /// Debug Clock
+    pub fn dbg_clk(&self) -> Option<HertzU32> {
+        self.dbg_clk
+    }
+
+    /// Busy wait for at least `cycles` core clock cycles. The CPU does nothing else, so this should only be used for
+    /// short delays (e.g. while bringing up oscillators), or before any timer is configured.
+    pub fn delay_cycles(cycles: u32) {
+        delay(cycles);
+    }
+
+    /// Busy wait for at least `us` microseconds, as measured by the HF Core Clock. See [`Clocks::delay_cycles()`].
+    ///
+    /// The delay is only accurate if [`Clocks::hf_core_clk()`] matches the actual core clock, i.e. the clocks have not
+    /// been changed since this `Clocks` instance was created, and the frequency of the HF clock source is correct.
+    pub fn delay_us(&self, us: u32) {
+        let mut cycles = self.hf_core_clk.to_Hz() as u64 * us as u64 / 1_000_000;
+
+        while cycles > 0 {
+            let chunk = cycles.min(u32::MAX as u64);
+            delay(chunk as u32);
+            cycles -= chunk;
+        }
+    }
+
+    /// Set the LF XO configuration which is programmed (in `CMU_LFXOCTRL`) by the `with_*()` methods which enable the
+    /// LF XO, before enabling it.
+    ///
+    /// This must be called before the `with_*()` methods which select an `LfXO` source. Crystals with a load
+    /// capacitance different from the default may otherwise fail to oscillate.
+    ///
+    /// ```rust,no_run
+    /// let clocks = p
+    ///     .cmu
+    ///     .split()
+    ///     .with_lfxo_config(LfXoConfig::new(0x30, 2, LfXoTimeout::Cycles32K).unwrap())
+    ///     .with_lfa_clk(LfClockSource::LfXO(32_768.Hz()));
+    /// ```
+    pub fn with_lfxo_config(self, cfg: LfXoConfig) -> Self {
+        Self {
+            lfxo_cfg: Some(cfg),
+            ..self
+        }
+    }
+
+    /// Program the LF XO configuration (`CMU_LFXOCTRL`). The LF XO must be disabled.
+    pub fn configure_lfxo(&self, cfg: LfXoConfig) -> Result<(), CmuError> {
+        let cmu = unsafe { Cmu::steal() };
+
+        if cmu.status().read().lfxoens().bit_is_set() {
+            return Err(CmuError::LfXoEnabled);
+        }
+
+        cmu.lfxoctrl().modify(|_, w| unsafe {
+            w.tuning().bits(cfg.tuning);
+            w.gain().bits(cfg.gain);
+            w.timeout().bits(cfg.timeout as u8)
+        });
+
+        Ok(())
+    }
+
+    /// Get the enabled and ready status of the given oscillator (`CMU_STATUS` register)
+    pub fn oscillator_status(&self, osc: Oscillator) -> OscStatus {
+        let status = unsafe { Cmu::steal() }.status().read();
+
+        let (enabled, ready) = match osc {
+            Oscillator::HfRco => (status.hfrcoens().bit_is_set(), status.hfrcordy().bit_is_set()),
+            Oscillator::HfXO => (status.hfxoens().bit_is_set(), status.hfxordy().bit_is_set()),
+            Oscillator::AuxHfRco => (status.auxhfrcoens().bit_is_set(), status.auxhfrcordy().bit_is_set()),
+            Oscillator::LfRco => (status.lfrcoens().bit_is_set(), status.lfrcordy().bit_is_set()),
+            Oscillator::LfXO => (status.lfxoens().bit_is_set(), status.lfxordy().bit_is_set()),
+        };
+
+        OscStatus { enabled, ready }
+    }
+
+    /// Enable the given oscillator, and wait until it is ready. Returns [`CmuError::OscillatorTimeout`] if it is not
+    /// ready within `timeout` (in which case it is disabled again, unless it was already enabled), or waits forever if
+    /// `timeout` is `None`.
+    ///
+    /// The `with_*()` methods wait forever for the oscillator they select, so a missing or broken crystal hangs the
+    /// MCU. Starting the crystal oscillators with this method first lets the application fall back to an RCO instead:
+    ///
+    /// ```rust,no_run
+    /// let clocks = p.cmu.split();
+    ///
+    /// let clocks = match clocks.enable_oscillator(Oscillator::HfXO, Some(10_000.micros())) {
+    ///     // the HF XO is ready, so this doesn't wait
+    ///     Ok(()) => clocks.with_hf_clk(HfClockSource::HfXO(38.MHz()), HfClockPrescaler::Div1),
+    ///     Err(_) => clocks,
+    /// };
+    /// ```
+    ///
+    /// The LF XO is configured from [`Clocks::with_lfxo_config()`] before it is enabled, like in the `with_*()`
+    /// methods. The timeout is measured with [`Clocks::delay_us()`], so it is only as accurate as the HF Core Clock.
+    pub fn enable_oscillator(&self, osc: Oscillator, timeout: Option<MicrosDurationU32>) -> Result<(), CmuError> {
+        let cmu = unsafe { Cmu::steal() };
+
+        /// Interval between two reads of the ready flag
+        const POLL_INTERVAL_US: u32 = 10;
+
+        let was_enabled = self.oscillator_status(osc).enabled;
+
+        if !was_enabled {
+            match osc {
+                Oscillator::HfRco => cmu.oscencmd().write(|w| w.hfrcoen().set_bit()),
+                Oscillator::HfXO => cmu.oscencmd().write(|w| w.hfxoen().set_bit()),
+                Oscillator::AuxHfRco => cmu.oscencmd().write(|w| w.auxhfrcoen().set_bit()),
+                Oscillator::LfRco => cmu.oscencmd().write(|w| w.lfrcoen().set_bit()),
+                Oscillator::LfXO => {
+                    if let Some(cfg) = self.lfxo_cfg {
+                        // [PANIC]: the LF XO was just checked to be disabled, so this cannot fail
+                        self.configure_lfxo(cfg).unwrap();
+                    }
+                    cmu.oscencmd().write(|w| w.lfxoen().set_bit())
+                }
+            };
+        }
+
+        let mut elapsed_us = 0;
+        while !self.oscillator_status(osc).ready {
+            if timeout.is_some_and(|t| elapsed_us >= t.to_micros()) {
+                if !was_enabled {
+                    match osc {
+                        Oscillator::HfRco => cmu.oscencmd().write(|w| w.hfrcodis().set_bit()),
+                        Oscillator::HfXO => cmu.oscencmd().write(|w| w.hfxodis().set_bit()),
+                        Oscillator::AuxHfRco => cmu.oscencmd().write(|w| w.auxhfrcodis().set_bit()),
+                        Oscillator::LfRco => cmu.oscencmd().write(|w| w.lfrcodis().set_bit()),
+                        Oscillator::LfXO => cmu.oscencmd().write(|w| w.lfxodis().set_bit()),
+                    };
+                }
+
+                return Err(CmuError::OscillatorTimeout(osc));
+            }
+
+            self.delay_us(POLL_INTERVAL_US);
+            elapsed_us += POLL_INTERVAL_US;
+        }
+
+        Ok(())
+    }
+
+    /// Tune the HF RCO to `target`, using the LF XO (a `32.768 kHz` crystal) as the frequency reference, and return
+    /// the clocks with the achieved frequency.
+    ///
+    /// The EFM32PG1B has no DPLL, so the lock is done once, in software: the CMU calibration counters
+    /// (`CMU_CALCTRL`/`CMU_CALCNT`) count the HF RCO cycles during 512 LF XO periods, and the `TUNING` field of
+    /// `CMU_HFRCOCTRL` is searched for the count closest to `target`. The HF RCO is not tracked afterwards, so the
+    /// lock can be repeated when the temperature or the supply voltage changes.
+    ///
+    /// ```rust,no_run
+    /// let clocks = p.cmu.split().with_lfxo_config(LfXoConfig::default());
+    ///
+    /// let clocks = clocks.lock_hfrco_to_lfxo(19_200.kHz()).unwrap();
+    /// defmt::println!("HF Core Clock: {}", clocks.hf_core_clk());
+    /// ```
+    ///
+    /// The HF RCO must be the selected HF Clock source (as after reset), otherwise [`CmuError::HfRcoNotSelected`] is
+    /// returned. The frequency band (`FREQRANGE`) is not changed, so `target` must be within the tuning range of the
+    /// current band: if the achieved frequency is off by more than `0.5 %`, the tuning is restored and
+    /// [`CmuError::HfRcoLockFailed`] is returned with the closest achievable frequency. The flash wait states are
+    /// raised during the search, and set for the achieved frequency afterwards.
+    ///
+    /// The LF XO is enabled (and left enabled) like in the `with_*()` methods, so this waits forever if the crystal
+    /// is missing: use [`Clocks::enable_oscillator()`] with a timeout first. Peripherals which were configured from the
+    /// previous `Clocks` (e.g. an SPI baudrate) must be configured again using the returned `Clocks`.
+    pub fn lock_hfrco_to_lfxo(self, target: HertzU32) -> Result<Self, CmuError> {
+        let cmu = unsafe { Cmu::steal() };
+
+        if !matches!(self.current_hf_source(), HfClockSource::HfRco) {
+            return Err(CmuError::HfRcoNotSelected);
+        }
+
+        self.enable_oscillator(Oscillator::LfXO, None)?;
+
+        let prev_tuning = cmu.hfrcoctrl().read().tuning().bits();
+
+        // Each `TUNING` value is tried while the core runs from the HF RCO, so keep a wait state during the search
+        set_flash_wait_states(HertzU32::MHz(38));
+
+        // The up counter counts the HF RCO cycles, while the down counter counts `CALCNT + 1` LF XO cycles
+        cmu.calctrl().write(|w| {
+            w.upsel().hfrco();
+            w.downsel().lfxo();
+            w.cont().clear_bit()
+        });
+        cmu.calcnt()
+            .write(|w| unsafe { w.calcnt().bits(HF_RCO_LOCK_REF_CYCLES - 1) });
+
+        let measure = |tuning: u8| -> u32 {
+            cmu.hfrcoctrl().modify(|_, w| unsafe { w.tuning().bits(tuning) });
+
+            cmu.ifc().write(|w| w.calrdy().set_bit());
+            cmu.cmd().write(|w| w.calstart().set_bit());
+
+            while cmu.if_().read().calrdy().bit_is_clear() {
+                nop();
+            }
+
+            cmu.calcnt().read().calcnt().bits()
+        };
+
+        let target_count =
+            (target.raw() as u64 * HF_RCO_LOCK_REF_CYCLES as u64 / LF_XO_REFERENCE_FREQUENCY.raw() as u64) as u32;
+
+        // The direction of `TUNING` is found from the ends of its range, and the search is done on the position `p`
+        // in the order of increasing frequency
+        let rising = measure(0) < measure(HF_RCO_TUNING_MAX);
+        let tuning = |p: u8| match rising {
+            true => p,
+            false => HF_RCO_TUNING_MAX - p,
+        };
+
+        // Find the first position whose frequency is not below `target`
+        let (mut lo, mut hi) = (0, HF_RCO_TUNING_MAX);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+
+            if measure(tuning(mid)) < target_count {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        // The position just below may be closer to `target`
+        let (best, count) = [lo.checked_sub(1), Some(lo)]
+            .into_iter()
+            .flatten()
+            .map(|p| (p, measure(tuning(p))))
+            .min_by_key(|&(_, count)| count.abs_diff(target_count))
+            // [PANIC]: the iterator contains at least `lo`
+            .unwrap();
+
+        let achieved = HertzU32::Hz(
+            (count as u64 * LF_XO_REFERENCE_FREQUENCY.raw() as u64 / HF_RCO_LOCK_REF_CYCLES as u64) as u32,
+        );
+
+        if achieved.raw().abs_diff(target.raw()) > target.raw() / HF_RCO_LOCK_TOLERANCE_DIV {
+            cmu.hfrcoctrl().modify(|_, w| unsafe { w.tuning().bits(prev_tuning) });
+            set_flash_wait_states(self.hf_bus_clk);
+
+            return Err(CmuError::HfRcoLockFailed(achieved));
+        }
+
+        cmu.hfrcoctrl().modify(|_, w| unsafe { w.tuning().bits(tuning(best)) });
+
+        let clocks = Self::calculate_hf_clocks(achieved);
+        set_flash_wait_states(clocks.hf_bus_clk);
+
+        // the Debug Clock follows the HF Clock if it is selected as its source
+        let dbg_clk = match cmu.dbgclksel().read().dbg().is_hfclk() {
+            true => self.dbg_clk.map(|_| clocks.hf_bus_clk),
+            false => self.dbg_clk,
+        };
+
+        Ok(Self {
+            lfa_clk: self.lfa_clk,
+            lfb_clk: self.lfb_clk,
+            lfe_clk: self.lfe_clk,
+            wdog_clk: self.wdog_clk,
+            cryo_clk: self.cryo_clk,
+            lfxo_cfg: self.lfxo_cfg,
+            dbg_clk,
+            ..clocks
+        })
+    }
+
+    /// Get the High Frequency Clock source which is currently selected (`CMU_HFCLKSTATUS` register)
+    ///
+    /// The frequency of the external oscillators is the one which was declared when the source was selected.
+    pub fn current_hf_source(&self) -> HfClockSource {
+        let cmu = unsafe { Cmu::steal() };
+
+        // [PANIC]: the reset value of the `SELECTED` field is `0x01`, so the field value cannot evaluate to something
+        //          other than the enum
+        match cmu.hfclkstatus().read().selected().variant().unwrap() {
+            SELECTED::Hfrco => HfClockSource::HfRco,
+            SELECTED::Hfxo => HfClockSource::HfXO(self.hf_src_clk),
+            SELECTED::Lfrco => HfClockSource::LfRco,
+            SELECTED::Lfxo => HfClockSource::LfXO(self.hf_src_clk),
+        }
+    }
+
+    /// Select the High Frequency Clock source, and the High Frequency Clock prescaler (`PRESC` field of `CMU_HFPRESC`)
+    ///
+    /// All the [`HfClockPrescaler`] values are valid, so no runtime check is needed for the prescaler.
     pub fn with_hf_clk(self, clk_src: HfClockSource, prescaler: HfClockPrescaler) -> Self {
         let cmu = unsafe { Cmu::steal() };
 
@@ -157,12 +796,7 @@ impl Clocks {
             }
             HfClockSource::LfXO(freq) => {
                 // Enable LF XO
-                cmu.oscencmd().write(|w| w.lfxoen().set_bit());
-
-                // wait for LF XO clock to be stable
-                while cmu.status().read().lfxordy().bit_is_clear() {
-                    nop();
-                }
+                self.enable_lfxo_clock();
 
                 // select to LF XO
                 cmu.hfclksel().write(|w| w.hf().variant(HF::Lfxo));
@@ -214,31 +848,76 @@ impl Clocks {
         cmu.hfpresc()
             .write(|w| unsafe { w.presc().bits(prescaler as u8) });
 
-        Self::calculate_hf_clocks(hf_src_clk_freq)
+        let clocks = Self::calculate_hf_clocks(hf_src_clk_freq);
+
+        // the Debug Clock follows the HF Clock if it is selected as its source
+        let dbg_clk = match cmu.dbgclksel().read().dbg().is_hfclk() {
+            true => self.dbg_clk.map(|_| clocks.hf_bus_clk),
+            false => self.dbg_clk,
+        };
+
+        Self {
+            lfxo_cfg: self.lfxo_cfg,
+            dbg_clk,
+            ..clocks
+        }
     }
 
-    /// TODO:
+    /// Set the HF Peripheral Clock prescaler (`CMU_HFPERPRESC`), and return the clocks with the updated
+    /// [`Clocks::hf_per_clk()`].
+    ///
+    /// The `USART`, `TIMER` and other HF peripherals are clocked by HFPERCLK, so it can be slowed down to save power
+    /// without slowing down the core. Peripherals which were configured from the previous `Clocks` (e.g. an SPI
+    /// baudrate) must be configured again using the returned `Clocks`.
+    pub fn set_hf_per_prescaler(self, presc: HfPerPrescaler) -> Self {
+        let cmu = unsafe { Cmu::steal() };
+
+        cmu.hfperpresc()
+            .write(|w| unsafe { w.presc().bits(presc.divider() - 1) });
+
+        Self {
+            hf_per_clk: self.hf_bus_clk / presc.divider() as u32,
+            ..self
+        }
+    }
+
+    /// Select the Debug Clock source. The resulting frequency is available through [`Clocks::dbg_clk()`].
+    ///
+    /// When AUX HF RCO is selected, it also clocks the flash erase/write logic (MSC).
     pub fn with_dbg_clk(self, clk_src: DbgClockSource) -> Self {
+        let dbg_clk_freq = self.set_dbg_clk(clk_src);
+
+        Self {
+            dbg_clk: Some(dbg_clk_freq),
+            ..self
+        }
+    }
+
+    /// Select the Debug Clock source, and return its frequency
+    fn set_dbg_clk(&self, clk_src: DbgClockSource) -> HertzU32 {
         let cmu = unsafe { Cmu::steal() };
 
         let dbg_clk_freq = match clk_src {
             DbgClockSource::AuxHfRco => {
-                // check if Aux High Frequency RCO is enabled
-                if cmu.status().read().auxhfrcoens().bit_is_clear() {
-                    // Enable HF RCO
-                    cmu.oscencmd().write(|w| w.auxhfrcoen().set_bit());
-                }
-
-                // wait for AUX HF RCO clock to be stable
-                while cmu.status().read().auxhfrcordy().bit_is_clear() {
-                    nop();
-                }
+                self.enable_auxhfrco_clock();
 
-                // select to LF RCO
+                // select to AUX HF RCO
                 cmu.dbgclksel().write(|w| w.dbg().auxhfrco());
 
                 DEFAULT_AUX_HF_RCO_FREQUENCY
             }
+            DbgClockSource::AuxHfRcoBand(band) => {
+                // Load the factory calibration of the band. The AUX HF RCO can be re-tuned while running, but the
+                // frequency may overshoot while switching, so the band is changed before the oscillator is selected.
+                cmu.auxhfrcoctrl().write(|w| unsafe { w.bits(band.calibration()) });
+
+                self.enable_auxhfrco_clock();
+
+                // select to AUX HF RCO
+                cmu.dbgclksel().write(|w| w.dbg().auxhfrco());
+
+                band.frequency()
+            }
             DbgClockSource::HfClk => {
                 // select to HF Clock as the Debug Clock
                 cmu.dbgclksel().write(|w| w.dbg().hfclk());
@@ -248,7 +927,7 @@ impl Clocks {
             }
         };
 
-        Self::calculate_hf_clocks(dbg_clk_freq)
+        dbg_clk_freq
     }
 
     /// TODO:
@@ -345,8 +1024,14 @@ impl Clocks {
         // be enabled when programming a Low Energy (LE) peripheral.
         self.enable_hf_bus_clk_le();
 
+        let hf_clk_le = match clk_src {
+            LfBClockSource::HfClkLe(_) => lfb_clk_freq,
+            _ => self.hf_clk_le,
+        };
+
         Self {
             lfb_clk: Some(lfb_clk_freq),
+            hf_clk_le,
             ..self
         }
     }
@@ -489,25 +1174,51 @@ impl Clocks {
 
         let hf_bus_clk = hf_clk;
 
+        let hf_clk_le = hf_bus_clk / HfDividers::hf_clk_le(&cmu);
+
         Clocks {
+            hf_src_clk,
             hf_per_clk,
             hf_core_clk,
             hf_exp_clk,
             hf_bus_clk,
+            hf_clk_le,
             lfa_clk: None,
             lfb_clk: None,
             lfe_clk: None,
             wdog_clk: None,
             cryo_clk: None,
+            dbg_clk: None,
+            lfxo_cfg: None,
         }
     }
 
+    /// Enable the clock of the given peripheral
+    pub fn enable(&mut self, clk: PeripheralClock) {
+        gates::enable(clk);
+    }
+
+    /// Disable the clock of the given peripheral
+    ///
+    /// # Safety
+    ///
+    /// No driver of the peripheral (e.g. a [`crate::usart::Usart`], a [`crate::timer::Timer`] or its channels, or
+    /// [`crate::gpio::Gpio`] and its pins) may be used until the clock is enabled again with [`Clocks::enable()`].
+    /// Accessing the registers of a peripheral whose clock is disabled has no effect (or may result in a bus fault for
+    /// some peripherals), so a driver would silently ignore its writes, or wait forever for a status flag.
+    pub unsafe fn disable(&mut self, clk: PeripheralClock) {
+        gates::disable(clk);
+    }
+
+    /// Check if the clock of the given peripheral is enabled
+    pub fn is_enabled(&self, clk: PeripheralClock) -> bool {
+        gates::is_enabled(clk)
+    }
+
     /// Set to enable the clock for LE. Interface used for bus access to Low Energy peripherals.
     fn enable_hf_bus_clk_le(&self) {
-        let cmu = unsafe { Cmu::steal() };
-
         // Enable High Frequency Clock LE
-        cmu.hfbusclken0().modify(|_, w| w.le().set_bit());
+        gates::enable(PeripheralClock::Le);
     }
 
     /// Enable Low Frequency XO
@@ -515,6 +1226,11 @@ impl Clocks {
         let cmu = unsafe { Cmu::steal() };
         // Ensure Low Frequency XO is enabled
         if cmu.status().read().lfxoens().bit_is_clear() {
+            if let Some(cfg) = self.lfxo_cfg {
+                // [PANIC]: the LF XO was just checked to be disabled, so this cannot fail
+                self.configure_lfxo(cfg).unwrap();
+            }
+
             cmu.oscencmd().write(|w| w.lfxoen().set_bit());
         }
 
@@ -537,6 +1253,278 @@ impl Clocks {
             nop();
         }
     }
+
+    fn enable_auxhfrco_clock(&self) {
+        let cmu = unsafe { Cmu::steal() };
+
+        // check if Aux High Frequency RCO is enabled
+        if cmu.status().read().auxhfrcoens().bit_is_clear() {
+            // Enable AUX HF RCO
+            cmu.oscencmd().write(|w| w.auxhfrcoen().set_bit());
+        }
+
+        // wait for AUX HF RCO clock to be stable
+        while cmu.status().read().auxhfrcordy().bit_is_clear() {
+            nop();
+        }
+    }
+}
+
+/// Dividers of the HF clocks (the `PRESC` field of the corresponding prescaler register, plus `1`)
+struct HfDividers {
+    hf_clk: u32,
+    hf_core_clk: u32,
+    hf_per_clk: u32,
+    hf_exp_clk: u32,
+    hf_clk_le: u32,
+}
+
+impl HfDividers {
+    fn read() -> Self {
+        let cmu = unsafe { Cmu::steal() };
+
+        Self {
+            hf_clk: cmu.hfpresc().read().presc().bits() as u32 + 1,
+            hf_core_clk: cmu.hfcorepresc().read().presc().bits() as u32 + 1,
+            hf_per_clk: cmu.hfperpresc().read().presc().bits() as u32 + 1,
+            hf_exp_clk: cmu.hfexppresc().read().presc().bits() as u32 + 1,
+            hf_clk_le: Self::hf_clk_le(&cmu),
+        }
+    }
+
+    /// Divider of the HF Clock LE, relative to the HF Bus Clock
+    fn hf_clk_le(cmu: &Cmu) -> u32 {
+        match cmu.hfpresc().read().hfclklepresc().is_div4() {
+            true => 4,
+            false => 2,
+        }
+    }
+}
+
+impl fmt::Debug for Clocks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let div = HfDividers::read();
+
+        writeln!(f, "Clocks")?;
+        writeln!(f, "|- HFSRCCLK: {:?} @ {}", self.current_hf_source(), self.hf_src_clk)?;
+        writeln!(f, "|  `- HFCLK (/{}): {}", div.hf_clk, self.hf_bus_clk)?;
+        writeln!(f, "|     |- HFBUSCLK: {}", self.hf_bus_clk)?;
+        writeln!(f, "|     |  `- HFCLKLE (/{}): {}", div.hf_clk_le, self.hf_clk_le)?;
+        writeln!(f, "|     |- HFCORECLK (/{}): {}", div.hf_core_clk, self.hf_core_clk)?;
+        writeln!(f, "|     |- HFPERCLK (/{}): {}", div.hf_per_clk, self.hf_per_clk)?;
+        writeln!(f, "|     `- HFEXPCLK (/{}): {}", div.hf_exp_clk, self.hf_exp_clk)?;
+        writeln!(f, "|- LFACLK: {:?}", self.lfa_clk)?;
+        writeln!(f, "|- LFBCLK: {:?}", self.lfb_clk)?;
+        writeln!(f, "|- LFECLK: {:?}", self.lfe_clk)?;
+        writeln!(f, "|- WDOGCLK: {:?}", self.wdog_clk)?;
+        writeln!(f, "|- CRYOCLK: {:?}", self.cryo_clk)?;
+        write!(f, "`- DBGCLK: {:?}", self.dbg_clk)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Clocks {
+    fn format(&self, f: defmt::Formatter) {
+        let div = HfDividers::read();
+
+        defmt::write!(
+            f,
+            "Clocks\n\
+             |- HFSRCCLK: {} @ {}\n\
+             |  `- HFCLK (/{}): {}\n\
+             |     |- HFBUSCLK: {}\n\
+             |     |  `- HFCLKLE (/{}): {}\n\
+             |     |- HFCORECLK (/{}): {}\n\
+             |     |- HFPERCLK (/{}): {}\n\
+             |     `- HFEXPCLK (/{}): {}\n\
+             |- LFACLK: {}\n\
+             |- LFBCLK: {}\n\
+             |- LFECLK: {}\n\
+             |- WDOGCLK: {}\n\
+             |- CRYOCLK: {}\n\
+             `- DBGCLK: {}",
+            self.current_hf_source(),
+            self.hf_src_clk,
+            div.hf_clk,
+            self.hf_bus_clk,
+            self.hf_bus_clk,
+            div.hf_clk_le,
+            self.hf_clk_le,
+            div.hf_core_clk,
+            self.hf_core_clk,
+            div.hf_per_clk,
+            self.hf_per_clk,
+            div.hf_exp_clk,
+            self.hf_exp_clk,
+            self.lfa_clk,
+            self.lfb_clk,
+            self.lfe_clk,
+            self.wdog_clk,
+            self.cryo_clk,
+            self.dbg_clk
+        );
+    }
+}
+
+/// Peripheral clocks which can be enabled or disabled individually (`CMU_HFBUSCLKEN0`, `CMU_HFPERCLKEN0` and
+/// `CMU_LFxCLKEN0` registers)
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PeripheralClock {
+    /// Low Energy peripherals bus interface (HFBUSCLKLE)
+    Le,
+    /// Advanced Encryption Standard Accelerator
+    Crypto,
+    /// General purpose Input/Output
+    Gpio,
+    /// Peripheral Reflex System
+    Prs,
+    /// Linked Direct Memory Access Controller
+    Ldma,
+    /// General Purpose CRC
+    Gpcrc,
+    /// Timer 0
+    Timer0,
+    /// Timer 1
+    Timer1,
+    /// Universal Synchronous/Asynchronous Receiver/Transmitter 0
+    Usart0,
+    /// Universal Synchronous/Asynchronous Receiver/Transmitter 1
+    Usart1,
+    /// Analog Comparator 0
+    Acmp0,
+    /// Analog Comparator 1
+    Acmp1,
+    /// Cryo Timer
+    Cryotimer,
+    /// I2C 0
+    I2c0,
+    /// Analog to Digital Converter 0
+    Adc0,
+    /// Current Digital to Analog Converter 0
+    Idac0,
+    /// Low Energy Timer 0 (LFACLK)
+    LeTimer0,
+    /// Low Energy UART 0 (LFBCLK)
+    LeUart0,
+    /// Real Time Counter and Calendar (LFECLK)
+    Rtcc,
+}
+
+impl PeripheralClock {
+    /// Get the `Timer<N>` peripheral clock
+    pub(crate) const fn timer(n: u8) -> Self {
+        match n {
+            0 => Self::Timer0,
+            1 => Self::Timer1,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Get the `Usart<N>` peripheral clock
+    pub(crate) const fn usart(n: u8) -> Self {
+        match n {
+            0 => Self::Usart0,
+            1 => Self::Usart1,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Peripheral clock gates
+pub(crate) mod gates {
+    use super::PeripheralClock;
+    use crate::pac::Cmu;
+    use cortex_m::asm::nop;
+
+    /// Enable the clock of the given peripheral
+    pub(crate) fn enable(clk: PeripheralClock) {
+        set(clk, true);
+    }
+
+    /// Disable the clock of the given peripheral
+    pub(crate) fn disable(clk: PeripheralClock) {
+        set(clk, false);
+    }
+
+    fn set(clk: PeripheralClock, en: bool) {
+        let cmu = cmu();
+
+        match clk {
+            PeripheralClock::Le => cmu.hfbusclken0().modify(|_, w| w.le().bit(en)),
+            PeripheralClock::Crypto => cmu.hfbusclken0().modify(|_, w| w.crypto().bit(en)),
+            PeripheralClock::Gpio => cmu.hfbusclken0().modify(|_, w| w.gpio().bit(en)),
+            PeripheralClock::Prs => cmu.hfbusclken0().modify(|_, w| w.prs().bit(en)),
+            PeripheralClock::Ldma => cmu.hfbusclken0().modify(|_, w| w.ldma().bit(en)),
+            PeripheralClock::Gpcrc => cmu.hfbusclken0().modify(|_, w| w.gpcrc().bit(en)),
+            PeripheralClock::Timer0 => cmu.hfperclken0().modify(|_, w| w.timer0().bit(en)),
+            PeripheralClock::Timer1 => cmu.hfperclken0().modify(|_, w| w.timer1().bit(en)),
+            PeripheralClock::Usart0 => cmu.hfperclken0().modify(|_, w| w.usart0().bit(en)),
+            PeripheralClock::Usart1 => cmu.hfperclken0().modify(|_, w| w.usart1().bit(en)),
+            PeripheralClock::Acmp0 => cmu.hfperclken0().modify(|_, w| w.acmp0().bit(en)),
+            PeripheralClock::Acmp1 => cmu.hfperclken0().modify(|_, w| w.acmp1().bit(en)),
+            PeripheralClock::Cryotimer => cmu.hfperclken0().modify(|_, w| w.cryotimer().bit(en)),
+            PeripheralClock::I2c0 => cmu.hfperclken0().modify(|_, w| w.i2c0().bit(en)),
+            PeripheralClock::Adc0 => cmu.hfperclken0().modify(|_, w| w.adc0().bit(en)),
+            PeripheralClock::Idac0 => cmu.hfperclken0().modify(|_, w| w.idac0().bit(en)),
+            PeripheralClock::LeTimer0 => {
+                cmu.lfaclken0().modify(|_, w| w.letimer0().bit(en));
+
+                // Sync
+                while cmu.syncbusy().read().lfaclken0().bit_is_set() {
+                    nop()
+                }
+            }
+            PeripheralClock::LeUart0 => {
+                cmu.lfbclken0().modify(|_, w| w.leuart0().bit(en));
+
+                // Sync
+                while cmu.syncbusy().read().lfbclken0().bit_is_set() {
+                    nop()
+                }
+            }
+            PeripheralClock::Rtcc => {
+                cmu.lfeclken0().modify(|_, w| w.rtcc().bit(en));
+
+                // Sync
+                while cmu.syncbusy().read().lfeclken0().bit_is_set() {
+                    nop()
+                }
+            }
+        }
+    }
+
+    /// Check if the clock of the given peripheral is enabled
+    pub(crate) fn is_enabled(clk: PeripheralClock) -> bool {
+        let cmu = cmu();
+
+        match clk {
+            PeripheralClock::Le => cmu.hfbusclken0().read().le().bit_is_set(),
+            PeripheralClock::Crypto => cmu.hfbusclken0().read().crypto().bit_is_set(),
+            PeripheralClock::Gpio => cmu.hfbusclken0().read().gpio().bit_is_set(),
+            PeripheralClock::Prs => cmu.hfbusclken0().read().prs().bit_is_set(),
+            PeripheralClock::Ldma => cmu.hfbusclken0().read().ldma().bit_is_set(),
+            PeripheralClock::Gpcrc => cmu.hfbusclken0().read().gpcrc().bit_is_set(),
+            PeripheralClock::Timer0 => cmu.hfperclken0().read().timer0().bit_is_set(),
+            PeripheralClock::Timer1 => cmu.hfperclken0().read().timer1().bit_is_set(),
+            PeripheralClock::Usart0 => cmu.hfperclken0().read().usart0().bit_is_set(),
+            PeripheralClock::Usart1 => cmu.hfperclken0().read().usart1().bit_is_set(),
+            PeripheralClock::Acmp0 => cmu.hfperclken0().read().acmp0().bit_is_set(),
+            PeripheralClock::Acmp1 => cmu.hfperclken0().read().acmp1().bit_is_set(),
+            PeripheralClock::Cryotimer => cmu.hfperclken0().read().cryotimer().bit_is_set(),
+            PeripheralClock::I2c0 => cmu.hfperclken0().read().i2c0().bit_is_set(),
+            PeripheralClock::Adc0 => cmu.hfperclken0().read().adc0().bit_is_set(),
+            PeripheralClock::Idac0 => cmu.hfperclken0().read().idac0().bit_is_set(),
+            PeripheralClock::LeTimer0 => cmu.lfaclken0().read().letimer0().bit_is_set(),
+            PeripheralClock::LeUart0 => cmu.lfbclken0().read().leuart0().bit_is_set(),
+            PeripheralClock::Rtcc => cmu.lfeclken0().read().rtcc().bit_is_set(),
+        }
+    }
+
+    #[inline(always)]
+    fn cmu() -> Cmu {
+        unsafe { Cmu::steal() }
+    }
 }
 
 /// TODO:
@@ -554,6 +1542,9 @@ pub enum HfClockSource {
 }
 
 /// High Frequency Clock divider values
+///
+/// The `PRESC` field of `CMU_HFPRESC` is 5 bits wide, and the HF Clock is divided by `PRESC + 1`, so all the valid
+/// dividers are `Div1` to `Div32`.
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
@@ -624,17 +1615,218 @@ pub enum HfClockPrescaler {
     Div32,
 }
 
-/// TODO:
+/// HF Peripheral Clock divider
+///
+/// The `PRESC` field of `CMU_HFPERPRESC` is 9 bits wide, and the HF Clock is divided by `PRESC + 1`, so the valid
+/// dividers are `1` to `512`.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HfPerPrescaler {
+    divider: u16,
+}
+
+impl HfPerPrescaler {
+    /// Create an HF Peripheral Clock prescaler which divides the HF Clock by `divider` (`1..=512`)
+    pub fn new(divider: u16) -> Result<Self, CmuError> {
+        if !(1..=512).contains(&divider) {
+            return Err(CmuError::InvalidHfPerPrescaler(divider));
+        }
+
+        Ok(Self { divider })
+    }
+
+    /// The HF Clock divider
+    pub fn divider(&self) -> u16 {
+        self.divider
+    }
+}
+
+/// LF XO configuration (`CMU_LFXOCTRL`)
+///
+/// The [`Default`] is the reset configuration of the LF XO.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LfXoConfig {
+    tuning: u8,
+    gain: u8,
+    timeout: LfXoTimeout,
+}
+
+impl LfXoConfig {
+    /// Create an LF XO configuration with the given internal capacitor array `tuning` value (`0..=127`), the startup
+    /// `gain` (`0..=3`), and the startup `timeout`
+    pub fn new(tuning: u8, gain: u8, timeout: LfXoTimeout) -> Result<Self, CmuError> {
+        if tuning > 0x7F || gain > 0x03 {
+            return Err(CmuError::InvalidLfXoConfig);
+        }
+
+        Ok(Self {
+            tuning,
+            gain,
+            timeout,
+        })
+    }
+
+    /// Internal capacitor array tuning value
+    pub fn tuning(&self) -> u8 {
+        self.tuning
+    }
+
+    /// Startup gain
+    pub fn gain(&self) -> u8 {
+        self.gain
+    }
+
+    /// Startup timeout
+    pub fn timeout(&self) -> LfXoTimeout {
+        self.timeout
+    }
+}
+
+impl Default for LfXoConfig {
+    fn default() -> Self {
+        Self {
+            tuning: 0x00,
+            gain: 0x02,
+            timeout: LfXoTimeout::Cycles32K,
+        }
+    }
+}
+
+/// LF XO startup timeout (`TIMEOUT` field of `CMU_LFXOCTRL`), in LF XO cycles
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
+pub enum LfXoTimeout {
+    /// 2 cycles
+    Cycles2,
+    /// 256 cycles
+    Cycles256,
+    /// 1024 cycles
+    Cycles1K,
+    /// 2048 cycles
+    Cycles2K,
+    /// 4096 cycles
+    Cycles4K,
+    /// 8192 cycles
+    Cycles8K,
+    /// 16384 cycles
+    Cycles16K,
+    /// 32768 cycles
+    Cycles32K,
+}
+
+/// Oscillators which can be enabled and disabled through `CMU_OSCENCMD`
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Oscillator {
+    /// High Frequency Rco
+    HfRco,
+    /// High Frequency external oscillator
+    HfXO,
+    /// Auxiliary High Frequency Rco
+    AuxHfRco,
+    /// Low Frequency Rco
+    LfRco,
+    /// Low Frequency external oscillator
+    LfXO,
+}
+
+/// Status of an [`Oscillator`], as reported by `CMU_STATUS`
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OscStatus {
+    /// The oscillator is enabled (`xxxENS` bit)
+    pub enabled: bool,
+    /// The oscillator is enabled and its output is stable (`xxxRDY` bit)
+    pub ready: bool,
+}
+
+/// TODO:
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DbgClockSource {
-    /// High Frequency Rco ()
+    /// Auxiliary High Frequency Rco, as currently tuned (19 MHz at reset)
     AuxHfRco,
+    /// Auxiliary High Frequency Rco, tuned to the given band using its factory calibration (`DEVINFO_AUXHFRCOCALn`)
+    AuxHfRcoBand(AuxHfRcoFreq),
     /// High Frequency Clock (i.e. the prescaled High Frequency Source Clock)
     HfClk,
 }
 
+/// Calibrated frequency bands of the AUX HF RCO
+///
+/// The `1 MHz` and `2 MHz` bands use the `4 MHz` calibration, divided locally (`CMU_AUXHFRCOCTRL.CLKDIV`).
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuxHfRcoFreq {
+    /// 1 MHz
+    MHz1,
+    /// 2 MHz
+    MHz2,
+    /// 4 MHz
+    MHz4,
+    /// 7 MHz
+    MHz7,
+    /// 13 MHz
+    MHz13,
+    /// 16 MHz
+    MHz16,
+    /// 19 MHz (reset value)
+    MHz19,
+    /// 26 MHz
+    MHz26,
+    /// 32 MHz
+    MHz32,
+    /// 38 MHz
+    MHz38,
+}
+
+impl AuxHfRcoFreq {
+    /// Nominal frequency of the band
+    pub fn frequency(&self) -> HertzU32 {
+        match self {
+            AuxHfRcoFreq::MHz1 => HertzU32::MHz(1),
+            AuxHfRcoFreq::MHz2 => HertzU32::MHz(2),
+            AuxHfRcoFreq::MHz4 => HertzU32::MHz(4),
+            AuxHfRcoFreq::MHz7 => HertzU32::MHz(7),
+            AuxHfRcoFreq::MHz13 => HertzU32::MHz(13),
+            AuxHfRcoFreq::MHz16 => HertzU32::MHz(16),
+            AuxHfRcoFreq::MHz19 => HertzU32::MHz(19),
+            AuxHfRcoFreq::MHz26 => HertzU32::MHz(26),
+            AuxHfRcoFreq::MHz32 => HertzU32::MHz(32),
+            AuxHfRcoFreq::MHz38 => HertzU32::MHz(38),
+        }
+    }
+
+    /// Value to be written to `CMU_AUXHFRCOCTRL`, as read from the Device Information page
+    fn calibration(&self) -> u32 {
+        let (cal_idx, clk_div) = match self {
+            AuxHfRcoFreq::MHz1 => (0, Some(2)),
+            AuxHfRcoFreq::MHz2 => (0, Some(1)),
+            AuxHfRcoFreq::MHz4 => (0, None),
+            AuxHfRcoFreq::MHz7 => (3, None),
+            AuxHfRcoFreq::MHz13 => (6, None),
+            AuxHfRcoFreq::MHz16 => (7, None),
+            AuxHfRcoFreq::MHz19 => (8, None),
+            AuxHfRcoFreq::MHz26 => (10, None),
+            AuxHfRcoFreq::MHz32 => (11, None),
+            AuxHfRcoFreq::MHz38 => (12, None),
+        };
+
+        let addr = DEVINFO_BASE + DEVINFO_AUXHFRCOCAL0_OFFSET + 4 * cal_idx;
+
+        // SAFETY: the Device Information page is always mapped and read-only
+        let cal = unsafe { core::ptr::read_volatile(addr as *const u32) };
+
+        match clk_div {
+            // `CLKDIV` field, at bits 25..=26
+            Some(div) => (cal & !(0b11 << 25)) | (div << 25),
+            None => cal,
+        }
+    }
+}
+
 /// Low Frequency clocks sources (used for LFACLK, LFECLK, WDOGCLK, CRYOCLK)
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -0,0 +1,245 @@
+//! Energy Management Unit
+//!
+//! Only the voltage monitors (VMON) are supported for now. Each monitor compares one of the supplies with a threshold,
+//! so that a battery powered application gets an early warning (a brown-out interrupt) while the supply is still high
+//! enough to save its state, long before the brown-out detectors reset the MCU:
+//!
+//! ```rust,no_run
+//! let mut emu = p.emu.into_emu();
+//!
+//! emu.configure_bod(VmonSupply::Avdd, 2_200).unwrap();
+//! emu.enable_bod_interrupt(VmonSupply::Avdd);
+//!
+//! #[interrupt]
+//! fn EMU() {
+//!     if Emu::on_interrupt().avdd {
+//!         // save the application state to flash
+//!     }
+//! }
+//! ```
+//!
+//! The thresholds are the nominal values of the `THRESCOARSE`/`THRESFINE` fields, i.e. they are not corrected with the
+//! calibration of the Device Information page, so they are only accurate to a few tens of millivolts.
+
+use crate::pac::{Emu as EmuPeripheral, Interrupt, NVIC};
+
+/// Lowest voltage monitor threshold, in millivolts
+const VMON_THRESHOLD_MIN_MV: u16 = 1_620;
+
+/// Highest voltage monitor threshold, in millivolts
+const VMON_THRESHOLD_MAX_MV: u16 = 3_400;
+
+/// Threshold which corresponds to `THRESCOARSE = 0` and `THRESFINE = 0`, in millivolts
+const VMON_THRESHOLD_BASE_MV: u16 = 1_200;
+
+/// Threshold step of `THRESCOARSE`, in millivolts
+const VMON_COARSE_STEP_MV: u16 = 200;
+
+/// Threshold step of `THRESFINE`, in millivolts
+const VMON_FINE_STEP_MV: u16 = 20;
+
+/// Extension trait for the EMU PAC peripheral
+pub trait EmuExt {
+    /// Convert the PAC peripheral into the HAL `Emu` driver
+    fn into_emu(self) -> Emu;
+}
+
+impl EmuExt for EmuPeripheral {
+    fn into_emu(self) -> Emu {
+        Emu { _p: () }
+    }
+}
+
+/// Energy Management Unit driver
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Emu {
+    _p: (),
+}
+
+impl Emu {
+    /// Enable the voltage monitor of `supply`, with a falling threshold of `threshold_mv` millivolts (`1620..=3400`,
+    /// in steps of `20 mV`, rounded down).
+    ///
+    /// The `AVDD` monitor has separate rising and falling thresholds, and both are set to `threshold_mv`. The monitor
+    /// only raises an interrupt once it is enabled with [`Emu::enable_bod_interrupt()`].
+    pub fn configure_bod(&mut self, supply: VmonSupply, threshold_mv: u16) -> Result<(), EmuError> {
+        if !(VMON_THRESHOLD_MIN_MV..=VMON_THRESHOLD_MAX_MV).contains(&threshold_mv) {
+            return Err(EmuError::InvalidThreshold(threshold_mv));
+        }
+
+        let emu = unsafe { EmuPeripheral::steal() };
+
+        let above_base = threshold_mv - VMON_THRESHOLD_BASE_MV;
+        let coarse = (above_base / VMON_COARSE_STEP_MV) as u8;
+        let fine = ((above_base % VMON_COARSE_STEP_MV) / VMON_FINE_STEP_MV) as u8;
+
+        match supply {
+            VmonSupply::Avdd => emu.vmonavddctrl().modify(|_, w| unsafe {
+                w.fallthrescoarse().bits(coarse);
+                w.fallthresfine().bits(fine);
+                w.risethrescoarse().bits(coarse);
+                w.risethresfine().bits(fine);
+                w.en().set_bit()
+            }),
+            VmonSupply::AltAvdd => emu.vmonaltavddctrl().modify(|_, w| unsafe {
+                w.threscoarse().bits(coarse);
+                w.thresfine().bits(fine);
+                w.en().set_bit()
+            }),
+            VmonSupply::Dvdd => emu.vmondvddctrl().modify(|_, w| unsafe {
+                w.threscoarse().bits(coarse);
+                w.thresfine().bits(fine);
+                w.en().set_bit()
+            }),
+            VmonSupply::Io0 => emu.vmonio0ctrl().modify(|_, w| unsafe {
+                w.threscoarse().bits(coarse);
+                w.thresfine().bits(fine);
+                w.en().set_bit()
+            }),
+        };
+
+        Ok(())
+    }
+
+    /// Disable the voltage monitor of `supply`, and its brown-out interrupt
+    pub fn disable_bod(&mut self, supply: VmonSupply) {
+        let emu = unsafe { EmuPeripheral::steal() };
+
+        self.disable_bod_interrupt(supply);
+
+        match supply {
+            VmonSupply::Avdd => emu.vmonavddctrl().modify(|_, w| w.en().clear_bit()),
+            VmonSupply::AltAvdd => emu.vmonaltavddctrl().modify(|_, w| w.en().clear_bit()),
+            VmonSupply::Dvdd => emu.vmondvddctrl().modify(|_, w| w.en().clear_bit()),
+            VmonSupply::Io0 => emu.vmonio0ctrl().modify(|_, w| w.en().clear_bit()),
+        };
+    }
+
+    /// Raise the `EMU` interrupt when `supply` falls below the threshold set by [`Emu::configure_bod()`], and unmask
+    /// the interrupt in the NVIC. The interrupt handler must call [`Emu::on_interrupt()`].
+    pub fn enable_bod_interrupt(&mut self, supply: VmonSupply) {
+        let emu = unsafe { EmuPeripheral::steal() };
+
+        // A brown-out which happened before the interrupt was enabled is not reported
+        emu.ifc().write(|w| match supply {
+            VmonSupply::Avdd => w.vmonavddfall().set_bit(),
+            VmonSupply::AltAvdd => w.vmonaltavddfall().set_bit(),
+            VmonSupply::Dvdd => w.vmondvddfall().set_bit(),
+            VmonSupply::Io0 => w.vmonio0fall().set_bit(),
+        });
+
+        critical_section::with(|_cs| {
+            emu.ien().modify(|_, w| match supply {
+                VmonSupply::Avdd => w.vmonavddfall().set_bit(),
+                VmonSupply::AltAvdd => w.vmonaltavddfall().set_bit(),
+                VmonSupply::Dvdd => w.vmondvddfall().set_bit(),
+                VmonSupply::Io0 => w.vmonio0fall().set_bit(),
+            })
+        });
+
+        unsafe { NVIC::unmask(Interrupt::EMU) };
+    }
+
+    /// Stop raising the `EMU` interrupt when `supply` falls below its threshold. The interrupt stays unmasked in the
+    /// NVIC.
+    pub fn disable_bod_interrupt(&mut self, supply: VmonSupply) {
+        let emu = unsafe { EmuPeripheral::steal() };
+
+        critical_section::with(|_cs| {
+            emu.ien().modify(|_, w| match supply {
+                VmonSupply::Avdd => w.vmonavddfall().clear_bit(),
+                VmonSupply::AltAvdd => w.vmonaltavddfall().clear_bit(),
+                VmonSupply::Dvdd => w.vmondvddfall().clear_bit(),
+                VmonSupply::Io0 => w.vmonio0fall().clear_bit(),
+            })
+        });
+    }
+
+    /// Check if `supply` is currently above the threshold of its voltage monitor (`EMU_STATUS`). This is only valid
+    /// once the monitor is enabled and ready, see [`Emu::vmon_ready()`].
+    pub fn is_above_threshold(&self, supply: VmonSupply) -> bool {
+        let status = unsafe { EmuPeripheral::steal() }.status().read();
+
+        match supply {
+            VmonSupply::Avdd => status.vmonavdd().bit_is_set(),
+            VmonSupply::AltAvdd => status.vmonaltavdd().bit_is_set(),
+            VmonSupply::Dvdd => status.vmondvdd().bit_is_set(),
+            VmonSupply::Io0 => status.vmonio0().bit_is_set(),
+        }
+    }
+
+    /// Check if the enabled voltage monitors are ready (`EMU_STATUS.VMONRDY`)
+    pub fn vmon_ready(&self) -> bool {
+        unsafe { EmuPeripheral::steal() }.status().read().vmonrdy().bit_is_set()
+    }
+
+    /// Handle the brown-out interrupts. Must be called from the `EMU` interrupt handler.
+    ///
+    /// The falling flags of the enabled brown-out interrupts are cleared, and returned as [`BrownOutEvents`].
+    pub fn on_interrupt() -> BrownOutEvents {
+        let emu = unsafe { EmuPeripheral::steal() };
+
+        // Only the flags whose interrupt is enabled are handled, the others may be polled by the application
+        let flags = emu.if_().read();
+        let ien = emu.ien().read();
+        let events = BrownOutEvents {
+            avdd: flags.vmonavddfall().bit_is_set() && ien.vmonavddfall().bit_is_set(),
+            alt_avdd: flags.vmonaltavddfall().bit_is_set() && ien.vmonaltavddfall().bit_is_set(),
+            dvdd: flags.vmondvddfall().bit_is_set() && ien.vmondvddfall().bit_is_set(),
+            io0: flags.vmonio0fall().bit_is_set() && ien.vmonio0fall().bit_is_set(),
+        };
+
+        emu.ifc().write(|w| {
+            w.vmonavddfall().bit(events.avdd);
+            w.vmonaltavddfall().bit(events.alt_avdd);
+            w.vmondvddfall().bit(events.dvdd);
+            w.vmonio0fall().bit(events.io0)
+        });
+
+        events
+    }
+}
+
+/// Supplies which have a voltage monitor
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum VmonSupply {
+    /// Analog supply (`AVDD`)
+    Avdd,
+    /// Analog supply (`AVDD`), monitored by the alternative monitor, which only has a falling threshold
+    AltAvdd,
+    /// Digital supply (`DVDD`)
+    Dvdd,
+    /// IO supply of port 0 (`IOVDD0`)
+    Io0,
+}
+
+/// Supplies which fell below the threshold of their voltage monitor, as reported by [`Emu::on_interrupt()`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BrownOutEvents {
+    /// `AVDD` fell below its threshold
+    pub avdd: bool,
+    /// `AVDD` fell below the threshold of the alternative monitor
+    pub alt_avdd: bool,
+    /// `DVDD` fell below its threshold
+    pub dvdd: bool,
+    /// `IOVDD0` fell below its threshold
+    pub io0: bool,
+}
+
+impl BrownOutEvents {
+    /// Check if any supply fell below its threshold
+    pub fn any(&self) -> bool {
+        self.avdd || self.alt_avdd || self.dvdd || self.io0
+    }
+}
+
+/// EMU errors
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EmuError {
+    /// The voltage monitor threshold is outside of the supported range (1620 mV to 3400 mV)
+    InvalidThreshold(u16),
+}
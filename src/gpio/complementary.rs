@@ -0,0 +1,96 @@
+//! Complementary output pins
+//!
+//! A pair of output pins which are driven to opposite levels, e.g. the two inputs of an H-bridge driver or a
+//! bit-banged differential signal. Both pins are updated together: with a single `DOUT` write when they are in the
+//! same port, or as close together as possible otherwise.
+//!
+//! ```rust,no_run
+//! let in1 = gpio.pd13.into_mode::<OutPp>();
+//! let in2 = gpio.pd14.into_mode::<OutPp>();
+//! let mut bridge = ComplementaryPins::new(in1, in2).unwrap();
+//!
+//! // `PD13` high, `PD14` low
+//! bridge.set(PinState::High);
+//!
+//! // `PD13` low, `PD14` high
+//! bridge.set(PinState::Low);
+//!
+//! // both low (brake)
+//! bridge.set_both(PinState::Low);
+//! ```
+//!
+//! This is a software convenience for slow signals. For complementary PWM with dead time, use a timer instead.
+
+use crate::gpio::{
+    pin::{pins, PinInfo},
+    GpioError,
+};
+use embedded_hal::digital::{OutputPin, PinState};
+
+/// Two output pins which are driven to opposite levels
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ComplementaryPins<A, B> {
+    pin_a: A,
+    pin_b: B,
+}
+
+impl<A, B> ComplementaryPins<A, B>
+where
+    A: OutputPin + PinInfo,
+    B: OutputPin + PinInfo,
+{
+    /// Pair the two given output pins. The pins keep their current levels until the first call to
+    /// [`ComplementaryPins::set()`] or [`ComplementaryPins::set_both()`].
+    ///
+    /// Returns [`GpioError::InvalidMode`] if one of the pins (e.g. a [`DynamicPin`](crate::gpio::dynamic::DynamicPin))
+    /// is not in an output mode.
+    pub fn new(pin_a: A, pin_b: B) -> Result<Self, GpioError> {
+        for mode in [pin_a.mode(), pin_b.mode()] {
+            if !mode.writable() {
+                return Err(GpioError::InvalidMode(mode));
+            }
+        }
+
+        Ok(Self { pin_a, pin_b })
+    }
+
+    /// Drive the first pin to `level`, and the second pin to the opposite level
+    pub fn set(&mut self, level: PinState) {
+        self.write(level, !level);
+    }
+
+    /// Drive both pins to the same `level` (e.g. the brake or coast state of an H-bridge)
+    pub fn set_both(&mut self, level: PinState) {
+        self.write(level, level);
+    }
+
+    /// Release the two pins
+    pub fn free(self) -> (A, B) {
+        (self.pin_a, self.pin_b)
+    }
+
+    /// Write both pins. If the pins are in different ports, the pin which is driven low is written first, so that
+    /// both pins are never high at the same time while switching from one state to the other.
+    fn write(&mut self, level_a: PinState, level_b: PinState) {
+        let (port_a, bit_a) = (self.pin_a.port(), 1u16 << self.pin_a.pin() as u8);
+        let (port_b, bit_b) = (self.pin_b.port(), 1u16 << self.pin_b.pin() as u8);
+
+        let masks = |level: PinState, bit: u16| match level {
+            PinState::High => (bit, 0),
+            PinState::Low => (0, bit),
+        };
+        let (set_a, clr_a) = masks(level_a, bit_a);
+        let (set_b, clr_b) = masks(level_b, bit_b);
+
+        if port_a == port_b {
+            pins::write_port_pins(port_a, set_a | set_b, clr_a | clr_b);
+        } else if level_a == PinState::Low {
+            pins::write_port_pins(port_a, set_a, clr_a);
+            pins::write_port_pins(port_b, set_b, clr_b);
+        } else {
+            pins::write_port_pins(port_b, set_b, clr_b);
+            pins::write_port_pins(port_a, set_a, clr_a);
+        }
+    }
+}
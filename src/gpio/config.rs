@@ -0,0 +1,142 @@
+//! Snapshot of the GPIO configuration
+//!
+//! [`Gpio::dump_config()`](crate::gpio::Gpio::dump_config) reads the configuration of all the ports and pins from the
+//! GPIO registers, regardless of where the pin instances currently are, e.g. to find out why a pin doesn't behave as
+//! expected:
+//!
+//! ```rust,no_run
+//! let config = Gpio::dump_config();
+//!
+//! for pin in config.pins.iter().filter(|p| p.mode != PinMode::Disabled) {
+//!     defmt::println!("{}", pin);
+//! }
+//! ```
+//!
+//! The pin mode is decoded from the `MODEx` field and `DOUT`, so [`PinMode::Analog`] (which has the same register
+//! values as [`PinMode::Disabled`]) is reported as [`PinMode::Disabled`].
+
+use crate::gpio::{
+    dynamic::PinMode,
+    pin::{pin_exists, pins, PinId},
+    port::{ports, DriveSlewRate, DriveStrength, PortId},
+};
+
+/// All the ports, in register order
+const PORTS: [PortId; 5] = [PortId::A, PortId::B, PortId::C, PortId::D, PortId::F];
+
+/// Number of pins which are available in the selected package (see [`pin_exists()`])
+pub const PIN_COUNT: usize = pin_count();
+
+const fn pin_count() -> usize {
+    let mut count = 0;
+    let mut p = 0;
+
+    while p < PORTS.len() {
+        let mut pin = 0;
+        while pin < 16 {
+            if pin_exists(PORTS[p], pin) {
+                count += 1;
+            }
+            pin += 1;
+        }
+        p += 1;
+    }
+
+    count
+}
+
+/// Configuration of all the ports and pins
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GpioConfig {
+    /// Port wide configurations, in the order `A`, `B`, `C`, `D`, `F`
+    pub ports: [PortConfig; 5],
+    /// Configuration of each pin which is available in the selected package, sorted by port and pin
+    pub pins: [PinConfig; PIN_COUNT],
+}
+
+/// Port wide configuration (`GPIO_Px_CTRL`)
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PortConfig {
+    /// Port id
+    pub port: PortId,
+    /// Drive strength (not in Alternate Mode)
+    pub drive_strength: DriveStrength,
+    /// Alternate drive strength
+    pub drive_strength_alt: DriveStrength,
+    /// Slew rate (not in Alternate Mode)
+    pub slew_rate: DriveSlewRate,
+    /// Alternate slew rate
+    pub slew_rate_alt: DriveSlewRate,
+    /// Data In is disabled (not in Alternate Mode)
+    pub din_dis: bool,
+    /// Alternate Data In is disabled
+    pub din_dis_alt: bool,
+}
+
+/// Configuration and levels of a pin
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PinConfig {
+    /// Port id
+    pub port: PortId,
+    /// Pin id
+    pub pin: PinId,
+    /// Pin mode, decoded from the `MODEx` field and `DOUT`
+    pub mode: PinMode,
+    /// Output level (`DOUT`), which also selects the pull direction or the filter of some input modes
+    pub dout: bool,
+    /// Input level (`DIN`), which reads as `false` when Data In is disabled for the port
+    pub din: bool,
+    /// Over Voltage Tolerance is enabled
+    pub ovt: bool,
+}
+
+impl GpioConfig {
+    /// Read the configuration of all the ports and pins
+    pub(crate) fn read() -> Self {
+        let mut pin_ids = PORTS
+            .iter()
+            .flat_map(|&port| (0..16).map(move |pin| (port, pin)))
+            .filter(|&(port, pin)| pin_exists(port, pin));
+
+        Self {
+            ports: PORTS.map(PortConfig::read),
+            pins: core::array::from_fn(|_| {
+                // `PIN_COUNT` is the number of items of `pin_ids`
+                let (port, pin) = pin_ids.next().unwrap();
+                PinConfig::read(port, PinId::from_u8_unchecked(pin))
+            }),
+        }
+    }
+}
+
+impl PortConfig {
+    fn read(port: PortId) -> Self {
+        Self {
+            port,
+            drive_strength: ports::drive_strength(port),
+            drive_strength_alt: ports::drive_strength_alt(port),
+            slew_rate: ports::slew_rate(port),
+            slew_rate_alt: ports::slew_rate_alt(port),
+            din_dis: ports::din_dis(port),
+            din_dis_alt: ports::din_dis_alt(port),
+        }
+    }
+}
+
+impl PinConfig {
+    fn read(port: PortId, pin: PinId) -> Self {
+        let dout = pins::dout(port, pin);
+
+        Self {
+            port,
+            pin,
+            mode: PinMode::from_regs(pins::mode_bits(port, pin), dout),
+            dout,
+            din: pins::din(port, pin),
+            ovt: pins::ovt(port, pin),
+        }
+    }
+}
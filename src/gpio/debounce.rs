@@ -0,0 +1,107 @@
+//! Debounced input pins
+//!
+//! Software complement to the hardware glitch filter (see [`Pin::set_filter()`](crate::gpio::pin::Pin::set_filter)),
+//! which only suppresses pulses shorter than a few nanoseconds. Mechanical buttons bounce for milliseconds, so the
+//! input level is only reported once it has been stable for a given debounce interval, measured with a
+//! [`MonoTimer`].
+//!
+//! ```rust,no_run
+//! let mono = p.timer1.into_timer(TimerDivider::Div16).into_mono_timer(&clocks);
+//! let debounce = MicrosDurationU64::millis(20);
+//! let mut btn0 = DebouncedInput::new(gpio.pf6.into_mode::<InFloat>(), &mono, debounce);
+//!
+//! loop {
+//!     if let Some(PinEdge::Falling) = btn0.update() {
+//!         // button pressed
+//!     }
+//! }
+//! ```
+
+use crate::timer::MonoTimer;
+use embedded_hal::digital::InputPin;
+use fugit::MicrosDurationU64;
+
+/// Edge of a debounced input
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PinEdge {
+    /// The input changed from low to high
+    Rising,
+    /// The input changed from high to low
+    Falling,
+}
+
+/// Input pin which reports an edge only after the input level has been stable for the debounce interval
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DebouncedInput<'a, const TN: u8, PIN> {
+    pin: PIN,
+    mono: &'a MonoTimer<TN>,
+    debounce: MicrosDurationU64,
+    /// The last reported (stable) level
+    stable: bool,
+    /// The last sampled level, and the time (in microseconds) at which it was first sampled
+    candidate: (bool, u64),
+}
+
+impl<'a, const TN: u8, PIN> DebouncedInput<'a, TN, PIN>
+where
+    PIN: InputPin,
+{
+    /// Wrap the given input `pin`. The current level of the pin is taken as the initial stable level.
+    ///
+    /// Anything which converts into a microseconds duration can be used as the `debounce` interval (e.g.
+    /// `MicrosDurationU64::millis(20)`, or a `MicrosDurationU32`).
+    pub fn new(mut pin: PIN, mono: &'a MonoTimer<TN>, debounce: impl Into<MicrosDurationU64>) -> Self {
+        // A pin which cannot be read is assumed to be low, until it can be sampled by `update()`
+        let level = pin.is_high().unwrap_or(false);
+
+        Self {
+            pin,
+            mono,
+            debounce: debounce.into(),
+            stable: level,
+            candidate: (level, mono.now()),
+        }
+    }
+
+    /// Sample the input, and return the edge if the input level changed and has been stable for the debounce interval.
+    ///
+    /// This should be called periodically, at least as often as the debounce interval.
+    pub fn update(&mut self) -> Option<PinEdge> {
+        let level = self.pin.is_high().ok()?;
+        let now = self.mono.now();
+
+        if level != self.candidate.0 {
+            // The input bounced (or just changed), restart the debounce interval
+            self.candidate = (level, now);
+            return None;
+        }
+
+        if level != self.stable && MicrosDurationU64::micros(now - self.candidate.1) >= self.debounce {
+            self.stable = level;
+
+            return Some(match level {
+                true => PinEdge::Rising,
+                false => PinEdge::Falling,
+            });
+        }
+
+        None
+    }
+
+    /// The last stable (debounced) level of the input
+    pub fn is_high(&self) -> bool {
+        self.stable
+    }
+
+    /// The last stable (debounced) level of the input
+    pub fn is_low(&self) -> bool {
+        !self.stable
+    }
+
+    /// Release the wrapped pin
+    pub fn free(self) -> PIN {
+        self.pin
+    }
+}
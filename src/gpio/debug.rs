@@ -18,6 +18,12 @@
 //! let pf2 = pins.pf2;
 //! let pf3 = pins.pf3;
 //! ```
+//!
+//! The debug function of the pins can be restored at runtime (e.g. before field-reflashing):
+//!
+//! ```rust,no_run
+//! let debug_pins = pins.into_debug_pins();
+//! ```
 
 use crate::gpio::{
     pin::Pin,
@@ -101,6 +107,41 @@ impl DebugPinsDisabled {
             pf3: Pin::<'F', 3, Disabled>::new().into_mode::<Disabled>(),
         }
     }
+
+    /// Convert the gpio pins back into debug (SWD/JTAG) pins, so that a debugger can be attached again.
+    ///
+    /// The pins are first set to [`Disabled`] mode (regardless of how they were reconfigured in the meantime, e.g.
+    /// with `with_mode`), so that they don't drive the lines against the debugger. The `ROUTEPEN` SWCLK, SWDIO, TDI
+    /// and TDO bits are then set, and Data In Disable is cleared for port `F`.
+    ///
+    /// Be aware that any transfer the application is still performing on these lines is cut off as soon as the debug
+    /// function is enabled, and the debugger may see spurious activity if it is already connected.
+    pub fn into_debug_pins(self) -> DebugPinsEnabled {
+        let gpio = unsafe { crate::pac::Gpio::steal() };
+
+        // Make sure the pins are not driven before handing them over to the debugger
+        let _ = self.pf0.into_mode::<Disabled>();
+        let _ = self.pf1.into_mode::<Disabled>();
+        let _ = self.pf2.into_mode::<Disabled>();
+        let _ = self.pf3.into_mode::<Disabled>();
+
+        // Make sure Data In Disable is clear for port `F`
+        ports::set_din_dis(PortId::F, DataInCtrl::Enabled);
+
+        // Only touch the debug pin enable bits, and leave the SWV enable as it is
+        gpio.routepen().modify(|_, w| {
+            w.swclktckpen()
+                .set_bit()
+                .swdiotmspen()
+                .set_bit()
+                .tdipen()
+                .set_bit()
+                .tdopen()
+                .set_bit()
+        });
+
+        DebugPinsEnabled::new()
+    }
 }
 
 impl TryFrom<DebugPinsEnabled> for DebugPinsDisabled {
@@ -131,6 +172,11 @@ impl TryFrom<DebugPinsEnabled> for DebugPinsDisabled {
     }
 }
 
+/// Check if `pin` of `port` is one of the debug pins (pf0, pf1, pf2, pf3), whether they are currently enabled or not
+pub const fn is_debug_pin(port: PortId, pin: u8) -> bool {
+    matches!(port, PortId::F) && pin < 4
+}
+
 /// Check if debug pins are enabled (pf0, pf1, pf2, pf3)
 pub fn debug_pins_enabled() -> bool {
     let gpio = unsafe { crate::pac::Gpio::steal() };
@@ -3,14 +3,18 @@
 
 use crate::{
     gpio::{
-        pin::{self, mode::MultiMode, pins, PinId, PinInfo},
+        pin::{
+            self,
+            mode::{MultiMode, OutputMode},
+            pins, PinId, PinInfo,
+        },
         port::{self, PortId},
         GpioError,
     },
     Sealed,
 };
 use core::fmt;
-use embedded_hal::digital::{ErrorType, InputPin, OutputPin, StatefulOutputPin};
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, PinState, StatefulOutputPin};
 
 /// Pin which uses no type states for its state
 pub struct DynamicPin {
@@ -66,6 +70,18 @@ impl DynamicPin {
         Self::new(self.port(), self.pin(), MODE::dynamic_mode())
     }
 
+    /// Transition a pin into one of the _Output_ modes, with the output already driven to the given `state`.
+    ///
+    /// The output level is set _before_ the pin mode is changed, so there is no glitch between changing the mode and
+    /// the first call to `set_high()`/`set_low()`.
+    pub fn into_output_with_state<MODE>(self, state: PinState) -> Self
+    where
+        MODE: OutputMode,
+    {
+        pins::set_dout(self.port(), self.pin(), state == PinState::High);
+        self.into_mode::<MODE>()
+    }
+
     /// Temporarily set the mode of a given pin to a new mode while executing the given closure `f`.
     /// Available modes (see also [`crate::gpio#modes`] details):
     ///
@@ -107,21 +123,42 @@ impl DynamicPin {
         self.mode.set_regs(self.port(), self.pin());
         ret
     }
+
+    /// Get the current mode of this pin
+    pub fn mode(&self) -> PinMode {
+        self.mode
+    }
+
+    /// Read the actual level of a pin which is in one of the _Output_ modes, as seen by the pin's input buffer.
+    ///
+    /// Note that this is not necessarily the level which was set with [`OutputPin`] methods (e.g. an open drain pin
+    /// which is set high may be pulled low externally). Use [`StatefulOutputPin`] methods to get the output latch value.
+    pub fn read_output_level(&mut self) -> Result<bool, GpioError> {
+        if !(self.mode.readable_out() || self.mode.readable_out_alt()) {
+            Err(GpioError::InvalidMode(self.mode))
+        } else if !crate::gpio::is_enabled() {
+            Err(GpioError::GpioDisabled)
+        } else if (self.mode.readable_out() && port::ports::din_dis(self.port()))
+            || (self.mode.readable_out_alt() && port::ports::din_dis_alt(self.port()))
+        {
+            Err(GpioError::DataInDisabled)
+        } else {
+            Ok(pins::din(self.port(), self.pin()))
+        }
+    }
 }
 
 /// `InputPin` implementation for trait from `embedded-hal`
-/// Allows treating Outout pins as Input pins, since the uC allows it
-/// TODO: restrict this to input pins?
+///
+/// Only pins in one of the _Input_ modes can be read. Use [`DynamicPin::read_output_level`] to read back the level of
+/// a pin in one of the _Output_ modes.
 impl InputPin for DynamicPin {
     fn is_high(&mut self) -> Result<bool, Self::Error> {
-        if !self.mode.readable() {
+        if !self.mode.readable_input() {
             Err(GpioError::InvalidMode(self.mode))
         } else if !crate::gpio::is_enabled() {
             Err(GpioError::GpioDisabled)
-        } else if ((self.mode.readable_input() || self.mode.readable_out())
-            && port::ports::din_dis(self.port()))
-            || (self.mode.readable_out_alt() && port::ports::din_dis_alt(self.port()))
-        {
+        } else if port::ports::din_dis(self.port()) {
             Err(GpioError::DataInDisabled)
         } else {
             Ok(pins::din(self.port(), self.pin()))
@@ -256,6 +293,39 @@ impl PinMode {
         }
     }
 
+    /// Decode the mode from the raw `MODEx` field of `GPIO_Px_MODEL`/`GPIO_Px_MODEH` and the pin's `DOUT` bit.
+    ///
+    /// [`PinMode::Analog`] uses the same register values as [`PinMode::Disabled`], so it is decoded as the latter.
+    pub(crate) fn from_regs(mode_bits: u8, dout: bool) -> Self {
+        match (mode_bits & 0xF, dout) {
+            // `DISABLED`
+            (0, false) => PinMode::Disabled,
+            (0, true) => PinMode::DisabledPu,
+            // `INPUT`
+            (1, false) => PinMode::InFloat,
+            (1, true) => PinMode::InFilt,
+            // `INPUTPULL`
+            (2, false) => PinMode::InPd,
+            (2, true) => PinMode::InPu,
+            // `INPUTPULLFILTER`
+            (3, false) => PinMode::InPdFilt,
+            (3, true) => PinMode::InPuFilt,
+            (4, _) => PinMode::OutPp,
+            (5, _) => PinMode::OutPpAlt,
+            (6, _) => PinMode::OutOs,
+            (7, _) => PinMode::OutOsPd,
+            (8, _) => PinMode::OutOd,
+            (9, _) => PinMode::OutOdFilt,
+            (10, _) => PinMode::OutOdPu,
+            (11, _) => PinMode::OutOdPuFilt,
+            (12, _) => PinMode::OutOdAlt,
+            (13, _) => PinMode::OutOdFiltAlt,
+            (14, _) => PinMode::OutOdPuAlt,
+            (15, _) => PinMode::OutOdPuFiltAlt,
+            _ => unreachable!(),
+        }
+    }
+
     /// Checks if the mode is **not** Disabled or Analog
     pub(crate) fn readable(&self) -> bool {
         !matches!(
@@ -289,7 +359,7 @@ impl PinMode {
         )
     }
 
-    fn readable_out_alt(&self) -> bool {
+    pub(crate) fn readable_out_alt(&self) -> bool {
         matches!(
             self,
             PinMode::OutPpAlt
@@ -300,7 +370,7 @@ impl PinMode {
         )
     }
 
-    fn writable(&self) -> bool {
+    pub(crate) fn writable(&self) -> bool {
         !matches!(
             self,
             PinMode::Disabled
@@ -315,7 +385,8 @@ impl PinMode {
         )
     }
 
-    const fn name(&self) -> &'static str {
+    /// Get the name of this pin mode
+    pub const fn name(&self) -> &'static str {
         match self {
             PinMode::Disabled => "Disabled",
             PinMode::DisabledPu => "DisabledPu",
@@ -342,6 +413,43 @@ impl PinMode {
     }
 }
 
+impl TryFrom<u8> for PinMode {
+    type Error = GpioError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PinMode::Disabled),
+            1 => Ok(PinMode::DisabledPu),
+            2 => Ok(PinMode::Analog),
+            3 => Ok(PinMode::InFloat),
+            4 => Ok(PinMode::InFilt),
+            5 => Ok(PinMode::InPu),
+            6 => Ok(PinMode::InPuFilt),
+            7 => Ok(PinMode::InPd),
+            8 => Ok(PinMode::InPdFilt),
+            9 => Ok(PinMode::OutPp),
+            10 => Ok(PinMode::OutOs),
+            11 => Ok(PinMode::OutOsPd),
+            12 => Ok(PinMode::OutOd),
+            13 => Ok(PinMode::OutOdFilt),
+            14 => Ok(PinMode::OutOdPu),
+            15 => Ok(PinMode::OutOdPuFilt),
+            16 => Ok(PinMode::OutPpAlt),
+            17 => Ok(PinMode::OutOdAlt),
+            18 => Ok(PinMode::OutOdFiltAlt),
+            19 => Ok(PinMode::OutOdPuAlt),
+            20 => Ok(PinMode::OutOdPuFiltAlt),
+            _ => Err(GpioError::InvalidModeId(value)),
+        }
+    }
+}
+
+impl From<PinMode> for u8 {
+    fn from(value: PinMode) -> Self {
+        value as u8
+    }
+}
+
 impl PinInfo for DynamicPin {
     fn port(&self) -> PortId {
         PortId::from_u8_unchecked(self.port_pin >> 4)
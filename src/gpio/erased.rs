@@ -17,14 +17,22 @@
 //! // Erased pins with the same mode can be aggregated
 //! let pin_array = [pb11, pb12, pb13, pb14];
 //! ```
+//!
+//! Groups of pins (e.g. bus-like pins) can be erased with the [`erased_pins!`](crate::erased_pins) macro, and then
+//! transitioned to the same mode with [`configure_all`]
+//!
+//! ```rust,no_run
+//! let pins: [ErasedPin<Disabled>; 4] = erased_pins![gpio.pb11, gpio.pb12, gpio.pb13, gpio.pb14];
+//! let pin_array: [ErasedPin<InPu>; 4] = configure_all(pins);
+//! ```
 
-use embedded_hal::digital::{ErrorType, InputPin, OutputPin, StatefulOutputPin};
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, PinState, StatefulOutputPin};
 
 use crate::{
     gpio::{
         pin::{
             mode::{self, InputMode, MultiMode, OutputMode},
-            pins, PinId, PinInfo,
+            pins, PinId, PinInfo, Pull,
         },
         port::{self, PortId},
         GpioError,
@@ -93,6 +101,82 @@ where
         ErasedPin::new(self.port(), self.pin())
     }
 
+    /// Transition a pin into one of the _Output_ modes, with the output already driven to the given `state`.
+    ///
+    /// The output level is set _before_ the pin mode is changed, so there is no glitch between changing the mode and
+    /// the first call to `set_high()`/`set_low()`.
+    pub fn into_output_with_state<NMODE>(self, state: PinState) -> ErasedPin<NMODE>
+    where
+        NMODE: OutputMode,
+        ErasedPin<NMODE>: Sealed,
+    {
+        pins::set_dout(self.port(), self.pin(), state == PinState::High);
+        self.into_mode()
+    }
+
+    /// Enable or disable the glitch suppression filter of this input pin, without changing its pull configuration.
+    ///
+    /// See [`Pin::set_filter()`](crate::gpio::pin::Pin::set_filter) for details.
+    pub fn set_filter(&mut self, enabled: bool)
+    where
+        MODE: InputMode,
+    {
+        pins::set_filter(self.port(), self.pin(), enabled);
+    }
+
+    /// Change the pull of this input pin, keeping its filter setting.
+    ///
+    /// See [`Pin::set_pull()`](crate::gpio::pin::Pin::set_pull) for details.
+    pub fn set_pull(&mut self, pull: Pull)
+    where
+        MODE: InputMode,
+    {
+        pins::set_pull(self.port(), self.pin(), pull);
+    }
+
+    /// Drive several output pins at once. The updates are grouped by port, and each port is written only once, so all
+    /// the pins of the same port change state at the same time (e.g. the data lines of a parallel bus).
+    ///
+    /// The state of each pin can be given either as a [`PinState`] or as a `bool` (`true` is high). If a pin appears
+    /// more than once, the last update wins.
+    ///
+    /// ```rust,no_run
+    /// let mut leds: [ErasedPin<OutPp>; 3] = configure_all(erased_pins![gpio.pd13, gpio.pd14, gpio.pd15]);
+    /// ErasedPin::write_all(leds.iter_mut().zip([true, false, true]));
+    /// ```
+    pub fn write_all<'a, S>(updates: impl IntoIterator<Item = (&'a mut ErasedPin<MODE>, S)>)
+    where
+        MODE: OutputMode + 'a,
+        S: Into<PinState>,
+    {
+        // `(set_mask, clr_mask)` of each port, indexed by the `PortId` value
+        let mut masks = [(0u16, 0u16); PortId::F as usize + 1];
+
+        for (pin, state) in updates {
+            let (set_mask, clr_mask) = &mut masks[pin.port() as usize];
+            let bit = 1u16 << pin.pin() as u8;
+
+            match state.into() {
+                PinState::High => {
+                    *set_mask |= bit;
+                    *clr_mask &= !bit;
+                }
+                PinState::Low => {
+                    *clr_mask |= bit;
+                    *set_mask &= !bit;
+                }
+            }
+        }
+
+        for port in [PortId::A, PortId::B, PortId::C, PortId::D, PortId::F] {
+            let (set_mask, clr_mask) = masks[port as usize];
+
+            if (set_mask | clr_mask) != 0 {
+                pins::write_port_pins(port, set_mask, clr_mask);
+            }
+        }
+    }
+
     /// Temporarily set the mode of a given pin to a new mode while executing the given closure `f`.
     /// Available modes (see also [`crate::gpio#modes`] details):
     ///
@@ -137,6 +221,33 @@ where
     }
 }
 
+/// Transition all the pins in the given array from one mode to another, in the order in which they appear in the array.
+///
+/// See [`ErasedPin::into_mode`] for the available modes.
+pub fn configure_all<MODE, NMODE, const N: usize>(pins: [ErasedPin<MODE>; N]) -> [ErasedPin<NMODE>; N]
+where
+    MODE: MultiMode + Sealed,
+    ErasedPin<MODE>: Sealed,
+    NMODE: MultiMode + Sealed,
+    ErasedPin<NMODE>: Sealed,
+{
+    pins.map(|pin| pin.into_mode::<NMODE>())
+}
+
+/// Convert each of the given pins into an [`ErasedPin`], and aggregate them into an array.
+///
+/// All the given pins must be in the same mode.
+///
+/// ```rust,no_run
+/// let pins: [ErasedPin<Disabled>; 2] = erased_pins![gpio.pb11, gpio.pb12];
+/// ```
+#[macro_export]
+macro_rules! erased_pins {
+    ($($pin:expr),+ $(,)?) => {
+        [$($pin.into_erased_pin()),+]
+    };
+}
+
 impl<MODE> PinInfo for ErasedPin<MODE>
 where
     MODE: MultiMode,
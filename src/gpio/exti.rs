@@ -227,6 +227,27 @@ impl<PIN, const EN: u8> ExtiBoundPin<PIN, EN> {
         &mut self.exti_ctrl
     }
 
+    /// Call `handler` on each `edge` of the pin, from the `GPIO_EVEN`/`GPIO_ODD` interrupt, instead of the handler
+    /// given when the pin was bound. Any pending interrupt flag is cleared, then the interrupt is enabled.
+    ///
+    /// ```rust,no_run
+    /// btn0.on_edge(ExtiEdge::Falling, |exti| {
+    ///     // ...
+    /// });
+    /// ```
+    ///
+    /// The handler runs in interrupt context, so it should be short, and share its state through a critical section
+    /// (e.g. a `Mutex<RefCell<_>>`).
+    pub fn on_edge(&mut self, edge: ExtiEdge, handler: ExtiHandler) {
+        critical_section::with(|cs| {
+            self.exti_ctrl.disable();
+            set_handler(cs, self.exti_ctrl.id(), handler);
+            self.exti_ctrl.edge_select(edge);
+            self.exti_ctrl.clear();
+            self.exti_ctrl.enable();
+        });
+    }
+
     /// Return the PIN and `ExtiCtrl` used to construct this `ExtiBoundPin`
     pub fn release(self) -> (PIN, ExtiCtrl<EN>) {
         // cleanup
@@ -88,6 +88,20 @@
 //! | **[`OutOdPuAlt`]**        | Alternate Output open drain pull-up                         |
 //! | **[`OutOdPuFiltAlt`]**    | Alternate Output open drain pull-up with filter             |
 //!
+//! The input buffer is always a Schmitt trigger, and there is no hardware mode which bypasses it. The input modes only
+//! differ by their pull and their glitch suppression filter (the `*Filt` modes): the filter suppresses pulses of a few
+//! nanoseconds but delays the input, so [`InFloat`] (or [`InPu`]/[`InPd`]) is the fast path for high-speed inputs.
+//! Over Voltage Tolerance is enabled for all the input modes (see [`Pin::set_over_voltage_tolerant()`]).
+//!
+//! All the modes above can transition directly into any other mode, so a pin doesn't need to go back to [`Disabled`]
+//! first (the same applies to [`ErasedPin`](erased::ErasedPin) and [`DynamicPin`](dynamic::DynamicPin)):
+//!
+//! ```rust,no_run
+//! let led = gpio.pf4.into_mode::<OutPp>();
+//! let btn = led.into_mode::<InPu>();
+//! let led = btn.into_mode::<OutOdAlt>();
+//! ```
+//!
 //! ```rust,no_run
 //! // The return type of the closure can be omitted with `_` when supplying the generic type parameters
 //! let state = led0.with_mode::<InPuFilt, _>(|input_pin| input_pin.is_high().unwrap());
@@ -96,13 +110,34 @@
 //! let state: bool = led0.with_mode::<InPuFilt, bool>(|input_pin| input_pin.is_high().unwrap());
 //! ```
 //!
+//! # Wake up from EM4
+//!
+//! The pins keep their state while in EM4 (e.g. to keep an external device powered down), and it stays latched after
+//! the wake up, until it is released with [`Gpio::em4_unlatch()`]. Until then, the pins do not respond to the GPIO
+//! registers, which are reset by [`Gpio::new()`] like after any other reset.
+//!
+//! ```rust,no_run
+//! let mut gpio = Gpio::new(p.gpio);
+//!
+//! // Configure the pins as they were before entering EM4, then release them
+//! let led = gpio.pf4.into_mode::<OutPp>();
+//! Gpio::em4_unlatch();
+//! ```
+//!
+//! With the `em4_auto_unlatch` feature flag, [`Gpio::new()`] releases the pins itself after an EM4 wake up (as reported
+//! by [`ResetCause`](crate::rmu::ResetCause)), so they are all [`Disabled`] from then on. The wake up pins (see
+//! [`ExtiCtrl::exti_enable_em4wu()`](exti::ExtiCtrl::exti_enable_em4wu)) are also reset by [`Gpio::new()`], so they
+//! must be enabled again before the next EM4 entry.
+//!
 
 #[cfg(feature = "use_debug_pins")]
 pub use crate::gpio::debug::DebugPinsEnabled;
+use crate::cmu::{gates, PeripheralClock};
 use crate::gpio::{
+    config::GpioConfig,
     dynamic::PinMode,
     exti::{ExtiCtrl, ExtiId},
-    pin::PinId,
+    pin::{pins, PinId, PinInfo},
     port::PortId,
 };
 pub use crate::gpio::{
@@ -112,12 +147,15 @@ pub use crate::gpio::{
             OutOdAlt, OutOdFilt, OutOdFiltAlt, OutOdPu, OutOdPuAlt, OutOdPuFilt, OutOdPuFiltAlt,
             OutOs, OutOsPd, OutPp, OutPpAlt,
         },
-        Pin,
+        Pin, Pull,
     },
     port::Port,
 };
 use embedded_hal::digital::{self, ErrorKind};
 
+pub mod complementary;
+pub mod config;
+pub mod debounce;
 pub mod debug;
 pub mod dynamic;
 #[cfg(feature = "efemb")]
@@ -327,9 +365,69 @@ impl Gpio {
         gpio.reset();
         gpio.enable_clock();
 
+        #[cfg(feature = "em4_auto_unlatch")]
+        if crate::rmu::ResetCause::read().em4_wakeup {
+            Self::em4_unlatch();
+        }
+
         gpio
     }
 
+    /// Release the pins which are latched since the last EM4 wake up (`EMU_CMD.EM4UNLATCH`), so that they follow
+    /// their GPIO configuration again. This has no effect if the device did not wake up from EM4.
+    ///
+    /// The pins are usually moved out of the [`Gpio`] struct by the time they are configured, so this does not take
+    /// `self`.
+    ///
+    /// See the [module level documentation](crate::gpio#wake-up-from-em4).
+    pub fn em4_unlatch() {
+        let emu = unsafe { crate::pac::Emu::steal() };
+
+        emu.cmd().write(|w| w.em4unlatch().set_bit());
+    }
+
+    /// Put all the pins in the lowest leakage state before entering a low energy mode (e.g. EM2 or EM4), except the
+    /// pins in `exclude` (e.g. the wake up pins, or an output which keeps an external device powered down).
+    ///
+    /// The pins are [`Disabled`], without pull-up: this is the reset state of the pins, where both the input and the
+    /// output are disconnected (the `DISABLED` mode has no pull-down). The debug pins (`PF0`-`PF3`) are also kept while
+    /// they are enabled (see [`debug`]).
+    ///
+    /// The pins are usually moved out of the [`Gpio`] struct by the time they are configured, so this does not take
+    /// `self`. The pin instances which are not excluded keep their type, even though their mode has changed, so they
+    /// must be configured again (e.g. with [`Pin::into_mode()`]) before they are used after the wake up.
+    ///
+    /// ```rust,no_run
+    /// let button = gpio.pf7.into_mode::<InPuFilt>();
+    /// // ...
+    ///
+    /// // Keep the button as wake up source
+    /// Gpio::disable_all(&[&button]);
+    /// ```
+    pub fn disable_all(exclude: &[&dyn PinInfo]) {
+        for port in [PortId::A, PortId::B, PortId::C, PortId::D, PortId::F] {
+            let mut keep = exclude
+                .iter()
+                .filter(|p| p.port() == port)
+                .fold(0u16, |acc, p| acc | (1 << p.pin() as u8));
+
+            if port == PortId::F && debug::debug_pins_enabled() {
+                keep |= 0x000F;
+            }
+
+            pins::disable_port_pins(port, !keep);
+        }
+    }
+
+    /// Read the configuration of all the ports, and of all the pins which are available in the selected package, e.g.
+    /// to log the full pin state while debugging (see [`config`]).
+    ///
+    /// The pins are usually moved out of the [`Gpio`] struct by the time they are configured, so this does not take
+    /// `self`.
+    pub fn dump_config() -> GpioConfig {
+        GpioConfig::read()
+    }
+
     /// Reset the GPIO to a known state
     fn reset(&mut self) {
         self.port_a.reset();
@@ -356,25 +454,20 @@ impl Gpio {
 
     /// Enable clock for GPIO peripheral
     fn enable_clock(&mut self) {
-        let cmu = unsafe { crate::pac::Cmu::steal() };
-
         // Enable GPIO clock
-        cmu.hfbusclken0().modify(|_, w| w.gpio().set_bit());
+        gates::enable(PeripheralClock::Gpio);
     }
 
     /// Disable clock for GPIO peripheral
     fn disable_clock(&mut self) {
-        let cmu = unsafe { crate::pac::Cmu::steal() };
-
         // Disable GPIO clock
-        cmu.hfbusclken0().modify(|_, w| w.gpio().clear_bit());
+        gates::disable(PeripheralClock::Gpio);
     }
 }
 
 /// Check if the GPIO peripheral's clock is enabled
 pub(crate) fn is_enabled() -> bool {
-    let cmu = unsafe { crate::pac::Cmu::steal() };
-    cmu.hfbusclken0().read().gpio().bit_is_set()
+    gates::is_enabled(PeripheralClock::Gpio)
 }
 
 /// Gpio module errors
@@ -384,12 +477,19 @@ pub enum GpioError {
     /// GPIO peripheral is disabled
     GpioDisabled,
 
-    /// Pin level could not be read vecause Data In Disable is enablet for entire port
+    /// Pin level could not be read because Data In Disable is enabled for entire port
+    ///
+    /// Data In can be re-enabled with the `set_din_dis()` (or `set_output_only()`) method of the pin's [`Port`]. Note
+    /// that for port `F` this is also the case when Data In was disabled externally (e.g. by a bootloader), regardless
+    /// of the `use_debug_pins` crate feature.
     DataInDisabled,
 
     /// Dynamic Pin mode does not support the operation requested
     InvalidMode(PinMode),
 
+    /// Failed to convert a literal representation of a pin mode to a [`dynamic::PinMode`]
+    InvalidModeId(u8),
+
     /// Conversion of the given u8 to a [`port::DriveSlewRate`] failed
     InvalidSlewRate(u8),
 
@@ -425,6 +525,7 @@ impl embedded_hal::digital::Error for GpioError {
             GpioError::GpioDisabled => ErrorKind::Other,
             GpioError::DataInDisabled => ErrorKind::Other,
             GpioError::InvalidMode(_) => ErrorKind::Other,
+            GpioError::InvalidModeId(_) => ErrorKind::Other,
             GpioError::InvalidSlewRate(_) => ErrorKind::Other,
             GpioError::DebugPinsEnabled => ErrorKind::Other,
             GpioError::InvalidPortId(_) => ErrorKind::Other,
@@ -12,7 +12,7 @@ use crate::{
     Sealed,
 };
 use core::{fmt, marker::PhantomData};
-use embedded_hal::digital::{ErrorType, InputPin, OutputPin, StatefulOutputPin};
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, PinState, StatefulOutputPin};
 
 /// Generic pin type
 ///
@@ -149,6 +149,111 @@ where
         ret
     }
 
+    /// Transition a pin into one of the _Output_ modes, with the output already driven to the given `state`.
+    ///
+    /// The output level is set _before_ the pin mode is changed, so there is no glitch between changing the mode and
+    /// the first call to `set_high()`/`set_low()` (e.g. useful for active-low reset lines).
+    ///
+    /// ```rust,no_run
+    ///     let mut rst = gpio.pd12.into_output_with_state::<OutPp>(PinState::High);
+    /// ```
+    pub fn into_output_with_state<NMODE>(self, state: PinState) -> Pin<P, N, NMODE>
+    where
+        NMODE: OutputMode,
+        Pin<P, N, NMODE>: Sealed,
+    {
+        pins::set_dout(self.port(), self.pin(), state == PinState::High);
+        self.into_mode()
+    }
+
+    /// Enable or disable the glitch suppression filter of this input pin, without changing its pull configuration and
+    /// without cycling through [`Pin::into_mode()`].
+    ///
+    /// There is no port-wide filter control in `GPIO_Px_CTRL`: the filter is selected per pin, either by `DOUT` (for
+    /// [`InFloat`](`mode::InFloat`)/[`InFilt`](`mode::InFilt`)), or by the `INPUTPULL`/`INPUTPULLFILTER` mode (for
+    /// the pull-up and pull-down input modes, where `DOUT` selects the pull direction).
+    ///
+    /// Note: the type state of the pin is not changed, so the filter setting of the type is restored by
+    /// [`Pin::into_mode()`] and at the end of [`Pin::with_mode()`].
+    pub fn set_filter(&mut self, enabled: bool)
+    where
+        MODE: InputMode,
+    {
+        pins::set_filter(self.port(), self.pin(), enabled);
+    }
+
+    /// Change the pull of this input pin, keeping its filter setting and without cycling through [`Pin::into_mode()`].
+    ///
+    /// The pull direction is selected by `DOUT`, and `MODE` is only written when switching between a floating and a
+    /// pulled input (i.e. between the `INPUT` and `INPUTPULL`/`INPUTPULLFILTER` modes).
+    ///
+    /// Note: like [`Pin::set_filter()`], the type state of the pin is not changed, so the pull of the type is restored
+    /// by [`Pin::into_mode()`] and at the end of [`Pin::with_mode()`].
+    pub fn set_pull(&mut self, pull: Pull)
+    where
+        MODE: InputMode,
+    {
+        pins::set_pull(self.port(), self.pin(), pull);
+    }
+
+    /// Enable or disable the Over Voltage Tolerance of this pin (`GPIO_Px_OVTDIS`), without changing its mode.
+    ///
+    /// OVT allows the pin to be driven above the IO supply voltage (e.g. by a 5 V signal), and is enabled by every
+    /// mode except [`Analog`](`mode::Analog`): OVT must be disabled on pins used for analog functions, and it is
+    /// enabled again by the next mode change (e.g. [`Pin::into_mode()`], or at the end of [`Pin::with_mode()`]).
+    pub fn set_over_voltage_tolerant(&mut self, enabled: bool) {
+        pins::set_ovt(self.port(), self.pin(), enabled);
+    }
+
+    /// Check if the Over Voltage Tolerance of this pin is enabled
+    pub fn is_over_voltage_tolerant(&self) -> bool {
+        pins::ovt(self.port(), self.pin())
+    }
+
+    /// Read the output latch of this pin (`GPIO_Px_DOUT`), i.e. the level the pin was last commanded to.
+    ///
+    /// This is what [`StatefulOutputPin::is_set_high()`] returns, without the check that the GPIO clock is enabled.
+    /// The level actually seen on the pin is returned by [`Pin::pin_level()`], and the two can disagree: e.g. an
+    /// open-drain pin which is set high reads low while another device pulls the line low, and a heavily loaded
+    /// push-pull pin may not reach the commanded level.
+    pub fn output_latch(&mut self) -> bool
+    where
+        MODE: OutputMode,
+    {
+        pins::dout(self.port(), self.pin())
+    }
+
+    /// Read the level actually seen on this output pin (`GPIO_Px_DIN`), which may differ from the commanded level
+    /// (see [`Pin::output_latch()`]).
+    ///
+    /// Like [`InputPin::is_high()`], this fails if Data In is disabled for the port, since `DIN` then always reads
+    /// low. The _Alternate Output_ modes use the Alternate Data In Disable setting (`GPIO_Px_CTRL.DINDISALT`), the
+    /// other output modes use `GPIO_Px_CTRL.DINDIS`.
+    pub fn pin_level(&mut self) -> Result<bool, GpioError>
+    where
+        MODE: OutputMode,
+    {
+        let mode = MODE::dynamic_mode();
+
+        if !crate::gpio::is_enabled() {
+            Err(GpioError::GpioDisabled)
+        } else if (mode.readable_out() && port::ports::din_dis(self.port()))
+            || (mode.readable_out_alt() && port::ports::din_dis_alt(self.port()))
+        {
+            Err(GpioError::DataInDisabled)
+        } else {
+            Ok(pins::din(self.port(), self.pin()))
+        }
+    }
+
+    /// Get the mode of this pin as a runtime [`PinMode`] value (e.g. for logging, or for runtime pin registries),
+    /// without converting it into a [`DynamicPin`].
+    ///
+    /// This is the same as [`PinInfo::mode()`], without the need to import the trait.
+    pub fn current_mode(&self) -> PinMode {
+        MODE::dynamic_mode()
+    }
+
     /// Convert this pin into an erased pin, where the Port and Pin are not stored as type states
     pub fn into_erased_pin(self) -> ErasedPin<MODE> {
         ErasedPin::new(self.port(), self.pin())
@@ -299,10 +404,16 @@ pub(crate) mod mode {
     #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct DisabledPu;
     /// Input floating mode (type state)
+    ///
+    /// The input buffer is enabled without pull and without the glitch suppression filter, so this is the input mode
+    /// with the lowest propagation delay, and the one to use for fast signals.
     #[derive(Debug)]
     #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct InFloat;
     /// Input with filter mode (type state)
+    ///
+    /// Same as [`InFloat`], with the glitch suppression filter enabled: short pulses are suppressed, at the cost of
+    /// a longer propagation delay.
     #[derive(Debug)]
     #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct InFilt;
@@ -730,6 +841,36 @@ pub(crate) mod mode {
     }
 }
 
+/// Pull configuration of an input pin
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Pull {
+    /// No pull, the input is floating
+    Floating,
+    /// Pull-up
+    Up,
+    /// Pull-down
+    Down,
+}
+
+/// Check if `pin` of `port` is available in the selected package (see the `qfn32` and `qfn48` feature flags), e.g. to
+/// validate a pin map built at runtime. The debug pins (`PF0`-`PF3`) are available in all the packages.
+pub const fn pin_exists(port: PortId, pin: u8) -> bool {
+    const QFN32: bool = cfg!(any(feature = "qfn32", feature = "qfn48"));
+    const QFN48: bool = cfg!(feature = "qfn48");
+
+    // Pins which are bonded out in each package, one bit per pin
+    let mask: u16 = match port {
+        PortId::A => 0x0003 | (if QFN48 { 0x003C } else { 0 }),
+        PortId::B => 0xF800,
+        PortId::C => 0x0C00 | (if QFN32 { 0x0380 } else { 0 }) | (if QFN48 { 0x0040 } else { 0 }),
+        PortId::D => 0xFE00,
+        PortId::F => 0x000F | (if QFN32 { 0x0010 } else { 0 }) | (if QFN48 { 0x00E0 } else { 0 }),
+    };
+
+    pin < 16 && (mask & (1 << pin)) != 0
+}
+
 /// Port Pin ID
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 #[repr(u8)]
@@ -832,7 +973,7 @@ pub(crate) mod pins {
     use efm32pg1b_pac::gpio::port_a::model::MODE0;
 
     use crate::gpio::{
-        pin::PinId,
+        pin::{PinId, Pull},
         port::{ports, PortId},
     };
 
@@ -858,6 +999,76 @@ pub(crate) mod pins {
         }
     }
 
+    /// Get the raw Mode bits for a given pin `N` in port `P`
+    #[inline(always)]
+    pub(crate) fn mode_bits(port: PortId, pin: PinId) -> u8 {
+        const REG_MODE_BITS: u8 = 4;
+        const REG_MODE_MASK: u32 = 0xF;
+        const REG_MODES_PER_REGISTER: u8 = u32::BITS as u8 / REG_MODE_BITS;
+        let pin = pin as u8;
+        let offset = (pin % REG_MODES_PER_REGISTER) * REG_MODE_BITS;
+
+        let bits = if pin < PinId::Pin8 as u8 {
+            ports::get(port).model().read().bits()
+        } else {
+            ports::get(port).modeh().read().bits()
+        };
+
+        ((bits >> offset) & REG_MODE_MASK) as u8
+    }
+
+    /// Enable or disable the glitch suppression filter for a given input `pin` in `port`, keeping its pull
+    /// configuration.
+    ///
+    /// The filter is controlled by `DOUT` for the `INPUT` mode, and by the mode itself for the `INPUTPULL` and
+    /// `INPUTPULLFILTER` modes (where `DOUT` selects the pull direction).
+    pub(crate) fn set_filter(port: PortId, pin: PinId, enabled: bool) {
+        let mode = mode_bits(port, pin);
+
+        if mode == MODE0::Input as u8 {
+            set_dout(port, pin, enabled);
+        } else if mode == MODE0::Inputpull as u8 || mode == MODE0::Inputpullfilter as u8 {
+            match enabled {
+                true => mode_set(port, pin, MODE0::Inputpullfilter),
+                false => mode_set(port, pin, MODE0::Inputpull),
+            }
+        }
+    }
+
+    /// Set the `pull` of a given input `pin` in `port`, keeping its filter configuration.
+    ///
+    /// The `INPUT` mode uses `DOUT` to enable the filter, while the `INPUTPULL` and `INPUTPULLFILTER` modes use it to
+    /// select the pull direction, so `DOUT` is written first and `MODE` only when it changes.
+    pub(crate) fn set_pull(port: PortId, pin: PinId, pull: Pull) {
+        let mode = mode_bits(port, pin);
+        let filter = if mode == MODE0::Input as u8 {
+            dout(port, pin)
+        } else if mode == MODE0::Inputpull as u8 {
+            false
+        } else if mode == MODE0::Inputpullfilter as u8 {
+            true
+        } else {
+            // not an input pin
+            return;
+        };
+
+        let new_mode = match (pull, filter) {
+            (Pull::Floating, _) => MODE0::Input,
+            (_, false) => MODE0::Inputpull,
+            (_, true) => MODE0::Inputpullfilter,
+        };
+
+        match pull {
+            Pull::Floating => set_dout(port, pin, filter),
+            Pull::Up => set_dout(port, pin, true),
+            Pull::Down => set_dout(port, pin, false),
+        }
+
+        if new_mode as u8 != mode {
+            mode_set(port, pin, new_mode);
+        }
+    }
+
     /// Get the Data Out for a given `pin` in `port`
     #[inline(always)]
     pub(crate) fn dout(port: PortId, pin: PinId) -> bool {
@@ -873,6 +1084,43 @@ pub(crate) mod pins {
         });
     }
 
+    /// Drive the `set_mask` pins of `port` high and the `clr_mask` pins low, with a single write to `DOUT`.
+    ///
+    /// The EFM32PG1B has no `DOUTSET`/`DOUTCLR` registers, so `DOUT` is read, modified and written back in a critical
+    /// section. Pins which are in both masks are driven high.
+    #[inline(always)]
+    pub(crate) fn write_port_pins(port: PortId, set_mask: u16, clr_mask: u16) {
+        critical_section::with(|_| {
+            ports::get(port)
+                .dout()
+                .modify(|r, w| unsafe { w.pins_dout().bits((r.pins_dout().bits() & !clr_mask) | set_mask) });
+        });
+    }
+
+    /// Put the `mask` pins of `port` in the `DISABLED` mode, with `DOUT` cleared (i.e. without pull-up), with a single
+    /// write to each of `MODEL`, `MODEH` and `DOUT`
+    pub(crate) fn disable_port_pins(port: PortId, mask: u16) {
+        // Each pin has a 4 bit mode, so each bit of `mask` is expanded to a nibble
+        let mut mask_l = 0u32;
+        let mut mask_h = 0u32;
+        for i in 0..8 {
+            if mask & (1 << i) != 0 {
+                mask_l |= 0xF << (i * 4);
+            }
+            if mask & (1 << (i + 8)) != 0 {
+                mask_h |= 0xF << (i * 4);
+            }
+        }
+
+        let p = ports::get(port);
+        critical_section::with(|_| {
+            p.model().modify(|r, w| unsafe { w.bits(r.bits() & !mask_l) });
+            p.modeh().modify(|r, w| unsafe { w.bits(r.bits() & !mask_h) });
+            p.dout()
+                .modify(|r, w| unsafe { w.pins_dout().bits(r.pins_dout().bits() & !mask) });
+        });
+    }
+
     /// Get the Data In for a given pin `pin` in `port`
     #[inline(always)]
     pub(crate) fn din(port: PortId, pin: PinId) -> bool {
@@ -882,7 +1130,6 @@ pub(crate) mod pins {
     /// Return `true` if Over Voltage Tolerance is enabled for a given `pin` in `port`
     ///
     /// OVT is enabled by default for all pins
-    #[allow(dead_code)]
     #[inline(always)]
     pub(crate) fn ovt(port: PortId, pin: PinId) -> bool {
         ports::get(port).ovt_dis().read().pins_ovt_dis().bits() & (1u16 << pin as u8) == 0
@@ -2,15 +2,30 @@
 //!
 //! Sep port-wide configurations for each port
 //!
-//! Note than the `set_din_dis()` method is not implemented for Port F unless the `use_debug_pins` crate feature is
-//! enabled. This protects the debug pins (F0, F1, F2, F3) from being accidentally disabled.
+//! Note that the `set_din_dis()` method of Port F (see [`PortFDataInDisable`]) is fallible. This protects the debug
+//! pins (F0, F1, F2, F3) from being accidentally disabled:
 //!
-//! When the `use_debug_pins` feature is enabled, port F provides a `set_din_dis()` method.
-//! This method will only succeede if the debug pins have been converted into GPIO pins using the `into_gpio_pins()`
-//! method on `debug_pins` in [`crate::gpio::Gpio`].
+//! - when the `use_debug_pins` feature is not enabled, disabling Data In for port F always fails with
+//!   [`GpioError::DebugPinsEnabled`]
+//! - when the `use_debug_pins` feature is enabled, disabling Data In for port F will only succeed if the debug pins
+//!   have been converted into GPIO pins using the `into_gpio_pins()` method on `debug_pins` in [`crate::gpio::Gpio`].
+//!
+//! Re-enabling Data In for port F always succeeds.
+//!
+//! The input buffers draw power even when a pin is only used as an output. If all the pins of a port are outputs,
+//! `set_output_only()` disables Data In for the whole port, in both the primary and the Alternate Mode:
+//!
+//! ```rust,no_run
+//! use efm32pg1b_hal::gpio::port::PortDataInDisable;
+//!
+//! let mut led = gpio.pd13.into_mode::<OutPp>();
+//! gpio.port_d.set_output_only(DataInCtrl::Disabled);
+//!
+//! // `is_set_high()` and `toggle()` read the output latch, not the disabled input buffer
+//! led.toggle().unwrap();
+//! ```
 //!
 
-#[cfg(feature = "use_debug_pins")]
 use crate::gpio::debug::debug_pins_enabled;
 use crate::{gpio::GpioError, Sealed};
 
@@ -31,13 +46,31 @@ where
     }
 
     /// Reset the the Port `P` registers to their reset state
+    ///
+    /// For port `F`, the configuration of the debug pins (`PF0`-`PF3`) is kept while they are enabled, so that an
+    /// attached debugger is not disturbed.
     pub(crate) fn reset(&mut self) {
         let port = ports::get(self.id());
-        port.dout().reset();
-        port.model().reset();
+
+        if P == 'F' && debug_pins_enabled() {
+            // `DOUT` and `OVT_DIS` bits, and `MODEL` nibbles of `PF0`-`PF3`
+            const DEBUG_PINS_MASK: u32 = 0x0000_000F;
+            const DEBUG_PINS_MODE_MASK: u32 = 0x0000_FFFF;
+
+            // The reset value of these registers is `0`, so only the debug pins bits are kept
+            port.dout().modify(|r, w| unsafe { w.bits(r.bits() & DEBUG_PINS_MASK) });
+            port.model()
+                .modify(|r, w| unsafe { w.bits(r.bits() & DEBUG_PINS_MODE_MASK) });
+            port.ovt_dis()
+                .modify(|r, w| unsafe { w.bits(r.bits() & DEBUG_PINS_MASK) });
+        } else {
+            port.dout().reset();
+            port.model().reset();
+            port.ovt_dis().reset();
+        }
+
         port.modeh().reset();
         port.ctrl().reset();
-        port.ovt_dis().reset();
     }
 
     /// Get the port id
@@ -99,6 +132,11 @@ where
     pub fn set_din_dis_alt(&mut self, din_dis: DataInCtrl) {
         ports::set_din_dis_alt(self.id(), din_dis);
     }
+
+    /// Convert this port into an erased port, where the port id is not stored as a type state
+    pub fn erase(self) -> ErasedPort {
+        ErasedPort::new(self.id())
+    }
 }
 
 impl Sealed for Port<'A'> {}
@@ -107,6 +145,129 @@ impl Sealed for Port<'C'> {}
 impl Sealed for Port<'D'> {}
 impl Sealed for Port<'F'> {}
 
+/// Erased Port
+///
+/// Same as [`Port`], but the port id is stored at runtime instead of as a type state. This allows ports to be
+/// configured from a runtime list:
+///
+/// ```rust,no_run
+/// let mut ports = [gpio.port_a.erase(), gpio.port_b.erase(), gpio.port_c.erase()];
+///
+/// for port in ports.iter_mut() {
+///     port.set_drive_strength(DriveStrength::Weak);
+/// }
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ErasedPort {
+    id: PortId,
+}
+
+impl ErasedPort {
+    pub(crate) const fn new(id: PortId) -> Self {
+        Self { id }
+    }
+
+    /// Get the port id
+    pub fn id(&self) -> PortId {
+        self.id
+    }
+
+    /// Get the Drive Strength setting of this port (not in Alternate Mode)
+    pub fn drive_strength(&self) -> DriveStrength {
+        ports::drive_strength(self.id)
+    }
+
+    /// Get the Alternate Drive Strength setting of this port
+    pub fn drive_strength_alt(&self) -> DriveStrength {
+        ports::drive_strength_alt(self.id)
+    }
+
+    /// Set the Drive Strength setting of this port (not in Alternate Mode)
+    pub fn set_drive_strength(&mut self, drive_strength: DriveStrength) {
+        ports::set_drive_strength(self.id, drive_strength);
+    }
+
+    /// Set the Alternate Drive Strength setting of this port
+    pub fn set_drive_strength_alt(&mut self, drive_strength: DriveStrength) {
+        ports::set_drive_strength_alt(self.id, drive_strength);
+    }
+
+    /// Get the Slew Rate setting of this port (not in Alternate Mode). Higher values represent faster slewrates.
+    pub fn slew_rate(&self) -> DriveSlewRate {
+        ports::slew_rate(self.id)
+    }
+
+    /// Get the Slew Rate setting of this port. Higher values represent faster slewrates.
+    pub fn slew_rate_alt(&self) -> DriveSlewRate {
+        ports::slew_rate_alt(self.id)
+    }
+
+    /// Set the Slew Rate setting of this port (not in Alternate Mode). Higher values represent faster slewrates
+    pub fn set_slew_rate(&mut self, slew_rate: DriveSlewRate) {
+        ports::set_slew_rate(self.id, slew_rate);
+    }
+
+    /// Set the Alternate Slew Rate setting of this port. Higher values represent faster slewrates.
+    pub fn set_slew_rate_alt(&mut self, slew_rate: DriveSlewRate) {
+        ports::set_slew_rate_alt(self.id, slew_rate);
+    }
+
+    /// Get the Data In Disable setting of this port (not in Alternate Mode)
+    pub fn din_dis(&self) -> bool {
+        ports::din_dis(self.id)
+    }
+
+    /// Get the Alternate Data In Disable setting of this port
+    pub fn din_dis_alt(&self) -> bool {
+        ports::din_dis_alt(self.id)
+    }
+
+    /// Set the Data In Disable setting of this port (not in Alternate Mode).
+    ///
+    /// Disabling Data In for port `F` is only allowed if the `use_debug_pins` crate feature is enabled, and the debug
+    /// pins have been converted into GPIO pins. Otherwise [`GpioError::DebugPinsEnabled`] is returned.
+    pub fn set_din_dis(&mut self, din_dis: DataInCtrl) -> Result<(), GpioError> {
+        self.check_din_dis(&din_dis)?;
+
+        ports::set_din_dis(self.id, din_dis);
+        Ok(())
+    }
+
+    /// Disable (or re-enable) the input buffers of the whole port, in both the primary and the Alternate Mode. See
+    /// [`PortDataInDisable::set_output_only()`].
+    ///
+    /// Same as for [`ErasedPort::set_din_dis()`], disabling Data In for port `F` fails with
+    /// [`GpioError::DebugPinsEnabled`] while the debug pins are enabled.
+    pub fn set_output_only(&mut self, din_dis: DataInCtrl) -> Result<(), GpioError> {
+        self.check_din_dis(&din_dis)?;
+
+        ports::set_output_only(self.id, din_dis);
+        Ok(())
+    }
+
+    /// Check that `din_dis` does not disable Data In for the debug pins of port `F`
+    fn check_din_dis(&self, din_dis: &DataInCtrl) -> Result<(), GpioError> {
+        if self.id == PortId::F && matches!(din_dis, DataInCtrl::Disabled) {
+            #[cfg(feature = "use_debug_pins")]
+            let allowed = !debug_pins_enabled();
+            #[cfg(not(feature = "use_debug_pins"))]
+            let allowed = false;
+
+            if !allowed {
+                return Err(GpioError::DebugPinsEnabled);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Set the Alternate Data In Disable setting of this port
+    pub fn set_din_dis_alt(&mut self, din_dis: DataInCtrl) {
+        ports::set_din_dis_alt(self.id, din_dis);
+    }
+}
+
 /// Type safe representation of a Port ID
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 #[repr(u8)]
@@ -290,6 +451,14 @@ pub(crate) mod ports {
             DataInCtrl::Disabled => w.din_dis_alt().set_bit(),
         });
     }
+
+    /// Set both the Data In Disable and the Alternate Data In Disable settings of this port, with a single write
+    pub(crate) fn set_output_only(port: PortId, din_dis: DataInCtrl) {
+        get(port).ctrl().modify(|_, w| match din_dis {
+            DataInCtrl::Enabled => w.din_dis().clear_bit().din_dis_alt().clear_bit(),
+            DataInCtrl::Disabled => w.din_dis().set_bit().din_dis_alt().set_bit(),
+        });
+    }
 }
 
 /// Data In Disable trait used to protect the debug pins in port `F`.
@@ -298,40 +467,97 @@ pub(crate) mod ports {
 pub trait PortDataInDisable: Sealed {
     /// Set the Data In Disable setting of this port (not in Alternate Mode).
     fn set_din_dis(&mut self, din_dis: DataInCtrl);
+
+    /// Disable (or re-enable) the input buffers of the whole port, in both the primary and the Alternate Mode, to save
+    /// the power they draw when all the pins of the port are outputs.
+    ///
+    /// The [`StatefulOutputPin`](embedded_hal::digital::StatefulOutputPin) methods of the output pins (and therefore
+    /// `toggle()`) read the output latch (`DOUT`), so they keep working. Reading a pin through its input buffer (e.g.
+    /// [`InputPin`](embedded_hal::digital::InputPin) methods, or `pin_level()`) fails with
+    /// [`GpioError::DataInDisabled`] until Data In is enabled again.
+    fn set_output_only(&mut self, din_dis: DataInCtrl);
 }
 
 impl PortDataInDisable for Port<'A'> {
     fn set_din_dis(&mut self, din_dis: DataInCtrl) {
         ports::set_din_dis(self.id(), din_dis);
     }
+
+    fn set_output_only(&mut self, din_dis: DataInCtrl) {
+        ports::set_output_only(self.id(), din_dis);
+    }
 }
 
 impl PortDataInDisable for Port<'B'> {
     fn set_din_dis(&mut self, din_dis: DataInCtrl) {
         ports::set_din_dis(self.id(), din_dis);
     }
+
+    fn set_output_only(&mut self, din_dis: DataInCtrl) {
+        ports::set_output_only(self.id(), din_dis);
+    }
 }
 
 impl PortDataInDisable for Port<'C'> {
     fn set_din_dis(&mut self, din_dis: DataInCtrl) {
         ports::set_din_dis(self.id(), din_dis);
     }
+
+    fn set_output_only(&mut self, din_dis: DataInCtrl) {
+        ports::set_output_only(self.id(), din_dis);
+    }
 }
 
 impl PortDataInDisable for Port<'D'> {
     fn set_din_dis(&mut self, din_dis: DataInCtrl) {
         ports::set_din_dis(self.id(), din_dis);
     }
+
+    fn set_output_only(&mut self, din_dis: DataInCtrl) {
+        ports::set_output_only(self.id(), din_dis);
+    }
 }
 
 /// Data In Disable trait used to protect the debug pins in port `F`.
 ///
-/// Only implemented for port `F`, and only if the `use_debug_pins` crate feature is enabled.
+/// Only implemented for port `F`.
 pub trait PortFDataInDisable: Sealed {
     /// Set the Data In Disable setting of this port (not in Alternate Mode).
     ///
-    /// The `use_debug_pins` crate feature needs to be enabled in order to have this method on port `F`.
+    /// The `use_debug_pins` crate feature needs to be enabled in order to disable Data In on port `F`, otherwise
+    /// [`GpioError::DebugPinsEnabled`] is returned.
     fn set_din_dis(&mut self, din_dis: DataInCtrl) -> Result<(), GpioError>;
+
+    /// Disable (or re-enable) the input buffers of the whole port, in both the primary and the Alternate Mode. See
+    /// [`PortDataInDisable::set_output_only()`].
+    ///
+    /// Same as for [`PortFDataInDisable::set_din_dis()`], disabling Data In fails with [`GpioError::DebugPinsEnabled`]
+    /// unless the `use_debug_pins` crate feature is enabled and the debug pins have been converted into GPIO pins.
+    fn set_output_only(&mut self, din_dis: DataInCtrl) -> Result<(), GpioError>;
+}
+
+#[cfg(not(feature = "use_debug_pins"))]
+impl PortFDataInDisable for Port<'F'> {
+    fn set_din_dis(&mut self, din_dis: DataInCtrl) -> Result<(), GpioError> {
+        match din_dis {
+            DataInCtrl::Enabled => {
+                ports::set_din_dis(self.id(), din_dis);
+                Ok(())
+            }
+            // The debug pins (pf0-pf3) are always enabled without the `use_debug_pins` feature
+            DataInCtrl::Disabled => Err(GpioError::DebugPinsEnabled),
+        }
+    }
+
+    fn set_output_only(&mut self, din_dis: DataInCtrl) -> Result<(), GpioError> {
+        match din_dis {
+            DataInCtrl::Enabled => {
+                ports::set_output_only(self.id(), din_dis);
+                Ok(())
+            }
+            DataInCtrl::Disabled => Err(GpioError::DebugPinsEnabled),
+        }
+    }
 }
 
 #[cfg(feature = "use_debug_pins")]
@@ -353,6 +579,23 @@ impl PortFDataInDisable for Port<'F'> {
             }
         }
     }
+
+    fn set_output_only(&mut self, din_dis: DataInCtrl) -> Result<(), GpioError> {
+        match din_dis {
+            DataInCtrl::Enabled => {
+                ports::set_output_only(self.id(), din_dis);
+                Ok(())
+            }
+            DataInCtrl::Disabled => {
+                if debug_pins_enabled() {
+                    Err(GpioError::DebugPinsEnabled)
+                } else {
+                    ports::set_output_only(self.id(), din_dis);
+                    Ok(())
+                }
+            }
+        }
+    }
 }
 
 /// Data In Control variants for `DIN_DIS` (and `ALT`) field in `GPIO_Px_CTRL` Port Control Register
@@ -376,6 +619,9 @@ pub enum DriveStrength {
 }
 
 /// Slewrate limit for port pins. Higher values represent faster slewrates.
+///
+/// The `SLEWRATE` (and `ALT`) fields in `GPIO_Px_CTRL` are 3 bits wide, and all values (`0..=7`) are valid. The reset
+/// value is [`DriveSlewRate::SlewRate5`]. Converting any other `u8` value fails with [`GpioError::InvalidSlewRate`].
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 #[repr(u8)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
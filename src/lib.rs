@@ -11,8 +11,14 @@
 
 pub use efm32pg1b_pac as pac;
 
+pub mod bitbang;
 pub mod cmu;
+pub mod emu;
 pub mod gpio;
+pub mod msc;
+pub mod prs;
+pub mod rmu;
+pub mod systick;
 pub mod timer;
 pub mod timer_le;
 pub mod usart;
@@ -26,18 +32,33 @@ mod sealed {
 pub(crate) use sealed::Sealed;
 
 /// Convenience module which exports the most used types for each module
+///
+/// ```rust,no_run
+/// use efm32pg1b_hal::prelude::*;
+/// ```
+///
+/// Only the extension traits, the pin modes and the types which are commonly named in application code are exported,
+/// so that the glob import does not collide with the `embedded-hal` names (e.g. the HAL [`Pin`](crate::gpio::Pin)
+/// type is not exported, and the `embedded-hal` `spi` module is exported instead of the HAL
+/// [`usart::spi`](crate::usart::spi) module).
 pub mod prelude {
     pub use crate::{
         cmu::{CmuExt, HfClockPrescaler, HfClockSource, LfClockSource},
+        emu::EmuExt,
         gpio::{
             pin::mode::{
                 Analog, Disabled, DisabledPu, InFilt, InFloat, InPd, InPdFilt, InPu, InPuFilt,
                 OutOd, OutOdAlt, OutOdFilt, OutOdFiltAlt, OutOdPu, OutOdPuAlt, OutOdPuFilt,
                 OutOdPuFiltAlt, OutOs, OutOsPd, OutPp, OutPpAlt,
             },
+            erased::ErasedPin,
             port::{DataInCtrl, DriveStrength},
             Gpio, GpioError,
         },
+        msc::MscExt,
+        prs::{PrsChannelId, PrsEdge, PrsExt, PrsSignal, PrsSource},
+        timer::{TimerDivider, TimerExt},
+        timer_le::LeTimerExt,
         usart::{
             spi::{Spi, SpiError},
             Usart, UsartBuild,
@@ -0,0 +1,220 @@
+//! Memory System Controller (internal flash)
+//!
+//! The MSC erases and writes the internal flash, one page (`2 KiB`) at a time. Writes are done one word (`u32`) at a
+//! time, and the flash must have been erased (i.e. all bits `1`) beforehand.
+//!
+//! ```rust,no_run
+//! let mut msc = Msc::new();
+//!
+//! // Erase the last page of a 256 KiB flash, and write two words to it
+//! let addr = 0x0003_F800;
+//! msc.erase_page(addr).unwrap();
+//! msc.write_words(addr, &[0xDEAD_BEEF, 0xCAFE_F00D]).unwrap();
+//!
+//! let mut buf = [0u8; 8];
+//! msc.read(addr, &mut buf).unwrap();
+//! ```
+//!
+//! The CPU is stalled while the flash is busy, so code executing from flash (including interrupt handlers) is delayed
+//! until each erase or write operation completes. The erase and write timings are derived from the AUX HF RCO (see
+//! [`DbgClockSource::AuxHfRcoBand`](crate::cmu::DbgClockSource::AuxHfRcoBand)).
+
+use crate::pac::Msc as MscPeripheral;
+use cortex_m::asm::nop;
+
+/// Size of a flash page, which is the smallest erasable unit
+pub const FLASH_PAGE_SIZE: u32 = 2048;
+
+/// Address of the `DEVINFO_MSIZE` register, whose lower 16 bits are the flash size in KiB
+const DEVINFO_MSIZE_ADDR: usize = 0x0FE0_81F8;
+
+/// Key which unlocks the MSC registers (`MSC_LOCK`)
+const MSC_UNLOCK_KEY: u32 = 0x1B71;
+
+/// Maximum number of polling iterations while waiting for the flash to become ready
+const MSC_BUSY_MAX_ITER: u32 = 1_000_000;
+
+/// Extension trait for the MSC PAC peripheral
+pub trait MscExt {
+    /// Convert the PAC peripheral into the HAL `Msc` driver
+    fn into_msc(self) -> Msc;
+}
+
+impl MscExt for MscPeripheral {
+    fn into_msc(self) -> Msc {
+        Msc::new()
+    }
+}
+
+/// Internal flash driver
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Msc {
+    _p: (),
+}
+
+impl Msc {
+    /// Create the flash driver.
+    ///
+    /// Prefer [`MscExt::into_msc()`], which takes ownership of the PAC peripheral, unless the MSC is needed before
+    /// the peripherals are split (e.g. in a bootloader).
+    pub fn new() -> Self {
+        Self { _p: () }
+    }
+
+    /// Size of the internal flash, as read from the Device Information page
+    pub fn flash_size(&self) -> u32 {
+        // SAFETY: the Device Information page is always mapped and read-only
+        let msize = unsafe { core::ptr::read_volatile(DEVINFO_MSIZE_ADDR as *const u32) };
+
+        (msize & 0xFFFF) * 1024
+    }
+
+    /// Erase the flash page starting at `addr`, which must be aligned to [`FLASH_PAGE_SIZE`]
+    pub fn erase_page(&mut self, addr: u32) -> Result<(), MscError> {
+        if addr % FLASH_PAGE_SIZE != 0 {
+            return Err(MscError::Misaligned(addr));
+        }
+        self.check_range(addr, FLASH_PAGE_SIZE)?;
+
+        self.with_write_enabled(|msc| {
+            Self::load_address(msc, addr)?;
+
+            msc.writecmd().write(|w| w.erasepage().set_bit());
+
+            Self::wait_not_busy(msc)
+        })
+    }
+
+    /// Write `words` to the flash, starting at `addr`. The address must be word aligned, the words must be in the same
+    /// page, and the flash must have been erased beforehand.
+    pub fn write_words(&mut self, addr: u32, words: &[u32]) -> Result<(), MscError> {
+        if addr % 4 != 0 {
+            return Err(MscError::Misaligned(addr));
+        }
+
+        let len = words.len() as u32 * 4;
+        self.check_range(addr, len)?;
+
+        if len > 0 && (addr / FLASH_PAGE_SIZE) != ((addr + len - 1) / FLASH_PAGE_SIZE) {
+            return Err(MscError::PageBoundary(addr));
+        }
+
+        self.with_write_enabled(|msc| {
+            // `MSC_ADDRB` is incremented by the MSC after each word, as long as it stays in the same page
+            Self::load_address(msc, addr)?;
+
+            for word in words {
+                let mut iter = 0;
+                while msc.status().read().wdataready().bit_is_clear() {
+                    iter += 1;
+                    if iter >= MSC_BUSY_MAX_ITER {
+                        return Err(MscError::Timeout);
+                    }
+                    nop();
+                }
+
+                msc.wdata().write(|w| unsafe { w.wdata().bits(*word) });
+                msc.writecmd().write(|w| w.writeonce().set_bit());
+
+                Self::wait_not_busy(msc)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Read `buf.len()` bytes from the flash, starting at `addr`
+    pub fn read(&self, addr: u32, buf: &mut [u8]) -> Result<(), MscError> {
+        self.check_range(addr, buf.len() as u32)?;
+
+        for (i, byte) in buf.iter_mut().enumerate() {
+            // SAFETY: the range was checked to be inside the internal flash, which is always mapped
+            *byte = unsafe { core::ptr::read_volatile((addr as usize + i) as *const u8) };
+        }
+
+        Ok(())
+    }
+
+    /// Check that `len` bytes starting at `addr` are inside the internal flash
+    fn check_range(&self, addr: u32, len: u32) -> Result<(), MscError> {
+        // the internal flash starts at address `0`
+        let flash_end = self.flash_size();
+
+        match addr.checked_add(len) {
+            Some(end) if end <= flash_end => Ok(()),
+            _ => Err(MscError::InvalidAddress(addr)),
+        }
+    }
+
+    /// Unlock the MSC registers and enable writes for the duration of `f`, then disable writes and lock the MSC
+    /// registers again
+    fn with_write_enabled<F>(&mut self, f: F) -> Result<(), MscError>
+    where
+        F: FnOnce(&MscPeripheral) -> Result<(), MscError>,
+    {
+        let msc = unsafe { MscPeripheral::steal() };
+
+        msc.lock().write(|w| unsafe { w.bits(MSC_UNLOCK_KEY) });
+        msc.writectrl().modify(|_, w| w.wren().set_bit());
+
+        let res = f(&msc);
+
+        msc.writectrl().modify(|_, w| w.wren().clear_bit());
+        // any value other than the unlock key locks the MSC registers
+        msc.lock().write(|w| unsafe { w.bits(0) });
+
+        res
+    }
+
+    /// Load `addr` into `MSC_ADDRB`, and check that it can be written
+    fn load_address(msc: &MscPeripheral, addr: u32) -> Result<(), MscError> {
+        msc.addrb().write(|w| unsafe { w.addrb().bits(addr) });
+        msc.writecmd().write(|w| w.laddrim().set_bit());
+
+        let status = msc.status().read();
+        if status.invaddr().bit_is_set() {
+            Err(MscError::InvalidAddress(addr))
+        } else if status.locked().bit_is_set() {
+            Err(MscError::Locked(addr))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Wait for the current erase or write operation to complete
+    fn wait_not_busy(msc: &MscPeripheral) -> Result<(), MscError> {
+        let mut iter = 0;
+        while msc.status().read().busy().bit_is_set() {
+            iter += 1;
+            if iter >= MSC_BUSY_MAX_ITER {
+                return Err(MscError::Timeout);
+            }
+            nop();
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Msc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// MSC Errors
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MscError {
+    /// The address is not word aligned (or page aligned, for erasing)
+    Misaligned(u32),
+    /// The written words would cross a page boundary
+    PageBoundary(u32),
+    /// The address (or the end of the accessed range) is outside of the internal flash
+    InvalidAddress(u32),
+    /// The page is locked (`MSC_STATUS.LOCKED`), e.g. by the lock bits page
+    Locked(u32),
+    /// The flash did not become ready in time
+    Timeout,
+}
@@ -0,0 +1,310 @@
+//! Peripheral Reflex System
+//!
+//! The PRS routes signals produced by one peripheral (e.g. a timer overflow) to other peripherals which consume them,
+//! without any CPU involvement.
+//!
+//! ```rust,no_run
+//! let p = pac::Peripherals::take().unwrap();
+//! let mut prs = p.prs.into_prs();
+//!
+//! // Route the `Timer0` overflow signal on PRS channel 0, and generate a pulse on each rising edge
+//! let mut ch0 = prs.channel(PrsChannelId::Ch0);
+//! ch0.select(PrsSource::Timer0, PrsSignal::TimerOverflow).unwrap();
+//! ch0.set_edge(PrsEdge::Rising);
+//!
+//! // Use PRS channel 0 as the input of `Timer1` channel 0
+//! tim1ch0.select_prs_input(PrsChannelId::Ch0);
+//! ```
+//!
+//! The producers and consumers are currently limited to the `TIMER` peripherals.
+
+use crate::{
+    cmu::{gates, PeripheralClock},
+    pac::Prs as PrsPeripheral,
+};
+
+/// Number of PRS channels
+pub const PRS_CHANNEL_COUNT: usize = 12;
+
+/// Extension trait for the PRS PAC peripheral
+pub trait PrsExt {
+    /// Convert the PAC peripheral into the HAL `Prs` driver
+    fn into_prs(self) -> Prs;
+}
+
+impl PrsExt for PrsPeripheral {
+    fn into_prs(self) -> Prs {
+        Prs::new()
+    }
+}
+
+/// Peripheral Reflex System driver
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Prs {
+    _p: (),
+}
+
+impl Prs {
+    fn new() -> Self {
+        // Enable PRS clock
+        gates::enable(PeripheralClock::Prs);
+
+        Self { _p: () }
+    }
+
+    /// Get the PRS channel with the given id
+    pub fn channel(&mut self, id: PrsChannelId) -> PrsChannel<'_> {
+        PrsChannel { _prs: self, id }
+    }
+
+    /// Release the PAC peripheral, disabling all PRS channels and the PRS clock
+    pub fn free(self) -> PrsPeripheral {
+        for ch in 0..PRS_CHANNEL_COUNT as u8 {
+            mmio::ch_ctrl_write(PrsChannelId::from_u8_unchecked(ch), 0);
+        }
+
+        gates::disable(PeripheralClock::Prs);
+
+        unsafe { PrsPeripheral::steal() }
+    }
+}
+
+/// A single PRS channel, borrowed from [`Prs`]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PrsChannel<'a> {
+    _prs: &'a mut Prs,
+    id: PrsChannelId,
+}
+
+impl PrsChannel<'_> {
+    /// Get the id of this channel
+    pub fn id(&self) -> PrsChannelId {
+        self.id
+    }
+
+    /// Select the producer `source` and its `signal` which will drive this channel
+    ///
+    /// Fails if the `signal` is not produced by the given `source`
+    pub fn select(&mut self, source: PrsSource, signal: PrsSignal) -> Result<(), PrsError> {
+        let sigsel = signal.sigsel(source)?;
+
+        mmio::ch_ctrl_modify(self.id, |bits| {
+            (bits & !(mmio::SOURCESEL_MASK | mmio::SIGSEL_MASK))
+                | ((source as u32) << mmio::SOURCESEL_OFFSET)
+                | sigsel as u32
+        });
+
+        Ok(())
+    }
+
+    /// Select the edge of the producer signal which generates a pulse on this channel. Use [`PrsEdge::Off`] to let the
+    /// producer signal level through unchanged.
+    pub fn set_edge(&mut self, edge: PrsEdge) {
+        mmio::ch_ctrl_modify(self.id, |bits| {
+            (bits & !mmio::EDSEL_MASK) | ((edge as u32) << mmio::EDSEL_OFFSET)
+        });
+    }
+
+    /// Invert the channel output
+    pub fn set_inverted(&mut self, inverted: bool) {
+        mmio::ch_ctrl_modify(self.id, |bits| match inverted {
+            true => bits | mmio::INV_MASK,
+            false => bits & !mmio::INV_MASK,
+        });
+    }
+
+    /// Disconnect this channel from any producer
+    pub fn clear(&mut self) {
+        mmio::ch_ctrl_write(self.id, 0);
+    }
+
+    /// Get the current level of this channel
+    pub fn level(&self) -> bool {
+        mmio::peek(self.id)
+    }
+}
+
+/// PRS channel ID
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PrsChannelId {
+    /// PRS channel 0
+    Ch0,
+    /// PRS channel 1
+    Ch1,
+    /// PRS channel 2
+    Ch2,
+    /// PRS channel 3
+    Ch3,
+    /// PRS channel 4
+    Ch4,
+    /// PRS channel 5
+    Ch5,
+    /// PRS channel 6
+    Ch6,
+    /// PRS channel 7
+    Ch7,
+    /// PRS channel 8
+    Ch8,
+    /// PRS channel 9
+    Ch9,
+    /// PRS channel 10
+    Ch10,
+    /// PRS channel 11
+    Ch11,
+}
+
+impl PrsChannelId {
+    pub(crate) const fn from_u8_unchecked(u: u8) -> Self {
+        match u {
+            0 => Self::Ch0,
+            1 => Self::Ch1,
+            2 => Self::Ch2,
+            3 => Self::Ch3,
+            4 => Self::Ch4,
+            5 => Self::Ch5,
+            6 => Self::Ch6,
+            7 => Self::Ch7,
+            8 => Self::Ch8,
+            9 => Self::Ch9,
+            10 => Self::Ch10,
+            11 => Self::Ch11,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl TryFrom<u8> for PrsChannelId {
+    type Error = PrsError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        if (value as usize) < PRS_CHANNEL_COUNT {
+            Ok(Self::from_u8_unchecked(value))
+        } else {
+            Err(PrsError::InvalidChannel(value))
+        }
+    }
+}
+
+/// PRS signal producers (`SOURCESEL` field in `PRS_CHx_CTRL`)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PrsSource {
+    /// No source selected
+    None = 0x00,
+    /// `TIMER0` peripheral
+    Timer0 = 0x1C,
+    /// `TIMER1` peripheral
+    Timer1 = 0x1D,
+}
+
+/// PRS signals (`SIGSEL` field in `PRS_CHx_CTRL`)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PrsSignal {
+    /// Timer underflow
+    TimerUnderflow,
+    /// Timer overflow
+    TimerOverflow,
+    /// Timer Capture/Compare channel 0
+    TimerCc0,
+    /// Timer Capture/Compare channel 1
+    TimerCc1,
+    /// Timer Capture/Compare channel 2
+    TimerCc2,
+    /// Timer Capture/Compare channel 3
+    TimerCc3,
+}
+
+impl PrsSignal {
+    /// Get the `SIGSEL` value of this signal for the given `source`
+    const fn sigsel(&self, source: PrsSource) -> Result<u8, PrsError> {
+        match source {
+            PrsSource::None => Err(PrsError::InvalidSignal(source, *self)),
+            PrsSource::Timer0 | PrsSource::Timer1 => Ok(match self {
+                PrsSignal::TimerUnderflow => 0,
+                PrsSignal::TimerOverflow => 1,
+                PrsSignal::TimerCc0 => 2,
+                PrsSignal::TimerCc1 => 3,
+                PrsSignal::TimerCc2 => 4,
+                PrsSignal::TimerCc3 => 5,
+            }),
+        }
+    }
+}
+
+/// Edge detection on the producer signal (`EDSEL` field in `PRS_CHx_CTRL`)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PrsEdge {
+    /// Signal is left as it is
+    Off,
+    /// A one HFCLK cycle pulse is generated for every positive edge of the incoming signal
+    Rising,
+    /// A one HFCLK clock cycle pulse is generated for every negative edge of the incoming signal
+    Falling,
+    /// A one HFCLK clock cycle pulse is generated for every edge of the incoming signal
+    Both,
+}
+
+/// PRS module errors
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PrsError {
+    /// Failed to convert a literal representation of a channel id to a [`PrsChannelId`]
+    InvalidChannel(u8),
+    /// The signal is not produced by the given source
+    InvalidSignal(PrsSource, PrsSignal),
+}
+
+/// Access functions for PRS Memory Mapped IO
+pub(crate) mod mmio {
+    use crate::{pac::Prs, prs::PrsChannelId};
+
+    pub(crate) const SIGSEL_MASK: u32 = 0b111;
+    pub(crate) const SOURCESEL_OFFSET: u32 = 8;
+    pub(crate) const SOURCESEL_MASK: u32 = 0x7F << SOURCESEL_OFFSET;
+    pub(crate) const EDSEL_OFFSET: u32 = 20;
+    pub(crate) const EDSEL_MASK: u32 = 0b11 << EDSEL_OFFSET;
+    pub(crate) const INV_MASK: u32 = 1 << 26;
+
+    /// Write the raw `bits` in the `PRS_CHx_CTRL` register of the given channel
+    pub(crate) fn ch_ctrl_write(ch: PrsChannelId, bits: u32) {
+        ch_ctrl_modify(ch, |_| bits);
+    }
+
+    /// Modify the raw bits in the `PRS_CHx_CTRL` register of the given channel
+    pub(crate) fn ch_ctrl_modify(ch: PrsChannelId, f: impl FnOnce(u32) -> u32) {
+        let prs = prs();
+
+        match ch {
+            PrsChannelId::Ch0 => prs.ch0_ctrl().modify(|r, w| unsafe { w.bits(f(r.bits())) }),
+            PrsChannelId::Ch1 => prs.ch1_ctrl().modify(|r, w| unsafe { w.bits(f(r.bits())) }),
+            PrsChannelId::Ch2 => prs.ch2_ctrl().modify(|r, w| unsafe { w.bits(f(r.bits())) }),
+            PrsChannelId::Ch3 => prs.ch3_ctrl().modify(|r, w| unsafe { w.bits(f(r.bits())) }),
+            PrsChannelId::Ch4 => prs.ch4_ctrl().modify(|r, w| unsafe { w.bits(f(r.bits())) }),
+            PrsChannelId::Ch5 => prs.ch5_ctrl().modify(|r, w| unsafe { w.bits(f(r.bits())) }),
+            PrsChannelId::Ch6 => prs.ch6_ctrl().modify(|r, w| unsafe { w.bits(f(r.bits())) }),
+            PrsChannelId::Ch7 => prs.ch7_ctrl().modify(|r, w| unsafe { w.bits(f(r.bits())) }),
+            PrsChannelId::Ch8 => prs.ch8_ctrl().modify(|r, w| unsafe { w.bits(f(r.bits())) }),
+            PrsChannelId::Ch9 => prs.ch9_ctrl().modify(|r, w| unsafe { w.bits(f(r.bits())) }),
+            PrsChannelId::Ch10 => prs.ch10_ctrl().modify(|r, w| unsafe { w.bits(f(r.bits())) }),
+            PrsChannelId::Ch11 => prs.ch11_ctrl().modify(|r, w| unsafe { w.bits(f(r.bits())) }),
+        };
+    }
+
+    /// Get the current level of the given channel
+    pub(crate) fn peek(ch: PrsChannelId) -> bool {
+        prs().peek().read().bits() & (1 << ch as u8) != 0
+    }
+
+    #[inline(always)]
+    fn prs() -> Prs {
+        unsafe { Prs::steal() }
+    }
+}
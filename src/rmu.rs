@@ -0,0 +1,88 @@
+//! Reset Management Unit
+//!
+//! The cause of the last reset is latched in `RMU_RSTCAUSE`, e.g. to tell a watchdog reset apart from a cold boot:
+//!
+//! ```rust,no_run
+//! let cause = ResetCause::read();
+//!
+//! if cause.watchdog {
+//!     defmt::println!("recovered from a watchdog reset");
+//! }
+//!
+//! // Clear the latched flags, so that the next reset is reported on its own
+//! ResetCause::clear();
+//! ```
+//!
+//! The flags are only cleared by a power-on reset or by [`ResetCause::clear()`], so several of them may be set at the
+//! same time. Like in the vendor library, a flag is only reported if no reset with a higher priority (power-on,
+//! brown-out, then external pin) is also latched, since these set some of the other flags as a side effect.
+
+use crate::pac::Rmu;
+
+/// `RMU_RSTCAUSE` bits
+const PORST: u32 = 1 << 0;
+const AVDDBOD: u32 = 1 << 2;
+const DVDDBOD: u32 = 1 << 3;
+const DECBOD: u32 = 1 << 4;
+const EXTRST: u32 = 1 << 8;
+const LOCKUPRST: u32 = 1 << 9;
+const SYSREQRST: u32 = 1 << 10;
+const WDOGRST: u32 = 1 << 11;
+const EM4RST: u32 = 1 << 16;
+
+/// Brown-out resets
+const BOD_MASK: u32 = AVDDBOD | DVDDBOD | DECBOD;
+
+/// Decoded cause of the last reset (`RMU_RSTCAUSE`)
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ResetCause {
+    /// Power-on reset
+    pub power_on: bool,
+    /// Brown-out reset, of either the analog (`AVDD`), digital (`DVDD`) or regulated (`DEC`) supply
+    pub brown_out: bool,
+    /// Reset pin
+    pub external: bool,
+    /// Core lockup reset
+    pub lockup: bool,
+    /// System reset request (`SYSRESETREQ`), e.g. by software or by a debugger
+    pub system_request: bool,
+    /// Watchdog reset
+    pub watchdog: bool,
+    /// Wake up from EM4
+    pub em4_wakeup: bool,
+}
+
+impl ResetCause {
+    /// Read and decode the reset cause
+    pub fn read() -> Self {
+        let rmu = unsafe { Rmu::steal() };
+
+        Self::from_bits(rmu.rstcause().read().bits())
+    }
+
+    /// Clear all the reset cause flags (`RMU_CMD.RCCLR`)
+    pub fn clear() {
+        let rmu = unsafe { Rmu::steal() };
+
+        rmu.cmd().write(|w| w.rcclr().set_bit());
+    }
+
+    /// Decode the raw value of `RMU_RSTCAUSE`
+    fn from_bits(bits: u32) -> Self {
+        let is_set = |flag: u32, higher_priority: u32| (bits & flag) != 0 && (bits & higher_priority) == 0;
+
+        let above_pin = PORST | BOD_MASK;
+        let above_core = PORST | BOD_MASK | EXTRST;
+
+        Self {
+            power_on: is_set(PORST, 0),
+            brown_out: is_set(BOD_MASK, PORST),
+            external: is_set(EXTRST, above_pin),
+            lockup: is_set(LOCKUPRST, above_core),
+            system_request: is_set(SYSREQRST, above_core),
+            watchdog: is_set(WDOGRST, above_core),
+            em4_wakeup: is_set(EM4RST, above_core),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! SysTick based delay
+//!
+//! Blocking delays which use the Cortex-M SysTick timer instead of a TIMER channel, for applications where all the
+//! TIMER peripherals are needed (e.g. for PWM or input capture).
+//!
+//! ```rust,no_run
+//! let cp = cortex_m::Peripherals::take().unwrap();
+//! let clocks = p.cmu.split();
+//!
+//! let mut delay = SysTickDelay::new(cp.SYST, &clocks);
+//! delay.delay_ms(500);
+//! ```
+
+use crate::cmu::Clocks;
+use cortex_m::peripheral::{syst::SystClkSource, SYST};
+use embedded_hal::delay::DelayNs;
+use fugit::HertzU32;
+
+/// Blocking delay which counts HF Core Clock cycles with the SysTick timer
+///
+/// The SysTick counter is 24 bits wide, so longer delays are split into several SysTick periods. The counter is
+/// stopped between delays, and the SysTick interrupt is never enabled.
+pub struct SysTickDelay {
+    syst: SYST,
+    core_clk: HertzU32,
+}
+
+impl SysTickDelay {
+    /// Largest SysTick reload value
+    const MAX_RELOAD: u32 = 0x00FF_FFFF;
+
+    /// Create a delay from the SysTick timer, clocked by the HF Core Clock.
+    ///
+    /// The delays are only accurate if [`Clocks::hf_core_clk()`] matches the actual core clock, i.e. the clocks are
+    /// not changed while this delay is in use.
+    pub fn new(mut syst: SYST, clocks: &Clocks) -> Self {
+        syst.disable_counter();
+        syst.disable_interrupt();
+        syst.set_clock_source(SystClkSource::Core);
+
+        Self {
+            syst,
+            core_clk: clocks.hf_core_clk(),
+        }
+    }
+
+    /// Release the SysTick timer
+    pub fn free(self) -> SYST {
+        self.syst
+    }
+
+    /// Busy wait for at least `ticks` core clock cycles
+    fn delay_ticks(&mut self, ticks: u64) {
+        // A reload value of `MAX_RELOAD` gives a period of `MAX_RELOAD + 1` cycles
+        let full_periods = ticks / (Self::MAX_RELOAD as u64 + 1);
+        let remainder = (ticks % (Self::MAX_RELOAD as u64 + 1)) as u32;
+
+        if full_periods > 0 {
+            self.wait_periods(Self::MAX_RELOAD, full_periods);
+        }
+
+        // A reload value of 0 stops the counter, and a single cycle is shorter than the setup anyway
+        if remainder > 1 {
+            self.wait_periods(remainder - 1, 1);
+        }
+    }
+
+    /// Wait for `count` SysTick periods of `reload + 1` cycles
+    fn wait_periods(&mut self, reload: u32, count: u64) {
+        self.syst.set_reload(reload);
+        self.syst.clear_current();
+        self.syst.enable_counter();
+
+        for _ in 0..count {
+            while !self.syst.has_wrapped() {}
+        }
+
+        self.syst.disable_counter();
+    }
+}
+
+impl DelayNs for SysTickDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        // Round up, so that any non-zero delay waits for at least one cycle
+        let ticks = (self.core_clk.raw() as u64 * ns as u64).div_ceil(1_000_000_000);
+
+        self.delay_ticks(ticks);
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        let ticks = (self.core_clk.raw() as u64 * us as u64).div_ceil(1_000_000);
+
+        self.delay_ticks(ticks);
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        let ticks = (self.core_clk.raw() as u64 * ms as u64).div_ceil(1_000);
+
+        self.delay_ticks(ticks);
+    }
+}
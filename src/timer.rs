@@ -1,19 +1,58 @@
 //! Timer/Counter
 //!
+//! # Sharing a timer between channels
+//!
+//! [`Timer::into_channels()`] splits a timer into 4 channels, which are owned and specialised separately (e.g. as
+//! PWM outputs and delays), but which all use the same timer counter. The channels are independent of each other
+//! except for these shared resources:
+//!
+//! * the counter `TOP` value (and the `CNT` value), which sets the period of all the PWM channels. A delay reads
+//!   `TOP` each time it starts, and waits for as many counter periods as needed, so changing `TOP` (e.g. with
+//!   [`TimerCounter::set_top()`]) while a delay is in progress makes it shorter or longer;
+//! * the prescaler, which sets the tick frequency of all the channels. A [`TimerChannelDelay`] only reads it once,
+//!   see [`TimerChannelDelay::recalibrate()`];
+//! * the interrupt enable register (`IEN`) and the `TIMERn` interrupt. A [`TimerChannelDelay`] only polls the
+//!   interrupt flag of its channel, and clears both its interrupt enable bit (in a critical section, so the
+//!   interrupts enabled for the other channels are kept) and its flag when it returns, so that it never raises the
+//!   `TIMERn` interrupt which is used by the other channels (e.g. by [`TimerChannelCompare::on_match()`]).
+//!
+//! The channels which are currently used for delays can be checked at runtime with
+//! [`TimerCounter::delay_channels()`], e.g. before changing `TOP` or stopping the counter:
+//!
+//! ```rust,no_run
+//! let (mut counter, ch0, ch1, _ch2, _ch3) = p.timer0.into_timer(TimerDivider::Div1).into_channels_with_counter();
+//!
+//! let mut pwm = ch0.into_pwm(gpio.pd13.into_mode::<OutPp>());
+//! let mut delay = ch1.into_delay(&clocks);
+//!
+//! if counter.delay_channels() == 0 {
+//!     counter.set_top(999);
+//! }
+//! ```
 
-use crate::{cmu::Clocks, gpio::pin::Pin};
-use core::{convert::Infallible, marker::PhantomData};
+use crate::{
+    cmu::{gates, Clocks, PeripheralClock},
+    gpio::pin::Pin,
+    prs::PrsChannelId,
+};
+use core::{
+    cell::RefCell,
+    convert::Infallible,
+    marker::PhantomData,
+    sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering},
+};
+use critical_section::Mutex;
 pub use efm32pg1b_pac::timer0::ctrl::PRESC as TimerDivider;
 use efm32pg1b_pac::{
-    timer0::{cc0_ctrl, cc1_ctrl, cc2_ctrl, cc3_ctrl, ctrl, RegisterBlock},
-    Cmu, Timer0, Timer1,
+    timer0::{cc0_ctrl, cc1_ctrl, cc2_ctrl, cc3_ctrl, ctrl, ien, RegisterBlock},
+    Interrupt, Timer0, Timer1, NVIC,
 };
 use embedded_hal::{
     delay::DelayNs,
-    digital::OutputPin,
+    digital::{InputPin, OutputPin, PinState},
     pwm::{ErrorType, SetDutyCycle},
 };
-use fugit::HertzU32;
+use fugit::{HertzU32, MicrosDurationU64};
 
 /// Extension trait for Timer PAC peripherals
 pub trait TimerExt {
@@ -46,17 +85,60 @@ const fn timerx<const TN: u8>() -> &'static RegisterBlock {
     }
 }
 
+/// Read-modify-write the `TIMERn_IEN` register of one of the two timers, specified by `TN`.
+///
+/// The interrupt enable bits are shared by all the channels of a timer (which may be owned by different drivers, and
+/// used from interrupt handlers), so the register is never overwritten, and is only modified in a critical section.
+fn modify_ien<const TN: u8>(f: impl FnOnce(&mut ien::W) -> &mut ien::W) {
+    critical_section::with(|_| {
+        timerx::<TN>().ien().modify(|_, w| f(w));
+    });
+}
+
+/// Get the tick frequency of one of the two timers, specified by `TN`, based on the `HFPERCLK` frequency and the
+/// timer prescaler
+fn tick_frequency<const TN: u8>(clocks: &Clocks) -> HertzU32 {
+    let timer_div = timerx::<TN>().ctrl().read().presc().variant().unwrap();
+    clocks.hf_per_clk() / divider_value(timer_div)
+}
+
+/// All the timer prescaler values, in ascending order
+const TIMER_DIVIDERS: [TimerDivider; 11] = [
+    TimerDivider::Div1,
+    TimerDivider::Div2,
+    TimerDivider::Div4,
+    TimerDivider::Div8,
+    TimerDivider::Div16,
+    TimerDivider::Div32,
+    TimerDivider::Div64,
+    TimerDivider::Div128,
+    TimerDivider::Div256,
+    TimerDivider::Div512,
+    TimerDivider::Div1024,
+];
+
+/// Division factor of a timer prescaler value (the `PRESC` field holds the base 2 logarithm of the division factor)
+fn divider_value(div: TimerDivider) -> u32 {
+    let log2: u8 = div.into();
+    1 << log2
+}
+
 /// Timer
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Timer<const TN: u8> {}
 
 impl<const TN: u8> Timer<TN> {
+    /// The counter `TOP` value is set to `u16::MAX - 1` (see [`Timer::set_top()`] to change it).
+    ///
     /// FIXME: take a (timer counter) frequency as parameter and do a best effort to set the timer prescaler and the
     ///        `top` value to get as close as possible
     fn new(clock_divider: TimerDivider) -> Self {
         let timer = timerx::<TN>();
 
+        // enable Timer<TN> peripheral clock, so that the timer registers can be used before it is split into channels
+        gates::enable(PeripheralClock::timer(TN));
+
         timer.ctrl().write(|w| {
             w.presc()
                 .variant(clock_divider)
@@ -72,6 +154,119 @@ impl<const TN: u8> Timer<TN> {
         Self {}
     }
 
+    /// Get the frequency at which the timer counter is incremented, based on the `HFPERCLK` frequency and the
+    /// [`TimerDivider`] used to create this timer
+    pub fn tick_frequency(&self, clocks: &Clocks) -> HertzU32 {
+        tick_frequency::<TN>(clocks)
+    }
+
+    /// Handle the compare match interrupts. Must be called from the `TIMER0`/`TIMER1` interrupt handler when
+    /// [`TimerChannelCompare::on_match()`] is used.
+    ///
+    /// The handler of each channel whose interrupt is enabled and flagged is called, after its interrupt flag is
+    /// cleared (`IFC`), so that the handler can raise it again. The channels without a handler are left untouched, and
+    /// the delays of the same timer never enable their interrupt (see [`TimerChannel::into_delay()`]), so they do not
+    /// raise the `TIMERn` interrupt.
+    pub fn on_compare_interrupt() {
+        let timer = timerx::<TN>();
+
+        let handlers = critical_section::with(|cs| COMPARE_HANDLERS.borrow(cs).borrow()[TN as usize]);
+        let flags = timer.ifl().read();
+        let ien = timer.ien().read();
+        let pending = [
+            flags.cc0().bit_is_set() && ien.cc0().bit_is_set(),
+            flags.cc1().bit_is_set() && ien.cc1().bit_is_set(),
+            flags.cc2().bit_is_set() && ien.cc2().bit_is_set(),
+            flags.cc3().bit_is_set() && ien.cc3().bit_is_set(),
+        ];
+
+        for (cn, handler) in handlers.iter().enumerate() {
+            if let (true, Some(handler)) = (pending[cn], handler) {
+                match cn {
+                    0 => timer.ifc().write(|w| w.cc0().set_bit()),
+                    1 => timer.ifc().write(|w| w.cc1().set_bit()),
+                    2 => timer.ifc().write(|w| w.cc2().set_bit()),
+                    3 => timer.ifc().write(|w| w.cc3().set_bit()),
+                    _ => unreachable!(),
+                };
+
+                handler(cn as u8);
+            }
+        }
+    }
+
+    /// Select the timer prescaler which gives the highest tick frequency not above `target`, and return the timer
+    /// along with the actual tick frequency.
+    ///
+    /// If `target` is lower than the tick frequency with the largest divider ([`TimerDivider::Div1024`]), that
+    /// divider is used, so the returned frequency is above `target`.
+    ///
+    /// ```rust,no_run
+    /// let (timer, tick_freq) = p.timer0.into_timer(TimerDivider::Div1).with_frequency(1.MHz(), &clocks);
+    /// ```
+    pub fn with_frequency(self, target: HertzU32, clocks: &Clocks) -> (Self, HertzU32) {
+        let hf_per_clk = clocks.hf_per_clk();
+
+        // the dividers are sorted in ascending order, so the first one which is slow enough is the closest to `target`
+        let divider = TIMER_DIVIDERS
+            .iter()
+            .copied()
+            .find(|div| hf_per_clk / divider_value(*div) <= target)
+            .unwrap_or(TimerDivider::Div1024);
+
+        timerx::<TN>().ctrl().modify(|_, w| w.presc().variant(divider));
+
+        (self, hf_per_clk / divider_value(divider))
+    }
+
+    /// Get the current value of the timer counter (`CNT`)
+    pub fn counter(&self) -> u16 {
+        timerx::<TN>().cnt().read().cnt().bits()
+    }
+
+    /// Set the value of the timer counter (`CNT`)
+    pub fn set_counter(&mut self, value: u16) {
+        timerx::<TN>().cnt().write(|w| unsafe { w.cnt().bits(value) });
+    }
+
+    /// Reset the timer counter (`CNT`) to `0`
+    pub fn reset_counter(&mut self) {
+        self.set_counter(0);
+    }
+
+    /// Get the counter `TOP` value
+    pub fn top(&self) -> u16 {
+        timerx::<TN>().top().read().top().bits()
+    }
+
+    /// Set the counter `TOP` value, and return the value which was actually set.
+    ///
+    /// The counter wraps around after reaching `TOP`, so a smaller `TOP` gives a higher PWM frequency (i.e. the tick
+    /// frequency divided by `TOP + 1`) but a lower PWM resolution, since the duty cycle range is `0..=TOP + 1`.
+    ///
+    /// A 100% duty cycle is obtained by setting the channel Capture/Compare value to `TOP + 1`, so `TOP` is capped at
+    /// `u16::MAX - 1`.
+    pub fn set_top(&mut self, top: u16) -> u16 {
+        let top = top.min(u16::MAX - 1);
+        timerx::<TN>().top().write(|w| unsafe { w.top().bits(top) });
+        top
+    }
+
+    /// Start the timer counter (`CMD.START`)
+    pub fn start(&mut self) {
+        timerx::<TN>().cmd().write(|w| w.start().set_bit());
+    }
+
+    /// Stop the timer counter (`CMD.STOP`)
+    pub fn stop(&mut self) {
+        timerx::<TN>().cmd().write(|w| w.stop().set_bit());
+    }
+
+    /// Check if the timer counter is running (`STATUS.RUNNING`)
+    pub fn is_running(&self) -> bool {
+        timerx::<TN>().status().read().running().bit_is_set()
+    }
+
     /// Split the timer into channels which may be specialised for various uses (delay, pwm, etc.)
     pub fn into_channels(
         self,
@@ -81,32 +276,587 @@ impl<const TN: u8> Timer<TN> {
         TimerChannel<TN, 2>,
         TimerChannel<TN, 3>,
     ) {
-        // enable Timer<TN> peripheral clock
-        match TN {
-            0 => unsafe {
-                Cmu::steal()
-                    .hfperclken0()
-                    .modify(|_, w| w.timer0().set_bit());
-            },
-            1 => unsafe {
-                Cmu::steal()
-                    .hfperclken0()
-                    .modify(|_, w| w.timer1().set_bit());
-            },
-            _ => unreachable!(),
-        }
+        let (_counter, ch0, ch1, ch2, ch3) = self.into_channels_with_counter();
+        (ch0, ch1, ch2, ch3)
+    }
 
+    /// Same as [`Timer::into_channels()`], but also return a [`TimerCounter`] handle which gives access to the timer
+    /// counter after the timer has been split into channels
+    pub fn into_channels_with_counter(
+        self,
+    ) -> (
+        TimerCounter<TN>,
+        TimerChannel<TN, 0>,
+        TimerChannel<TN, 1>,
+        TimerChannel<TN, 2>,
+        TimerChannel<TN, 3>,
+    ) {
         // Enable timer
         timerx::<TN>().cmd().write(|w| w.start().set_bit());
 
         // Split the peripheral into its channels
         (
+            TimerCounter {},
             TimerChannel {},
             TimerChannel {},
             TimerChannel {},
             TimerChannel {},
         )
     }
+
+    /// Convert the timer into a free running [`MonoTimer`]
+    pub fn into_mono_timer(self, clocks: &Clocks) -> MonoTimer<TN> {
+        MonoTimer::new(clocks)
+    }
+
+    /// Convert the timer into a [`PulseCounter`], which counts the rising edges on the given `pin`.
+    ///
+    /// The pin is routed to Capture/Compare channel 1, whose input clocks the timer counter (`CTRL.CLKSEL = CC1`), so
+    /// the [`TimerDivider`] used to create this timer is not used.
+    pub fn into_pulse_counter<PIN>(self, pin: PIN) -> PulseCounter<TN, PIN>
+    where
+        PIN: InputPin + TimerPin<1>,
+    {
+        PulseCounter::new(self, pin)
+    }
+
+    /// Convert the timer into a [`Tone`] generator, which outputs a square wave (50% duty cycle) at the frequency
+    /// closest to `freq` on the given `pin`, and return it along with the actual frequency.
+    ///
+    /// The pin is routed to Capture/Compare channel 0, which toggles its output each time the counter wraps around.
+    /// The timer prescaler and `TOP` are selected from `freq`, so the [`TimerDivider`] used to create this timer is
+    /// not used.
+    pub fn into_tone<PIN>(self, pin: PIN, freq: HertzU32, clocks: &Clocks) -> (Tone<TN, PIN>, HertzU32)
+    where
+        PIN: OutputPin + TimerPin<0>,
+    {
+        Tone::new(self, pin, freq, clocks)
+    }
+}
+
+impl Timer<0> {
+    /// Chain `Timer0` and `Timer1` into a [`Timer32`], where `Timer1` counts the overflows of `Timer0`.
+    ///
+    /// `Timer1` is clocked by the overflows of `Timer0` (`CTRL.CLKSEL = TIMEROUF`), so the [`TimerDivider`] given to
+    /// `Timer0` sets the tick frequency of the 32 bit counter, and the one given to `Timer1` is not used.
+    ///
+    /// ```rust,no_run
+    /// let timer0 = p.timer0.into_timer(TimerDivider::Div1024);
+    /// let timer1 = p.timer1.into_timer(TimerDivider::Div1);
+    /// let mut timer32 = Timer::chain(timer0, timer1);
+    ///
+    /// timer32.start();
+    /// let count: u32 = timer32.count();
+    /// ```
+    pub fn chain(timer0: Timer<0>, timer1: Timer<1>) -> Timer32 {
+        Timer32::new(timer0, timer1)
+    }
+}
+
+/// 32 bit timer, made of two chained 16 bit timers (see [`Timer::chain()`])
+///
+/// The lower half of the counter is the counter of `Timer0`, and the upper half is the counter of `Timer1`.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Timer32 {
+    _timer0: Timer<0>,
+    _timer1: Timer<1>,
+}
+
+impl Timer32 {
+    fn new(timer0: Timer<0>, timer1: Timer<1>) -> Self {
+        gates::enable(PeripheralClock::timer(0));
+        gates::enable(PeripheralClock::timer(1));
+
+        let tim0 = timerx::<0>();
+        let tim1 = timerx::<1>();
+
+        tim0.cmd().write(|w| w.stop().set_bit());
+        tim1.cmd().write(|w| w.stop().set_bit());
+
+        // Use the whole range of both counters, so that they can be simply concatenated
+        tim0.top().write(|w| unsafe { w.top().bits(u16::MAX) });
+        tim1.top().write(|w| unsafe { w.top().bits(u16::MAX) });
+
+        tim0.ctrl().modify(|_, w| w.mode().variant(ctrl::MODE::Up));
+        tim1.ctrl().write(|w| {
+            w.clksel().timerouf();
+            w.mode().variant(ctrl::MODE::Up)
+        });
+
+        tim0.cnt().write(|w| unsafe { w.cnt().bits(0) });
+        tim1.cnt().write(|w| unsafe { w.cnt().bits(0) });
+
+        Self {
+            _timer0: timer0,
+            _timer1: timer1,
+        }
+    }
+
+    /// Get the frequency at which the 32 bit counter is incremented, i.e. the tick frequency of `Timer0`
+    pub fn tick_frequency(&self, clocks: &Clocks) -> HertzU32 {
+        tick_frequency::<0>(clocks)
+    }
+
+    /// Get the current value of the 32 bit counter
+    pub fn count(&self) -> u32 {
+        let tim0 = timerx::<0>();
+        let tim1 = timerx::<1>();
+
+        // `Timer1` may be incremented between the two reads, in which case the lower half wrapped around and must be
+        // read again
+        loop {
+            let high = tim1.cnt().read().cnt().bits();
+            let low = tim0.cnt().read().cnt().bits();
+
+            if tim1.cnt().read().cnt().bits() == high {
+                return ((high as u32) << 16) | low as u32;
+            }
+        }
+    }
+
+    /// Set the value of the 32 bit counter
+    pub fn set_count(&mut self, value: u32) {
+        timerx::<0>().cnt().write(|w| unsafe { w.cnt().bits(value as u16) });
+        timerx::<1>()
+            .cnt()
+            .write(|w| unsafe { w.cnt().bits((value >> 16) as u16) });
+    }
+
+    /// Start the 32 bit counter. `Timer1` is started first, so that no overflow of `Timer0` is missed.
+    pub fn start(&mut self) {
+        timerx::<1>().cmd().write(|w| w.start().set_bit());
+        timerx::<0>().cmd().write(|w| w.start().set_bit());
+    }
+
+    /// Stop the 32 bit counter
+    pub fn stop(&mut self) {
+        timerx::<0>().cmd().write(|w| w.stop().set_bit());
+        timerx::<1>().cmd().write(|w| w.stop().set_bit());
+    }
+
+    /// Check if the 32 bit counter is running
+    pub fn is_running(&self) -> bool {
+        timerx::<0>().status().read().running().bit_is_set()
+    }
+}
+
+/// Counter of the pulses on a timer input pin (see [`Timer::into_pulse_counter()`])
+///
+/// Each rising edge on the pin increments the 16 bit counter, without any CPU involvement, e.g. for a flow meter or a
+/// Geiger counter:
+///
+/// ```rust,no_run
+/// let pin = gpio.pd14.into_mode::<InPd>();
+/// let mut pulses = p.timer1.into_timer(TimerDivider::Div1).into_pulse_counter(pin);
+///
+/// // ...
+/// let count = pulses.count();
+/// pulses.reset();
+/// ```
+///
+/// The input is synchronized to `HFPERCLK`, so pulses (and the gaps between them) must be longer than two `HFPERCLK`
+/// periods to be counted.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PulseCounter<const TN: u8, PIN> {
+    timer: Timer<TN>,
+    pin: PIN,
+}
+
+impl<const TN: u8, PIN> PulseCounter<TN, PIN>
+where
+    PIN: InputPin + TimerPin<1>,
+{
+    fn new(timer: Timer<TN>, pin: PIN) -> Self {
+        gates::enable(PeripheralClock::timer(TN));
+
+        let tim = timerx::<TN>();
+
+        tim.cmd().write(|w| w.stop().set_bit());
+
+        // Route the pin to the input of channel 1, and detect its rising edges
+        tim.routeloc0().modify(|_, w| unsafe { w.cc1loc().bits(pin.loc()) });
+        tim.cc1_ctrl().write(|w| {
+            w.icedge().variant(cc1_ctrl::ICEDGE::Rising);
+            w.mode().variant(cc1_ctrl::MODE::Inputcapture)
+        });
+        tim.routepen().modify(|_, w| w.cc1pen().set_bit());
+
+        // Count the edges over the whole range of the counter
+        tim.top().write(|w| unsafe { w.top().bits(u16::MAX) });
+        tim.ctrl().modify(|_, w| {
+            w.clksel().cc1();
+            w.mode().variant(ctrl::MODE::Up)
+        });
+        tim.cnt().write(|w| unsafe { w.cnt().bits(0) });
+
+        tim.cmd().write(|w| w.start().set_bit());
+
+        Self { timer, pin }
+    }
+
+    /// Get the number of pulses counted since the last [`PulseCounter::reset()`]. The counter wraps around after
+    /// `u16::MAX` pulses.
+    pub fn count(&self) -> u16 {
+        self.timer.counter()
+    }
+
+    /// Reset the pulse count to `0`
+    pub fn reset(&mut self) {
+        self.timer.reset_counter();
+    }
+
+    /// Stop counting, and release the timer and the pin. The timer is clocked from `HFPERCLK` again.
+    pub fn free(self) -> (Timer<TN>, PIN) {
+        let tim = timerx::<TN>();
+
+        tim.cmd().write(|w| w.stop().set_bit());
+        tim.routepen().modify(|_, w| w.cc1pen().clear_bit());
+        tim.cc1_ctrl().reset();
+        tim.ctrl().modify(|_, w| w.clksel().preschfperclk());
+
+        (self.timer, self.pin)
+    }
+}
+
+/// Square wave generator, e.g. for a piezo buzzer (see [`Timer::into_tone()`])
+///
+/// ```rust,no_run
+/// let pin = gpio.pd13.into_mode::<OutPp>();
+/// let (mut buzzer, _) = p.timer0.into_timer(TimerDivider::Div1).into_tone(pin, 440.Hz(), &clocks);
+///
+/// delay.delay_ms(500);
+/// buzzer.set_frequency(880.Hz(), &clocks);
+/// delay.delay_ms(500);
+/// buzzer.mute().unwrap();
+/// ```
+///
+/// The output toggles once per counter period, so the lowest frequency is `HFPERCLK / (1024 * 2 * 65536)` (about
+/// 0.14 Hz at 19 MHz) and the highest one is `HFPERCLK / 2`. The closer the frequency is to the highest one, the
+/// coarser the available frequencies are.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Tone<const TN: u8, PIN> {
+    timer: Timer<TN>,
+    pin: PIN,
+    frequency: HertzU32,
+}
+
+impl<const TN: u8, PIN> Tone<TN, PIN>
+where
+    PIN: OutputPin + TimerPin<0>,
+{
+    fn new(timer: Timer<TN>, pin: PIN, freq: HertzU32, clocks: &Clocks) -> (Self, HertzU32) {
+        gates::enable(PeripheralClock::timer(TN));
+
+        let tim = timerx::<TN>();
+
+        tim.cmd().write(|w| w.stop().set_bit());
+
+        // Toggle the output of channel 0 when the counter matches `0`, i.e. once per period
+        tim.routeloc0().modify(|_, w| unsafe { w.cc0loc().bits(pin.loc()) });
+        tim.cc0_ctrl().write(|w| {
+            w.cmoa().variant(cc0_ctrl::CMOA::Toggle);
+            w.mode().variant(cc0_ctrl::MODE::Outputcompare)
+        });
+        tim.cc0_ccv().write(|w| unsafe { w.ccv().bits(0) });
+        tim.routepen().modify(|_, w| w.cc0pen().set_bit());
+
+        let mut tone = Self {
+            timer,
+            pin,
+            frequency: HertzU32::from_raw(0),
+        };
+        let frequency = tone.set_frequency(freq, clocks);
+
+        (tone, frequency)
+    }
+
+    /// Change the frequency of the square wave, and return the actual frequency (see [`Tone::frequency()`]). The
+    /// output is unmuted.
+    ///
+    /// The counter is restarted, so the current half period of the square wave is cut short.
+    pub fn set_frequency(&mut self, freq: HertzU32, clocks: &Clocks) -> HertzU32 {
+        let hf_per_clk = clocks.hf_per_clk().raw() as u64;
+        // The output toggles twice per period of the square wave
+        let toggle_freq = (2 * freq.raw() as u64).max(1);
+
+        // The smallest divider which fits the half period in the 16 bit counter gives the best resolution
+        let (divider, ticks) = TIMER_DIVIDERS
+            .iter()
+            .map(|&div| {
+                let div_value = divider_value(div) as u64;
+                let ticks = (hf_per_clk + div_value * toggle_freq / 2) / (div_value * toggle_freq);
+                (div, ticks.max(1))
+            })
+            .find(|&(_, ticks)| ticks <= 1 << 16)
+            .unwrap_or((TimerDivider::Div1024, 1 << 16));
+
+        let tim = timerx::<TN>();
+
+        tim.cmd().write(|w| w.stop().set_bit());
+        tim.ctrl().modify(|_, w| {
+            w.presc().variant(divider);
+            w.mode().variant(ctrl::MODE::Up)
+        });
+        tim.top().write(|w| unsafe { w.top().bits((ticks - 1) as u16) });
+        tim.cnt().write(|w| unsafe { w.cnt().bits(0) });
+        tim.routepen().modify(|_, w| w.cc0pen().set_bit());
+        tim.cmd().write(|w| w.start().set_bit());
+
+        let half_period = divider_value(divider) as u64 * ticks;
+        self.frequency = HertzU32::from_raw((hf_per_clk / (2 * half_period)) as u32);
+        self.frequency
+    }
+
+    /// Get the frequency of the square wave, as set by [`Tone::set_frequency()`] (rounded down to the nearest Hz)
+    pub fn frequency(&self) -> HertzU32 {
+        self.frequency
+    }
+
+    /// Stop the square wave, and drive the pin low, so that no DC voltage is left on e.g. a piezo buzzer. The square
+    /// wave is restarted by [`Tone::unmute()`] or [`Tone::set_frequency()`].
+    pub fn mute(&mut self) -> Result<(), PIN::Error> {
+        let tim = timerx::<TN>();
+
+        tim.cmd().write(|w| w.stop().set_bit());
+        // The pin is driven by its `DOUT` bit again
+        tim.routepen().modify(|_, w| w.cc0pen().clear_bit());
+
+        self.pin.set_low()
+    }
+
+    /// Restart the square wave after [`Tone::mute()`], at the same frequency
+    pub fn unmute(&mut self) {
+        let tim = timerx::<TN>();
+
+        tim.cnt().write(|w| unsafe { w.cnt().bits(0) });
+        tim.routepen().modify(|_, w| w.cc0pen().set_bit());
+        tim.cmd().write(|w| w.start().set_bit());
+    }
+
+    /// Check if the square wave is muted
+    pub fn is_muted(&self) -> bool {
+        !self.timer.is_running()
+    }
+
+    /// Stop the square wave, and release the timer and the pin. The counter `TOP` value is set back to
+    /// `u16::MAX - 1`, like for a new [`Timer`].
+    pub fn free(self) -> (Timer<TN>, PIN) {
+        let tim = timerx::<TN>();
+
+        tim.cmd().write(|w| w.stop().set_bit());
+        tim.routepen().modify(|_, w| w.cc0pen().clear_bit());
+        tim.cc0_ctrl().reset();
+        tim.top().write(|w| unsafe { w.top().bits(u16::MAX - 1) });
+
+        (self.timer, self.pin)
+    }
+}
+
+/// Number of times the counter of each timer overflowed, while used as a [`MonoTimer`]
+static MONO_OVF_COUNT: [AtomicU32; 2] = [AtomicU32::new(0), AtomicU32::new(0)];
+
+/// Monotonic clock, built on a free running timer
+///
+/// The 16 bit timer counter (`CNT`) is extended with an overflow counter, which is incremented in the timer interrupt.
+/// The HAL does not define the timer interrupt handler, so [`MonoTimer::on_interrupt()`] must be called from it:
+///
+/// ```rust,no_run
+/// let mono = p.timer1.into_timer(TimerDivider::Div16).into_mono_timer(&clocks);
+///
+/// let t0 = mono.now();
+/// // ...
+/// let elapsed = mono.elapsed_since(t0);
+///
+/// #[interrupt]
+/// fn TIMER1() {
+///     MonoTimer::<1>::on_interrupt();
+/// }
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MonoTimer<const TN: u8> {
+    tick_freq: HertzU32,
+}
+
+impl<const TN: u8> MonoTimer<TN> {
+    fn new(clocks: &Clocks) -> Self {
+        let timer = timerx::<TN>();
+
+        gates::enable(PeripheralClock::timer(TN));
+
+        // Use the whole counter range, so that the counter and the overflow count can be simply concatenated
+        timer.top().write(|w| unsafe { w.top().bits(u16::MAX) });
+        timer.cnt().write(|w| unsafe { w.cnt().bits(0) });
+        MONO_OVF_COUNT[TN as usize].store(0, Ordering::SeqCst);
+
+        // Enable the overflow interrupt
+        timer.ifc().write(|w| w.of().set_bit());
+        modify_ien::<TN>(|w| w.of().set_bit());
+        unsafe {
+            match TN {
+                0 => NVIC::unmask(Interrupt::TIMER0),
+                1 => NVIC::unmask(Interrupt::TIMER1),
+                _ => unreachable!(),
+            }
+        }
+
+        // Start timer
+        timer.cmd().write(|w| w.start().set_bit());
+
+        Self {
+            tick_freq: tick_frequency::<TN>(clocks),
+        }
+    }
+
+    /// Handle the timer interrupt. Must be called from the `TIMER0`/`TIMER1` interrupt handler.
+    pub fn on_interrupt() {
+        let timer = timerx::<TN>();
+
+        if timer.ifl().read().of().bit_is_set() {
+            timer.ifc().write(|w| w.of().set_bit());
+            MONO_OVF_COUNT[TN as usize].fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Get the frequency at which the timer counter is incremented
+    pub fn tick_frequency(&self) -> HertzU32 {
+        self.tick_freq
+    }
+
+    /// Get the number of timer ticks since this monotonic timer was created
+    pub fn ticks(&self) -> u64 {
+        let timer = timerx::<TN>();
+
+        critical_section::with(|_cs| {
+            let mut ovf = MONO_OVF_COUNT[TN as usize].load(Ordering::SeqCst);
+            let mut counter = timer.cnt().read().cnt().bits();
+
+            // The overflow interrupt may be pending (i.e. not yet handled), in which case the counter must be read
+            // again, since it may have wrapped around after the first read
+            if timer.ifl().read().of().bit_is_set() {
+                counter = timer.cnt().read().cnt().bits();
+                ovf += 1;
+            }
+
+            (ovf as u64) << u16::BITS | counter as u64
+        })
+    }
+
+    /// Get the number of microseconds since this monotonic timer was created
+    pub fn now(&self) -> u64 {
+        let ticks = self.ticks();
+        let freq = self.tick_freq.raw() as u64;
+
+        // Split the conversion in order to avoid overflowing `u64`
+        (ticks / freq) * 1_000_000 + (ticks % freq) * 1_000_000 / freq
+    }
+
+    /// Get the time elapsed since the given `t0`, which was obtained with [`MonoTimer::now()`]
+    pub fn elapsed_since(&self, t0: u64) -> MicrosDurationU64 {
+        MicrosDurationU64::micros(self.now().saturating_sub(t0))
+    }
+
+    /// Check if the given `timeout` has elapsed since `t0`, which was obtained with [`MonoTimer::now()`]
+    pub fn has_elapsed(&self, t0: u64, timeout: MicrosDurationU64) -> bool {
+        self.elapsed_since(t0) >= timeout
+    }
+
+    /// Stop the timer and disable its interrupt
+    pub fn free(self) -> Timer<TN> {
+        let timer = timerx::<TN>();
+
+        timer.cmd().write(|w| w.stop().set_bit());
+        modify_ien::<TN>(|w| w.of().clear_bit());
+        timer.ifc().write(|w| w.of().set_bit());
+        match TN {
+            0 => NVIC::mask(Interrupt::TIMER0),
+            1 => NVIC::mask(Interrupt::TIMER1),
+            _ => unreachable!(),
+        }
+
+        // Restore the `TOP` value used by `Timer`
+        timer.top().write(|w| unsafe { w.top().bits(u16::MAX - 1) });
+
+        Timer {}
+    }
+}
+
+/// Timer counter handle, which coexists with the timer channels (see [`Timer::into_channels_with_counter()`])
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimerCounter<const TN: u8> {}
+
+impl<const TN: u8> TimerCounter<TN> {
+    /// Get the frequency at which the timer counter is incremented
+    pub fn tick_frequency(&self, clocks: &Clocks) -> HertzU32 {
+        tick_frequency::<TN>(clocks)
+    }
+
+    /// Get the current value of the timer counter (`CNT`)
+    pub fn counter(&self) -> u16 {
+        timerx::<TN>().cnt().read().cnt().bits()
+    }
+
+    /// Set the value of the timer counter (`CNT`)
+    ///
+    /// Note: this affects all the channels of this timer (e.g. PWM channels and delays which are in progress)
+    pub fn set_counter(&mut self, value: u16) {
+        timerx::<TN>().cnt().write(|w| unsafe { w.cnt().bits(value) });
+    }
+
+    /// Reset the timer counter (`CNT`) to `0`
+    ///
+    /// Note: this affects all the channels of this timer (e.g. PWM channels and delays which are in progress)
+    pub fn reset_counter(&mut self) {
+        self.set_counter(0);
+    }
+
+    /// Get the counter `TOP` value
+    pub fn top(&self) -> u16 {
+        timerx::<TN>().top().read().top().bits()
+    }
+
+    /// Set the counter `TOP` value, and return the value which was actually set.
+    ///
+    /// The counter wraps around after reaching `TOP`, so a smaller `TOP` gives a higher PWM frequency (i.e. the tick
+    /// frequency divided by `TOP + 1`) but a lower PWM resolution, since the duty cycle range is `0..=TOP + 1`.
+    ///
+    /// A 100% duty cycle is obtained by setting the channel Capture/Compare value to `TOP + 1`, so `TOP` is capped at
+    /// `u16::MAX - 1`.
+    ///
+    /// Note: this affects all the channels of this timer (e.g. PWM channels and delays which are in progress)
+    pub fn set_top(&mut self, top: u16) -> u16 {
+        let top = top.min(u16::MAX - 1);
+        timerx::<TN>().top().write(|w| unsafe { w.top().bits(top) });
+        top
+    }
+
+    /// Start the timer counter (`CMD.START`)
+    pub fn start(&mut self) {
+        timerx::<TN>().cmd().write(|w| w.start().set_bit());
+    }
+
+    /// Stop the timer counter (`CMD.STOP`)
+    ///
+    /// Note: this affects all the channels of this timer (e.g. PWM channels and delays which are in progress)
+    pub fn stop(&mut self) {
+        timerx::<TN>().cmd().write(|w| w.stop().set_bit());
+    }
+
+    /// Check if the timer counter is running (`STATUS.RUNNING`)
+    pub fn is_running(&self) -> bool {
+        timerx::<TN>().status().read().running().bit_is_set()
+    }
+
+    /// Get the channels of this timer which are currently used as a [`TimerChannelDelay`] (bit `n` is set for channel
+    /// `n`). These depend on the counter `TOP` value, and on the counter running (see the
+    /// [module level documentation](crate::timer#sharing-a-timer-between-channels)).
+    pub fn delay_channels(&self) -> u8 {
+        DELAY_CHANNELS[TN as usize].load(Ordering::SeqCst)
+    }
 }
 
 /// Timer channel
@@ -126,7 +876,7 @@ impl<const TN: u8, const CN: u8> TimerChannel<TN, CN> {
             0 => {
                 timer
                     .routeloc0()
-                    .write(|w| unsafe { w.cc0loc().bits(pin.loc()) });
+                    .modify(|_, w| unsafe { w.cc0loc().bits(pin.loc()) });
                 timer.cc0_ctrl().write(|w| {
                     w.icedge().variant(cc0_ctrl::ICEDGE::Both);
                     w.cmoa().variant(cc0_ctrl::CMOA::Toggle);
@@ -137,7 +887,7 @@ impl<const TN: u8, const CN: u8> TimerChannel<TN, CN> {
             1 => {
                 timer
                     .routeloc0()
-                    .write(|w| unsafe { w.cc1loc().bits(pin.loc()) });
+                    .modify(|_, w| unsafe { w.cc1loc().bits(pin.loc()) });
                 timer.cc1_ctrl().write(|w| {
                     w.icedge().variant(cc1_ctrl::ICEDGE::Both);
                     w.cmoa().variant(cc1_ctrl::CMOA::Toggle);
@@ -148,7 +898,7 @@ impl<const TN: u8, const CN: u8> TimerChannel<TN, CN> {
             2 => {
                 timer
                     .routeloc0()
-                    .write(|w| unsafe { w.cc2loc().bits(pin.loc()) });
+                    .modify(|_, w| unsafe { w.cc2loc().bits(pin.loc()) });
                 timer.cc2_ctrl().write(|w| {
                     w.icedge().variant(cc2_ctrl::ICEDGE::Both);
                     w.cmoa().variant(cc2_ctrl::CMOA::Toggle);
@@ -159,7 +909,7 @@ impl<const TN: u8, const CN: u8> TimerChannel<TN, CN> {
             3 => {
                 timer
                     .routeloc0()
-                    .write(|w| unsafe { w.cc3loc().bits(pin.loc()) });
+                    .modify(|_, w| unsafe { w.cc3loc().bits(pin.loc()) });
                 timer.cc3_ctrl().write(|w| {
                     w.icedge().variant(cc3_ctrl::ICEDGE::Both);
                     w.cmoa().variant(cc3_ctrl::CMOA::Toggle);
@@ -175,30 +925,298 @@ impl<const TN: u8, const CN: u8> TimerChannel<TN, CN> {
         }
     }
 
-    /// Convert timer to a Delay
-    pub fn into_delay(self, clocks: &Clocks) -> TimerChannelDelay<TN, CN> {
+    /// Convert timer channel to an Output Compare channel, which applies the given `action` on its `pin` each time the
+    /// timer counter matches the channel compare value (see [`TimerChannelCompare::set_compare()`])
+    pub fn into_output_compare<PIN>(
+        self,
+        pin: PIN,
+        action: CompareAction,
+    ) -> TimerChannelCompare<TN, CN, PIN>
+    where
+        PIN: OutputPin + TimerPin<CN>,
+    {
         let timer = timerx::<TN>();
-        let timer_div: u8 = timer.ctrl().read().presc().variant().unwrap().into();
-        let timer_freq = clocks.hf_per_clk() / (timer_div + 1) as u32;
 
         match CN {
-            0 => timer
-                .cc0_ctrl()
-                .write(|w| w.mode().variant(cc0_ctrl::MODE::Outputcompare)),
-            1 => timer
-                .cc1_ctrl()
-                .write(|w| w.mode().variant(cc1_ctrl::MODE::Outputcompare)),
-            2 => timer
-                .cc2_ctrl()
-                .write(|w| w.mode().variant(cc2_ctrl::MODE::Outputcompare)),
-            3 => timer
+            0 => {
+                timer
+                    .routeloc0()
+                    .modify(|_, w| unsafe { w.cc0loc().bits(pin.loc()) });
+                timer.cc0_ctrl().write(|w| {
+                    w.cmoa().bits(action as u8);
+                    w.mode().variant(cc0_ctrl::MODE::Outputcompare)
+                });
+                timer.routepen().modify(|_, w| w.cc0pen().set_bit());
+            }
+            1 => {
+                timer
+                    .routeloc0()
+                    .modify(|_, w| unsafe { w.cc1loc().bits(pin.loc()) });
+                timer.cc1_ctrl().write(|w| {
+                    w.cmoa().bits(action as u8);
+                    w.mode().variant(cc1_ctrl::MODE::Outputcompare)
+                });
+                timer.routepen().modify(|_, w| w.cc1pen().set_bit());
+            }
+            2 => {
+                timer
+                    .routeloc0()
+                    .modify(|_, w| unsafe { w.cc2loc().bits(pin.loc()) });
+                timer.cc2_ctrl().write(|w| {
+                    w.cmoa().bits(action as u8);
+                    w.mode().variant(cc2_ctrl::MODE::Outputcompare)
+                });
+                timer.routepen().modify(|_, w| w.cc2pen().set_bit());
+            }
+            3 => {
+                timer
+                    .routeloc0()
+                    .modify(|_, w| unsafe { w.cc3loc().bits(pin.loc()) });
+                timer.cc3_ctrl().write(|w| {
+                    w.cmoa().bits(action as u8);
+                    w.mode().variant(cc3_ctrl::MODE::Outputcompare)
+                });
+                timer.routepen().modify(|_, w| w.cc3pen().set_bit());
+            }
+            _ => unreachable!(),
+        }
+
+        TimerChannelCompare {
+            _compare_pin: PhantomData,
+        }
+    }
+
+    /// Convert timer to a Delay
+    ///
+    /// The delay polls the interrupt flag of this channel (`IF.CCx`) without enabling its interrupt, and its length
+    /// depends on the counter `TOP` value, which is shared with the other channels (see the
+    /// [module level documentation](crate::timer#sharing-a-timer-between-channels)).
+    pub fn into_delay(self, clocks: &Clocks) -> TimerChannelDelay<TN, CN> {
+        let timer = timerx::<TN>();
+        let timer_freq = tick_frequency::<TN>(clocks);
+
+        match CN {
+            0 => timer
+                .cc0_ctrl()
+                .write(|w| w.mode().variant(cc0_ctrl::MODE::Outputcompare)),
+            1 => timer
+                .cc1_ctrl()
+                .write(|w| w.mode().variant(cc1_ctrl::MODE::Outputcompare)),
+            2 => timer
+                .cc2_ctrl()
+                .write(|w| w.mode().variant(cc2_ctrl::MODE::Outputcompare)),
+            3 => timer
                 .cc3_ctrl()
                 .write(|w| w.mode().variant(cc3_ctrl::MODE::Outputcompare)),
             _ => unreachable!(),
         };
 
+        DELAY_CHANNELS[TN as usize].fetch_or(1 << CN, Ordering::SeqCst);
+
         TimerChannelDelay { timer_freq }
     }
+
+    /// Use the given PRS channel as the input of this timer channel, instead of the channel's pin
+    pub fn select_prs_input(&mut self, prs_ch: PrsChannelId) {
+        let timer = timerx::<TN>();
+
+        match CN {
+            0 => timer.cc0_ctrl().modify(|_, w| unsafe {
+                w.prssel().bits(prs_ch as u8);
+                w.insel().set_bit()
+            }),
+            1 => timer.cc1_ctrl().modify(|_, w| unsafe {
+                w.prssel().bits(prs_ch as u8);
+                w.insel().set_bit()
+            }),
+            2 => timer.cc2_ctrl().modify(|_, w| unsafe {
+                w.prssel().bits(prs_ch as u8);
+                w.insel().set_bit()
+            }),
+            3 => timer.cc3_ctrl().modify(|_, w| unsafe {
+                w.prssel().bits(prs_ch as u8);
+                w.insel().set_bit()
+            }),
+            _ => unreachable!(),
+        };
+    }
+
+    /// Generate a single pulse of `width_ns` nanoseconds on the given `pin`, and wait until it is complete.
+    ///
+    /// The pin is routed to this channel for the duration of the pulse, in Output Compare mode: a first compare match
+    /// sets the output, and a second one, `width_ns` later, clears it. Pulses longer than half a timer period are
+    /// split into several compare steps, so any width can be generated. The pin is driven low before the pulse, and it
+    /// returns to this (idle) state when the routing is disabled at the end.
+    ///
+    /// The timer must be running. Pulses shorter than the time needed to program the second compare value (a few tens
+    /// of timer ticks, depending on the timer prescaler and the core clock) are stretched to that minimum width. A
+    /// pulse is never cut short: if the CPU is held up (e.g. by an interrupt) past one of the compare steps, the
+    /// remaining steps are counted from the current counter value (so the pulse is extended by the delay), and if the
+    /// last step is missed, the pulse is ended as soon as this is detected.
+    pub fn pulse<PIN>(&mut self, pin: &mut PIN, width_ns: u32, clocks: &Clocks)
+    where
+        PIN: OutputPin + TimerPin<CN>,
+    {
+        /// Timer ticks between programming the compare value and the start of the pulse
+        const PULSE_START_TICKS: u32 = 32;
+
+        let timer = timerx::<TN>();
+        let period = timer.top().read().top().bits() as u32 + 1;
+        let max_step = (period / 2).max(1);
+
+        let mut ticks_left = (tick_frequency::<TN>(clocks).raw() as u64 * width_ns as u64)
+            .div_ceil(1_000_000_000_u64)
+            .clamp(1, u32::MAX as u64) as u32;
+
+        // idle level, once the pin is not routed to the timer anymore
+        let _ = pin.set_low();
+
+        Self::set_compare_mode(CompareAction::None);
+        Self::set_routing(pin.loc(), true);
+
+        // Rising edge
+        loop {
+            let start = timer.cnt().read().cnt().bits() as u32;
+            if Self::compare_in(start, PULSE_START_TICKS.min(max_step), period, CompareAction::Set) {
+                break;
+            }
+        }
+
+        // Falling edge, possibly after a few intermediate compare matches which don't change the output
+        let mut reference = Self::read_compare();
+        while ticks_left > 0 {
+            let step = ticks_left.min(max_step);
+            ticks_left -= step;
+
+            let action = match ticks_left {
+                0 => CompareAction::Clear,
+                _ => CompareAction::None,
+            };
+
+            match (Self::compare_in(reference, step, period, action), ticks_left) {
+                (true, _) => reference = (reference + step) % period,
+                // The CPU was held up (e.g. by an interrupt) past an intermediate compare value: the remaining ticks
+                // are counted from the current counter value, so that the pulse is extended instead of cut short
+                (false, 1..) => reference = timer.cnt().read().cnt().bits() as u32,
+                // The last compare value was already passed, so the pulse is at least `width_ns` long: it is ended
+                // right away, when the routing is disabled below
+                (false, 0) => break,
+            }
+        }
+
+        Self::set_routing(pin.loc(), false);
+        Self::set_compare_mode(CompareAction::None);
+    }
+
+    /// Set the channel in Output Compare mode, with the given `action`
+    fn set_compare_mode(action: CompareAction) {
+        let timer = timerx::<TN>();
+
+        match CN {
+            0 => timer.cc0_ctrl().write(|w| {
+                w.cmoa().bits(action as u8);
+                w.mode().variant(cc0_ctrl::MODE::Outputcompare)
+            }),
+            1 => timer.cc1_ctrl().write(|w| {
+                w.cmoa().bits(action as u8);
+                w.mode().variant(cc1_ctrl::MODE::Outputcompare)
+            }),
+            2 => timer.cc2_ctrl().write(|w| {
+                w.cmoa().bits(action as u8);
+                w.mode().variant(cc2_ctrl::MODE::Outputcompare)
+            }),
+            3 => timer.cc3_ctrl().write(|w| {
+                w.cmoa().bits(action as u8);
+                w.mode().variant(cc3_ctrl::MODE::Outputcompare)
+            }),
+            _ => unreachable!(),
+        };
+    }
+
+    /// Route the channel output to the pin at location `loc`, or disable the routing
+    fn set_routing(loc: u8, enabled: bool) {
+        let timer = timerx::<TN>();
+
+        match CN {
+            0 => {
+                timer.routeloc0().modify(|_, w| unsafe { w.cc0loc().bits(loc) });
+                timer.routepen().modify(|_, w| w.cc0pen().bit(enabled));
+            }
+            1 => {
+                timer.routeloc0().modify(|_, w| unsafe { w.cc1loc().bits(loc) });
+                timer.routepen().modify(|_, w| w.cc1pen().bit(enabled));
+            }
+            2 => {
+                timer.routeloc0().modify(|_, w| unsafe { w.cc2loc().bits(loc) });
+                timer.routepen().modify(|_, w| w.cc2pen().bit(enabled));
+            }
+            3 => {
+                timer.routeloc0().modify(|_, w| unsafe { w.cc3loc().bits(loc) });
+                timer.routepen().modify(|_, w| w.cc3pen().bit(enabled));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Get the compare value of the channel
+    fn read_compare() -> u32 {
+        let timer = timerx::<TN>();
+
+        match CN {
+            0 => timer.cc0_ccv().read().ccv().bits() as u32,
+            1 => timer.cc1_ccv().read().ccv().bits() as u32,
+            2 => timer.cc2_ccv().read().ccv().bits() as u32,
+            3 => timer.cc3_ccv().read().ccv().bits() as u32,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Apply `action` on the compare match `step` ticks after the `reference` counter value, and wait for the match.
+    ///
+    /// Returns `false` (without waiting) if the counter had already passed the compare value once it was programmed.
+    fn compare_in(reference: u32, step: u32, period: u32, action: CompareAction) -> bool {
+        let timer = timerx::<TN>();
+        let compare = (reference + step) % period;
+
+        match CN {
+            0 => {
+                timer.cc0_ctrl().modify(|_, w| w.cmoa().bits(action as u8));
+                timer.cc0_ccv().write(|w| unsafe { w.ccv().bits(compare as u16) });
+                timer.ifc().write(|w| w.cc0().set_bit());
+            }
+            1 => {
+                timer.cc1_ctrl().modify(|_, w| w.cmoa().bits(action as u8));
+                timer.cc1_ccv().write(|w| unsafe { w.ccv().bits(compare as u16) });
+                timer.ifc().write(|w| w.cc1().set_bit());
+            }
+            2 => {
+                timer.cc2_ctrl().modify(|_, w| w.cmoa().bits(action as u8));
+                timer.cc2_ccv().write(|w| unsafe { w.ccv().bits(compare as u16) });
+                timer.ifc().write(|w| w.cc2().set_bit());
+            }
+            3 => {
+                timer.cc3_ctrl().modify(|_, w| w.cmoa().bits(action as u8));
+                timer.cc3_ccv().write(|w| unsafe { w.ccv().bits(compare as u16) });
+                timer.ifc().write(|w| w.cc3().set_bit());
+            }
+            _ => unreachable!(),
+        };
+
+        let elapsed = (timer.cnt().read().cnt().bits() as u32 + period - reference) % period;
+        if elapsed >= step {
+            return false;
+        }
+
+        match CN {
+            0 => while timer.ifl().read().cc0().bit_is_clear() {},
+            1 => while timer.ifl().read().cc1().bit_is_clear() {},
+            2 => while timer.ifl().read().cc2().bit_is_clear() {},
+            3 => while timer.ifl().read().cc3().bit_is_clear() {},
+            _ => unreachable!(),
+        }
+
+        true
+    }
 }
 
 /// Specialize the timer channel to be used for delays
@@ -208,97 +1226,397 @@ pub struct TimerChannelDelay<const TN: u8, const CN: u8> {
     timer_freq: HertzU32,
 }
 
+/// Channels which are used as a [`TimerChannelDelay`], for each timer (bit `n` is set for channel `n`)
+static DELAY_CHANNELS: [AtomicU8; 2] = [const { AtomicU8::new(0) }; 2];
+
+/// Cancellation flags of the delays in progress, for each timer and channel
+static DELAY_CANCEL: [[AtomicBool; 4]; 2] = [const { [const { AtomicBool::new(false) }; 4] }; 2];
+
+impl<const TN: u8, const CN: u8> TimerChannelDelay<TN, CN> {
+    /// Delays shorter than this number of timer ticks are done by polling the timer counter instead of using the
+    /// compare value of the channel
+    const SHORT_DELAY_TICKS: u32 = 64;
+
+    /// Get the timer tick frequency which is used to convert the delays into timer ticks
+    pub fn tick_frequency(&self) -> HertzU32 {
+        self.timer_freq
+    }
+
+    /// Refresh the timer tick frequency from the given `clocks` and the current timer prescaler.
+    ///
+    /// The tick frequency is calculated once by [`TimerChannel::into_delay()`], so this must be called after the
+    /// HF Peripheral Clock is changed (e.g. with [`Clocks::set_hf_per_prescaler()`]), otherwise the delays are scaled
+    /// by the ratio between the old and the new frequencies.
+    pub fn recalibrate(&mut self, clocks: &Clocks) {
+        self.timer_freq = tick_frequency::<TN>(clocks);
+    }
+
+    /// Release the timer channel, so that it can be specialised again
+    pub fn free(self) -> TimerChannel<TN, CN> {
+        let timer = timerx::<TN>();
+
+        match CN {
+            0 => timer.cc0_ctrl().reset(),
+            1 => timer.cc1_ctrl().reset(),
+            2 => timer.cc2_ctrl().reset(),
+            3 => timer.cc3_ctrl().reset(),
+            _ => unreachable!(),
+        };
+
+        DELAY_CHANNELS[TN as usize].fetch_and(!(1 << CN), Ordering::SeqCst);
+
+        TimerChannel {}
+    }
+
+    /// Abort the delay which is in progress on this timer channel, if any (e.g. from an interrupt handler, such as a
+    /// [`TimerChannelCompare::on_match()`] handler of another channel of the same timer).
+    ///
+    /// The cancellation flag of the channel is set, and its interrupt flag is raised, so that the wait loop of the
+    /// delay returns early. Only this channel is affected: the delays on the other channels of the timer keep waiting,
+    /// and nothing is done if the channel is not used as a delay. Delays shorter than a few timer ticks may not be
+    /// cancelled, since they only poll the timer counter.
+    pub fn cancel() {
+        let timer = timerx::<TN>();
+
+        // The channel may be used for something else (e.g. an alarm), whose interrupt flag must not be raised
+        if DELAY_CHANNELS[TN as usize].load(Ordering::SeqCst) & (1 << CN) == 0 {
+            return;
+        }
+
+        DELAY_CANCEL[TN as usize][CN as usize].store(true, Ordering::SeqCst);
+
+        match CN {
+            0 => timer.ifs().write(|w| w.cc0().set_bit()),
+            1 => timer.ifs().write(|w| w.cc1().set_bit()),
+            2 => timer.ifs().write(|w| w.cc2().set_bit()),
+            3 => timer.ifs().write(|w| w.cc3().set_bit()),
+            _ => unreachable!(),
+        }
+    }
+}
+
 impl<const TN: u8, const CN: u8> DelayNs for TimerChannelDelay<TN, CN> {
     fn delay_ns(&mut self, ns: u32) {
-        let microsecs = ns / 1000;
-
-        // FIXME: converting ns to us is just a band-aid in order to avoid the delay duration being smaller than this
-        //        code can handle. Worst case scenario is if the timer frequency is the same as the core frequency,
-        //        in which case wanting to wait for a few nanoseconds may take longer than expected because the code
-        //        below needs to calculate a Compare value which may have already elapsed by the time it's written to
-        //        the compare field of the CC channel.
-        //        A better accuracy may be obtained if we implement `DelayNs` for `Timer` instead of `TimerChannelDelay`
-        //        since we can control when the timer starts.
-        if microsecs > 0 {
-            let timer = timerx::<TN>();
-            let ticks_left = self.timer_freq.raw() as u64 * microsecs as u64 / 1_000_000_u64;
-            let reload_max = timer.top().read().top().bits() as u32;
-            let reference_count = timer.cnt().read().cnt().bits() as u32;
-
-            let mut ticks_left = ticks_left as u32;
-            let mut reload = ticks_left.min(reload_max);
-            let mut compare = (reference_count + reload) % reload_max;
-
-            while ticks_left > 0 {
-                match CN {
-                    0 => {
-                        // clear interrupt flag
-                        timer.ifc().write(|w| w.cc0().set_bit());
-
-                        // set compare
-                        timer
-                            .cc0_ccv()
-                            .write(|w| unsafe { w.ccv().bits(compare as u16) });
-
-                        // enable channel interrupt
-                        timer.ien().write(|w| w.cc0().set_bit());
-                    }
-                    1 => {
-                        // clear interrupt flag
-                        timer.ifc().write(|w| w.cc1().set_bit());
-
-                        // set compare
-                        timer
-                            .cc1_ccv()
-                            .write(|w| unsafe { w.ccv().bits(compare as u16) });
-
-                        // enable channel interrupt
-                        timer.ien().write(|w| w.cc1().set_bit());
-                    }
-                    2 => {
-                        // clear interrupt flag
-                        timer.ifc().write(|w| w.cc2().set_bit());
-
-                        // set compare
-                        timer
-                            .cc2_ccv()
-                            .write(|w| unsafe { w.ccv().bits(compare as u16) });
-
-                        // enable channel interrupt
-                        timer.ien().write(|w| w.cc2().set_bit());
-                    }
-                    3 => {
-                        // clear interrupt flag
-                        timer.ifc().write(|w| w.cc3().set_bit());
-
-                        // set compare
-                        timer
-                            .cc3_ccv()
-                            .write(|w| unsafe { w.ccv().bits(compare as u16) });
-
-                        // enable channel interrupt
-                        timer.ien().write(|w| w.cc3().set_bit());
-                    }
-                    _ => unreachable!(),
+        if ns == 0 {
+            return;
+        }
+
+        let timer = timerx::<TN>();
+        let reference_count = timer.cnt().read().cnt().bits() as u32;
+        let reload_max = timer.top().read().top().bits() as u32;
+        // The counter wraps around after `TOP`, so it counts `TOP + 1` ticks per period
+        let period = reload_max + 1;
+
+        // Round up, so that any non-zero delay waits for at least one timer tick
+        let ticks_left = (self.timer_freq.raw() as u64 * ns as u64)
+            .div_ceil(1_000_000_000_u64)
+            .clamp(1, u32::MAX as u64) as u32;
+
+        // Short delays may already have elapsed by the time a compare value is written to the CC channel, in which
+        // case we would end up waiting for a whole timer period. Poll the counter instead.
+        if ticks_left < Self::SHORT_DELAY_TICKS && ticks_left <= reload_max {
+            while (timer.cnt().read().cnt().bits() as u32 + period - reference_count) % period < ticks_left {}
+            return;
+        }
+
+        // Only delays which are in progress can be cancelled
+        DELAY_CANCEL[TN as usize][CN as usize].store(false, Ordering::SeqCst);
+
+        let mut ticks_left = ticks_left;
+        let mut reload = ticks_left.min(reload_max);
+        let mut compare = (reference_count + reload) % period;
+
+        while ticks_left > 0 {
+            match CN {
+                0 => {
+                    // clear interrupt flag
+                    timer.ifc().write(|w| w.cc0().set_bit());
+
+                    // set compare
+                    timer
+                        .cc0_ccv()
+                        .write(|w| unsafe { w.ccv().bits(compare as u16) });
                 }
+                1 => {
+                    // clear interrupt flag
+                    timer.ifc().write(|w| w.cc1().set_bit());
 
-                // calculate next loop's values _before_ waiting so that the jitter between loops is minimal
-                ticks_left -= reload;
-                reload = ticks_left.min(reload_max);
-                compare = (reference_count + reload) % reload_max;
+                    // set compare
+                    timer
+                        .cc1_ccv()
+                        .write(|w| unsafe { w.ccv().bits(compare as u16) });
+                }
+                2 => {
+                    // clear interrupt flag
+                    timer.ifc().write(|w| w.cc2().set_bit());
 
-                match CN {
-                    0 => while timer.ifl().read().cc0().bit_is_clear() {},
-                    1 => while timer.ifl().read().cc1().bit_is_clear() {},
-                    2 => while timer.ifl().read().cc2().bit_is_clear() {},
-                    3 => while timer.ifl().read().cc3().bit_is_clear() {},
-                    _ => unreachable!(),
+                    // set compare
+                    timer
+                        .cc2_ccv()
+                        .write(|w| unsafe { w.ccv().bits(compare as u16) });
+                }
+                3 => {
+                    // clear interrupt flag
+                    timer.ifc().write(|w| w.cc3().set_bit());
+
+                    // set compare
+                    timer
+                        .cc3_ccv()
+                        .write(|w| unsafe { w.ccv().bits(compare as u16) });
                 }
+                _ => unreachable!(),
+            }
+
+            // calculate next loop's values _before_ waiting so that the jitter between loops is minimal. The next
+            // compare value follows the current one, so that no tick is lost or added between loops
+            ticks_left -= reload;
+            reload = ticks_left.min(reload_max);
+            compare = (compare + reload) % period;
+
+            // The cancellation flag is also polled, since the interrupt flag raised by `cancel()` is lost if the
+            // interrupt comes right before it is cleared for the next loop
+            let cancelled = || DELAY_CANCEL[TN as usize][CN as usize].load(Ordering::SeqCst);
+
+            match CN {
+                0 => while timer.ifl().read().cc0().bit_is_clear() && !cancelled() {},
+                1 => while timer.ifl().read().cc1().bit_is_clear() && !cancelled() {},
+                2 => while timer.ifl().read().cc2().bit_is_clear() && !cancelled() {},
+                3 => while timer.ifl().read().cc3().bit_is_clear() && !cancelled() {},
+                _ => unreachable!(),
             }
+
+            if DELAY_CANCEL[TN as usize][CN as usize].swap(false, Ordering::SeqCst) {
+                break;
+            }
+        }
+
+        // The flag is only polled, so the channel interrupt is left disabled (it would otherwise be raised forever in
+        // a `TIMERn` handler unmasked by another channel, e.g. by `TimerChannelCompare::on_match()`)
+        match CN {
+            0 => {
+                modify_ien::<TN>(|w| w.cc0().clear_bit());
+                timer.ifc().write(|w| w.cc0().set_bit());
+            }
+            1 => {
+                modify_ien::<TN>(|w| w.cc1().clear_bit());
+                timer.ifc().write(|w| w.cc1().set_bit());
+            }
+            2 => {
+                modify_ien::<TN>(|w| w.cc2().clear_bit());
+                timer.ifc().write(|w| w.cc2().set_bit());
+            }
+            3 => {
+                modify_ien::<TN>(|w| w.cc3().clear_bit());
+                timer.ifc().write(|w| w.cc3().set_bit());
+            }
+            _ => unreachable!(),
         }
     }
 }
 
+/// Output action applied on the channel pin when the timer counter matches the compare value (`CMOA` field in
+/// `TIMERn_CCx_CTRL`)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CompareAction {
+    /// No action on compare match
+    None = 0,
+    /// Toggle output on compare match
+    Toggle = 1,
+    /// Clear output on compare match
+    Clear = 2,
+    /// Set output on compare match
+    Set = 3,
+}
+
+/// Output polarity of a PWM channel (`OUTINV` field in `TIMERn_CCx_CTRL`)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PwmPolarity {
+    /// The output is high for the duty cycle, and low for the rest of the period (default)
+    ActiveHigh,
+    /// The output is low for the duty cycle, and high for the rest of the period
+    ActiveLow,
+}
+
+/// Compare match handler (see [`TimerChannelCompare::on_match()`]), called with the channel number
+pub type CompareHandler = fn(u8);
+
+/// Compare match handlers, for each timer and channel
+static COMPARE_HANDLERS: Mutex<RefCell<[[Option<CompareHandler>; 4]; 2]>> = Mutex::new(RefCell::new([[None; 4]; 2]));
+
+/// Output Compare
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimerChannelCompare<const TN: u8, const CN: u8, PIN>
+where
+    PIN: OutputPin + TimerPin<CN>,
+{
+    _compare_pin: PhantomData<PIN>,
+}
+
+impl<const TN: u8, const CN: u8, PIN> TimerChannelCompare<TN, CN, PIN>
+where
+    PIN: OutputPin + TimerPin<CN>,
+{
+    /// Get the compare value of this channel
+    pub fn compare(&self) -> u16 {
+        let timer = timerx::<TN>();
+
+        match CN {
+            0 => timer.cc0_ccv().read().ccv().bits(),
+            1 => timer.cc1_ccv().read().ccv().bits(),
+            2 => timer.cc2_ccv().read().ccv().bits(),
+            3 => timer.cc3_ccv().read().ccv().bits(),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Set the compare value of this channel. The output action is applied when the timer counter reaches this value.
+    pub fn set_compare(&mut self, value: u16) {
+        let timer = timerx::<TN>();
+
+        match CN {
+            0 => timer.cc0_ccv().write(|w| unsafe { w.ccv().bits(value) }),
+            1 => timer.cc1_ccv().write(|w| unsafe { w.ccv().bits(value) }),
+            2 => timer.cc2_ccv().write(|w| unsafe { w.ccv().bits(value) }),
+            3 => timer.cc3_ccv().write(|w| unsafe { w.ccv().bits(value) }),
+            _ => unreachable!(),
+        };
+    }
+
+    /// Set the output action applied on compare match
+    pub fn set_action(&mut self, action: CompareAction) {
+        let timer = timerx::<TN>();
+
+        match CN {
+            0 => timer.cc0_ctrl().modify(|_, w| w.cmoa().bits(action as u8)),
+            1 => timer.cc1_ctrl().modify(|_, w| w.cmoa().bits(action as u8)),
+            2 => timer.cc2_ctrl().modify(|_, w| w.cmoa().bits(action as u8)),
+            3 => timer.cc3_ctrl().modify(|_, w| w.cmoa().bits(action as u8)),
+            _ => unreachable!(),
+        };
+    }
+
+    /// Check if a compare match occurred since the last call of this method (the channel interrupt flag is cleared)
+    pub fn is_matched(&mut self) -> bool {
+        let timer = timerx::<TN>();
+
+        let matched = match CN {
+            0 => timer.ifl().read().cc0().bit_is_set(),
+            1 => timer.ifl().read().cc1().bit_is_set(),
+            2 => timer.ifl().read().cc2().bit_is_set(),
+            3 => timer.ifl().read().cc3().bit_is_set(),
+            _ => unreachable!(),
+        };
+
+        if matched {
+            match CN {
+                0 => timer.ifc().write(|w| w.cc0().set_bit()),
+                1 => timer.ifc().write(|w| w.cc1().set_bit()),
+                2 => timer.ifc().write(|w| w.cc2().set_bit()),
+                3 => timer.ifc().write(|w| w.cc3().set_bit()),
+                _ => unreachable!(),
+            };
+        }
+
+        matched
+    }
+
+    /// Call `f` from the timer interrupt each time the timer counter matches `at`, i.e. once per timer period, until
+    /// [`TimerChannelCompare::cancel_match()`] is called. The interrupt handler must call
+    /// [`Timer::on_compare_interrupt()`].
+    ///
+    /// Each channel is an independent alarm, so a timer gives up to 4 alarms. `at` is an absolute counter value, which
+    /// is wrapped around the timer period (`TOP + 1`, see [`Timer::set_top()`]), since a compare value above `TOP`
+    /// would never match. The output action of the channel is applied as usual, so use [`CompareAction::None`] for an
+    /// alarm which does not drive its pin.
+    ///
+    /// ```rust,no_run
+    /// fn alarm(channel: u8) {
+    ///     // ...
+    /// }
+    ///
+    /// let mut ch0 = tim0ch0.into_output_compare(gpio.pd13.into_mode::<OutPp>(), CompareAction::None);
+    /// ch0.on_match(1_000, alarm);
+    ///
+    /// #[interrupt]
+    /// fn TIMER0() {
+    ///     Timer::<0>::on_compare_interrupt();
+    /// }
+    /// ```
+    pub fn on_match(&mut self, at: u16, f: CompareHandler) {
+        let timer = timerx::<TN>();
+
+        let period = timer.top().read().top().bits() as u32 + 1;
+        self.set_compare((at as u32 % period) as u16);
+
+        critical_section::with(|cs| {
+            COMPARE_HANDLERS.borrow(cs).borrow_mut()[TN as usize][CN as usize] = Some(f);
+        });
+
+        // A match which happened before the handler was set is not reported
+        match CN {
+            0 => {
+                timer.ifc().write(|w| w.cc0().set_bit());
+                modify_ien::<TN>(|w| w.cc0().set_bit());
+            }
+            1 => {
+                timer.ifc().write(|w| w.cc1().set_bit());
+                modify_ien::<TN>(|w| w.cc1().set_bit());
+            }
+            2 => {
+                timer.ifc().write(|w| w.cc2().set_bit());
+                modify_ien::<TN>(|w| w.cc2().set_bit());
+            }
+            3 => {
+                timer.ifc().write(|w| w.cc3().set_bit());
+                modify_ien::<TN>(|w| w.cc3().set_bit());
+            }
+            _ => unreachable!(),
+        }
+
+        unsafe {
+            match TN {
+                0 => NVIC::unmask(Interrupt::TIMER0),
+                1 => NVIC::unmask(Interrupt::TIMER1),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    /// Stop calling the handler set by [`TimerChannelCompare::on_match()`]. The channel interrupt is disabled, and the
+    /// compare value and output action are not changed.
+    pub fn cancel_match(&mut self) {
+        let timer = timerx::<TN>();
+
+        match CN {
+            0 => {
+                modify_ien::<TN>(|w| w.cc0().clear_bit());
+                timer.ifc().write(|w| w.cc0().set_bit());
+            }
+            1 => {
+                modify_ien::<TN>(|w| w.cc1().clear_bit());
+                timer.ifc().write(|w| w.cc1().set_bit());
+            }
+            2 => {
+                modify_ien::<TN>(|w| w.cc2().clear_bit());
+                timer.ifc().write(|w| w.cc2().set_bit());
+            }
+            3 => {
+                modify_ien::<TN>(|w| w.cc3().clear_bit());
+                timer.ifc().write(|w| w.cc3().set_bit());
+            }
+            _ => unreachable!(),
+        }
+
+        critical_section::with(|cs| {
+            COMPARE_HANDLERS.borrow(cs).borrow_mut()[TN as usize][CN as usize] = None;
+        });
+    }
+}
+
 /// PWM
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -309,6 +1627,146 @@ where
     _pwm_pin: PhantomData<PIN>,
 }
 
+impl<const TN: u8, const CN: u8, PIN> TimerChannelPwm<TN, CN, PIN>
+where
+    PIN: OutputPin + TimerPin<CN>,
+{
+    /// Set the duty cycle immediately, in the current PWM period.
+    ///
+    /// [`SetDutyCycle::set_duty_cycle()`] writes the buffered Capture/Compare register (`CCVB`), which is only copied
+    /// to `CCV` on the next timer overflow, so the change is delayed by up to one PWM period but never glitches. This
+    /// method writes `CCV` directly instead (as well as `CCVB`, so that the pending buffered value doesn't override it
+    /// on the next overflow), which may produce one irregular pulse.
+    pub fn set_duty_cycle_immediate(&mut self, duty: u16) {
+        let timer = timerx::<TN>();
+
+        match CN {
+            0 => {
+                timer.cc0_ccvb().write(|w| unsafe { w.ccvb().bits(duty) });
+                timer.cc0_ccv().write(|w| unsafe { w.ccv().bits(duty) });
+            }
+            1 => {
+                timer.cc1_ccvb().write(|w| unsafe { w.ccvb().bits(duty) });
+                timer.cc1_ccv().write(|w| unsafe { w.ccv().bits(duty) });
+            }
+            2 => {
+                timer.cc2_ccvb().write(|w| unsafe { w.ccvb().bits(duty) });
+                timer.cc2_ccv().write(|w| unsafe { w.ccv().bits(duty) });
+            }
+            3 => {
+                timer.cc3_ccvb().write(|w| unsafe { w.ccvb().bits(duty) });
+                timer.cc3_ccv().write(|w| unsafe { w.ccv().bits(duty) });
+            }
+            _ => unreachable!(),
+        };
+    }
+
+    /// Set the duty cycle as a percentage (values above `100` are treated as `100`), rounded to the nearest duty
+    /// cycle value.
+    ///
+    /// `0` and `100` are mapped exactly to `0` and [`SetDutyCycle::max_duty_cycle()`] (i.e. `TOP + 1`), so that the
+    /// output is constantly low or high at the extremes. Like [`SetDutyCycle::set_duty_cycle()`], the new duty cycle
+    /// is applied on the next timer overflow.
+    pub fn set_duty_percent(&mut self, pct: u8) {
+        let pct = pct.min(100) as u32;
+        let max = self.max_duty_cycle() as u32;
+        let duty = (pct * max + 50) / 100;
+
+        // the error type is `Infallible`
+        let _ = self.set_duty_cycle(duty as u16);
+    }
+
+    /// Set the output polarity of the channel (`CCx_CTRL.OUTINV`). The default is [`PwmPolarity::ActiveHigh`].
+    ///
+    /// The duty cycle always sets the length of the *active* part of the period, so e.g. a 25% duty cycle is 25% high
+    /// with [`PwmPolarity::ActiveHigh`], and 25% low with [`PwmPolarity::ActiveLow`]. Similarly,
+    /// [`TimerChannelPwm::force_output()`] gives the opposite level when the output is inverted.
+    pub fn set_polarity(&mut self, polarity: PwmPolarity) {
+        let timer = timerx::<TN>();
+        let inv = polarity == PwmPolarity::ActiveLow;
+
+        match CN {
+            0 => timer.cc0_ctrl().modify(|_, w| w.outinv().bit(inv)),
+            1 => timer.cc1_ctrl().modify(|_, w| w.outinv().bit(inv)),
+            2 => timer.cc2_ctrl().modify(|_, w| w.outinv().bit(inv)),
+            3 => timer.cc3_ctrl().modify(|_, w| w.outinv().bit(inv)),
+            _ => unreachable!(),
+        };
+    }
+
+    /// Get the output polarity of the channel
+    pub fn polarity(&self) -> PwmPolarity {
+        let timer = timerx::<TN>();
+
+        let inv = match CN {
+            0 => timer.cc0_ctrl().read().outinv().bit_is_set(),
+            1 => timer.cc1_ctrl().read().outinv().bit_is_set(),
+            2 => timer.cc2_ctrl().read().outinv().bit_is_set(),
+            3 => timer.cc3_ctrl().read().outinv().bit_is_set(),
+            _ => unreachable!(),
+        };
+
+        match inv {
+            true => PwmPolarity::ActiveLow,
+            false => PwmPolarity::ActiveHigh,
+        }
+    }
+
+    /// Immediately force the PWM output to a constant `level` (e.g. for an emergency off), by setting a 0% or 100% duty
+    /// cycle with [`TimerChannelPwm::set_duty_cycle_immediate()`]
+    pub fn force_output(&mut self, level: PinState) {
+        let duty = match level {
+            PinState::Low => 0,
+            PinState::High => self.max_duty_cycle(),
+        };
+
+        self.set_duty_cycle_immediate(duty);
+    }
+
+    /// Move the PWM output to `new_pin`, while the timer keeps running.
+    ///
+    /// The channel output is unrouted (`ROUTEPEN`), its location is changed in `ROUTELOC0` (with a read-modify-write,
+    /// so that the locations of the other channels are kept) and the output is routed again. The duty cycle, period
+    /// and polarity are not changed, so the new pin continues the current PWM period. During the switch, which takes
+    /// a few bus cycles, neither pin is driven by the timer: the previous pin goes back to its GPIO output level
+    /// (`DOUT`), and the new pin drives its own `DOUT` until it is routed, which may produce one short glitch on both.
+    pub fn remap<NPIN>(self, new_pin: NPIN) -> TimerChannelPwm<TN, CN, NPIN>
+    where
+        NPIN: OutputPin + TimerPin<CN>,
+    {
+        let timer = timerx::<TN>();
+        let loc = new_pin.loc();
+
+        match CN {
+            0 => {
+                timer.routepen().modify(|_, w| w.cc0pen().clear_bit());
+                timer.routeloc0().modify(|_, w| unsafe { w.cc0loc().bits(loc) });
+                timer.routepen().modify(|_, w| w.cc0pen().set_bit());
+            }
+            1 => {
+                timer.routepen().modify(|_, w| w.cc1pen().clear_bit());
+                timer.routeloc0().modify(|_, w| unsafe { w.cc1loc().bits(loc) });
+                timer.routepen().modify(|_, w| w.cc1pen().set_bit());
+            }
+            2 => {
+                timer.routepen().modify(|_, w| w.cc2pen().clear_bit());
+                timer.routeloc0().modify(|_, w| unsafe { w.cc2loc().bits(loc) });
+                timer.routepen().modify(|_, w| w.cc2pen().set_bit());
+            }
+            3 => {
+                timer.routepen().modify(|_, w| w.cc3pen().clear_bit());
+                timer.routeloc0().modify(|_, w| unsafe { w.cc3loc().bits(loc) });
+                timer.routepen().modify(|_, w| w.cc3pen().set_bit());
+            }
+            _ => unreachable!(),
+        };
+
+        TimerChannelPwm {
+            _pwm_pin: PhantomData,
+        }
+    }
+}
+
 impl<const TN: u8, const CN: u8, PIN> SetDutyCycle for TimerChannelPwm<TN, CN, PIN>
 where
     PIN: OutputPin + TimerPin<CN>,
@@ -340,6 +1798,179 @@ where
     type Error = Infallible;
 }
 
+/// Group of PWM channels of the same timer, whose duty cycles and period are updated together.
+///
+/// The new values are written to the buffered registers (`CCVB` and `TOPB`), which are all copied by the timer on the
+/// same overflow, so that e.g. the channels of an RGB LED or of a 3-phase drive never run with a mix of old and new
+/// duty cycles.
+///
+/// ```rust,no_run
+/// let (counter, ch0, ch1, ch2, _ch3) = timer.into_channels_with_counter();
+///
+/// let mut rgb = TimerPwm::new(counter)
+///     .with_channel(ch0.into_pwm(gpio.pd10.into_mode::<OutPp>()))
+///     .with_channel(ch1.into_pwm(gpio.pd11.into_mode::<OutPp>()))
+///     .with_channel(ch2.into_pwm(gpio.pd12.into_mode::<OutPp>()));
+///
+/// rgb.set_period(255);
+/// rgb.set_duty_cycles([Some(255), Some(128), Some(0), None]);
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimerPwm<const TN: u8> {
+    counter: TimerCounter<TN>,
+    /// Bit `n` is set if channel `n` is part of the group
+    channels: u8,
+}
+
+impl<const TN: u8> TimerPwm<TN> {
+    /// Create an empty group of PWM channels. The channels are added with [`TimerPwm::with_channel()`].
+    pub fn new(counter: TimerCounter<TN>) -> Self {
+        Self { counter, channels: 0 }
+    }
+
+    /// Add a PWM channel (see [`TimerChannel::into_pwm()`]) to the group. The channel output is enabled.
+    pub fn with_channel<const CN: u8, PIN>(mut self, _pwm: TimerChannelPwm<TN, CN, PIN>) -> Self
+    where
+        PIN: OutputPin + TimerPin<CN>,
+    {
+        self.channels |= 1 << CN;
+        self
+    }
+
+    /// Check if `channel` (`0..=3`) is part of the group
+    pub fn has_channel(&self, channel: u8) -> bool {
+        channel < 4 && self.channels & (1 << channel) != 0
+    }
+
+    /// Connect the output of `channel` to its pin (`ROUTEPEN.CCxPEN`). Channels which are not part of the group are
+    /// ignored.
+    pub fn enable(&mut self, channel: u8) {
+        self.set_output_enabled(channel, true);
+    }
+
+    /// Disconnect the output of `channel` from its pin (`ROUTEPEN.CCxPEN`), which is then driven by its GPIO
+    /// configuration again. Channels which are not part of the group are ignored.
+    pub fn disable(&mut self, channel: u8) {
+        self.set_output_enabled(channel, false);
+    }
+
+    /// Check if the output of `channel` is connected to its pin
+    pub fn is_enabled(&self, channel: u8) -> bool {
+        let routepen = timerx::<TN>().routepen().read();
+
+        match channel {
+            0 => routepen.cc0pen().bit_is_set(),
+            1 => routepen.cc1pen().bit_is_set(),
+            2 => routepen.cc2pen().bit_is_set(),
+            3 => routepen.cc3pen().bit_is_set(),
+            _ => false,
+        }
+    }
+
+    /// Get the PWM period, i.e. the counter `TOP` value
+    pub fn period(&self) -> u16 {
+        self.counter.top()
+    }
+
+    /// Set the PWM period (i.e. the counter `TOP` value) and return the value which was actually set. It is capped at
+    /// `u16::MAX - 1`, like in [`TimerCounter::set_top()`].
+    ///
+    /// The period is written to `TOPB`, so it is applied on the next overflow, together with the pending duty cycles.
+    pub fn set_period(&mut self, top: u16) -> u16 {
+        let top = top.min(u16::MAX - 1);
+        self.write_buffered(Some(top), [None; 4]);
+        top
+    }
+
+    /// Maximum duty cycle (100%) for the current period, i.e. `TOP + 1`
+    pub fn max_duty_cycle(&self) -> u16 {
+        self.counter.top().saturating_add(1)
+    }
+
+    /// Set the duty cycle of a single `channel`, on the next overflow. Channels which are not part of the group are
+    /// ignored.
+    pub fn set_duty_cycle(&mut self, channel: u8, duty: u16) {
+        let mut duties = [None; 4];
+        if let Some(d) = duties.get_mut(channel as usize) {
+            *d = Some(duty);
+        }
+
+        self.write_buffered(None, duties);
+    }
+
+    /// Set the duty cycles of the channels `0..=3` together, so that all of them are applied on the same overflow.
+    /// `None` keeps the current duty cycle of a channel, and channels which are not part of the group are ignored.
+    pub fn set_duty_cycles(&mut self, duties: [Option<u16>; 4]) {
+        self.write_buffered(None, duties);
+    }
+
+    /// Release the timer counter handle. The channels keep their PWM configuration.
+    pub fn free(self) -> TimerCounter<TN> {
+        self.counter
+    }
+
+    fn set_output_enabled(&mut self, channel: u8, enabled: bool) {
+        if !self.has_channel(channel) {
+            return;
+        }
+
+        timerx::<TN>().routepen().modify(|_, w| match channel {
+            0 => w.cc0pen().bit(enabled),
+            1 => w.cc1pen().bit(enabled),
+            2 => w.cc2pen().bit(enabled),
+            _ => w.cc3pen().bit(enabled),
+        });
+    }
+
+    /// Write the buffered `TOPB` and `CCVB` registers of the group.
+    ///
+    /// If an overflow happens while the registers are written, the values which were written before it are already
+    /// applied, while the others would only be applied one period later. In that case, all the values are also written
+    /// to `TOP` and `CCV`, since the counter has just wrapped around, so that the whole update happens in the same
+    /// period.
+    fn write_buffered(&mut self, top: Option<u16>, duties: [Option<u16>; 4]) {
+        let timer = timerx::<TN>();
+
+        let write = |immediate: bool| {
+            if let Some(top) = top {
+                match immediate {
+                    true => timer.top().write(|w| unsafe { w.top().bits(top) }),
+                    false => timer.topb().write(|w| unsafe { w.topb().bits(top) }),
+                };
+            }
+
+            let updates = duties
+                .iter()
+                .enumerate()
+                .filter_map(|(ch, duty)| duty.map(|duty| (ch, duty)));
+
+            for (channel, duty) in updates.filter(|(ch, _)| self.has_channel(*ch as u8)) {
+                match (channel, immediate) {
+                    (0, false) => timer.cc0_ccvb().write(|w| unsafe { w.ccvb().bits(duty) }),
+                    (1, false) => timer.cc1_ccvb().write(|w| unsafe { w.ccvb().bits(duty) }),
+                    (2, false) => timer.cc2_ccvb().write(|w| unsafe { w.ccvb().bits(duty) }),
+                    (3, false) => timer.cc3_ccvb().write(|w| unsafe { w.ccvb().bits(duty) }),
+                    (0, true) => timer.cc0_ccv().write(|w| unsafe { w.ccv().bits(duty) }),
+                    (1, true) => timer.cc1_ccv().write(|w| unsafe { w.ccv().bits(duty) }),
+                    (2, true) => timer.cc2_ccv().write(|w| unsafe { w.ccv().bits(duty) }),
+                    (3, true) => timer.cc3_ccv().write(|w| unsafe { w.ccv().bits(duty) }),
+                    _ => unreachable!(),
+                };
+            }
+        };
+
+        critical_section::with(|_| {
+            let before = self.counter.counter();
+            write(false);
+
+            if self.counter.counter() < before {
+                write(true);
+            }
+        });
+    }
+}
+
 /// Trait to specify the location values for TIMERn_ROUTELOC0 and TIMERn_ROUTELOC1 for pins which can be used as PWM
 pub trait TimerPin<const CN: u8> {
     /// TIMERn_ROUTELOC0 and TIMERn_ROUTELOC1 values for each pin which implements this trait
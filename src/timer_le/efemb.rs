@@ -8,7 +8,8 @@ use crate::gpio::{
     OutPp,
 };
 use crate::{
-    pac::{interrupt, Cmu, Interrupt, NVIC},
+    cmu::{gates, PeripheralClock},
+    pac::{interrupt, Interrupt, NVIC},
     timer_le::mmio::{self, Command, InterruptFlag},
 };
 use core::{
@@ -72,8 +73,7 @@ impl Ticker {
         }
 
         // Enable LE Timer
-        let cmu = unsafe { Cmu::steal() };
-        cmu.lfaclken0().modify(|_, w| w.letimer0().set_bit());
+        gates::enable(PeripheralClock::LeTimer0);
 
         mmio::reset();
         mmio::comp1_set(0);
@@ -11,12 +11,14 @@
 pub mod efemb;
 
 use crate::{
+    cmu::{gates, Clocks, PeripheralClock},
     gpio::pin::Pin,
-    pac::{letimer0::ctrl::UFOA0, Cmu, Letimer0},
+    prs::{PrsChannelId, PrsEdge},
+    pac::{letimer0::ctrl::UFOA0, Letimer0},
 };
 use core::marker::PhantomData;
-use cortex_m::asm::nop;
 use embedded_hal::digital::OutputPin;
+use fugit::MicrosDurationU64;
 
 /// Extension trait for Letimer PAC peripheral
 pub trait LeTimerExt {
@@ -38,19 +40,60 @@ pub struct LeTimer;
 
 impl LeTimer {
     fn new() -> Self {
-        let cmu = unsafe { Cmu::steal() };
-
         // Enable LE Timer
-        cmu.lfaclken0().modify(|_, w| w.letimer0().set_bit());
-
-        // Sync
-        while cmu.syncbusy().read().lfaclken0().bit_is_set() {
-            nop()
-        }
+        gates::enable(PeripheralClock::LeTimer0);
 
         LeTimer {}
     }
 
+    /// Check if there are register writes which have not been synchronized to the Low Energy clock domain yet.
+    ///
+    /// Writes to synchronized registers issued while the timer is busy are silently dropped.
+    pub fn is_busy(&self) -> bool {
+        mmio::is_busy()
+    }
+
+    /// Stop the timer, and wait until the command has taken effect
+    pub fn stop(&mut self) {
+        mmio::cmd(mmio::Command::Stop);
+    }
+
+    /// Current value of the counter (`LETIMER0_CNT`).
+    ///
+    /// The timer counts _down_, and underflows (i.e. reloads `COMP0`, if used as top value) after reaching `0`. Pending
+    /// commands (e.g. a clear) are synchronized to the Low Energy clock domain before the counter is read.
+    pub fn counter(&self) -> u16 {
+        mmio::sync(mmio::SYNCBUSY_CMD);
+        mmio::counter_raw()
+    }
+
+    /// Time left until the next counter underflow, e.g. to know how long an EM2 application will sleep before the
+    /// next timer wake up.
+    ///
+    /// Returns `None` if the LfAClk (which drives the timer) is disabled.
+    pub fn remaining(&self, clocks: &Clocks) -> Option<MicrosDurationU64> {
+        let lfa_clk = clocks.lfa_clk()?.to_Hz() as u64;
+
+        // the underflow happens on the tick after the counter reaches `0`
+        let ticks = (self.counter() as u64 + 1) * mmio::prescaler() as u64;
+
+        Some(MicrosDurationU64::micros(ticks * 1_000_000 / lfa_clk))
+    }
+
+    /// Trigger the given timer `action` from the given PRS `channel`, on the given `edge` of the PRS signal, without
+    /// any CPU involvement (e.g. to start a pulse train on a CRYOTIMER event while in EM2).
+    ///
+    /// Each of the [`LeTimerPrsAction`]s (start, stop and clear) can be triggered by its own PRS channel. Using
+    /// [`PrsEdge::Off`] as `edge` disables the PRS trigger of the `action`.
+    pub fn enable_prs_trigger(&mut self, channel: PrsChannelId, action: LeTimerPrsAction, edge: PrsEdge) {
+        mmio::prs_trigger_set(channel, action, edge);
+    }
+
+    /// Stop triggering the given timer `action` from PRS
+    pub fn disable_prs_trigger(&mut self, action: LeTimerPrsAction) {
+        mmio::prs_trigger_set(PrsChannelId::Ch0, action, PrsEdge::Off);
+    }
+
     /// Convert timer to PWM
     pub fn into_ch0_pwm<PIN>(self, pin: PIN) -> LeTimerPwm<0, PIN>
     where
@@ -71,12 +114,7 @@ impl LeTimer {
         });
 
         // start timer
-        le_timer.cmd().write(|w| w.start().set_bit());
-
-        // Sync
-        while le_timer.syncbusy().read().cmd().bit_is_set() {
-            nop()
-        }
+        mmio::cmd(mmio::Command::Start);
 
         LeTimerPwm {
             _pwm_pin: PhantomData,
@@ -85,8 +123,10 @@ impl LeTimer {
 }
 
 mod mmio {
+    use super::LeTimerPrsAction;
+    use crate::prs::{PrsChannelId, PrsEdge};
     use cortex_m::asm::nop;
-    use efm32pg1b_pac::{letimer0::RegisterBlock, Letimer0};
+    use efm32pg1b_pac::{letimer0::RegisterBlock, Cmu, Letimer0};
 
     /// Reset the timer peripheral
     ///
@@ -126,6 +166,27 @@ mod mmio {
         u16::MAX - timer_le().cnt().read().cnt().bits()
     }
 
+    /// Get the raw value of the count _down_ counter
+    ///
+    /// NOTE: the counter runs in the Low Energy clock domain, so it is read until two consecutive reads agree
+    pub(crate) fn counter_raw() -> u16 {
+        let mut cnt = timer_le().cnt().read().cnt().bits();
+        loop {
+            let again = timer_le().cnt().read().cnt().bits();
+            if again == cnt {
+                return cnt;
+            }
+            cnt = again;
+        }
+    }
+
+    /// Clock divider of the timer (`CMU_LFAPRESC0.LETIMER0`)
+    pub(crate) fn prescaler() -> u32 {
+        let cmu = unsafe { Cmu::steal() };
+
+        1 << cmu.lfapresc0().read().letimer0().bits()
+    }
+
     /// Set the (logical) counter value.
     ///
     /// NOTE: This is a count _down_ timer, so actual register value will be set to `u16::MAX - cnt`
@@ -236,11 +297,43 @@ mod mmio {
         });
 
         // Block until the timer commands have been applied
-        while p.syncbusy().read().cmd().bit_is_set() {
+        sync(SYNCBUSY_CMD);
+    }
+
+    /// Offset of the `PRSxSEL` field of each `LeTimerPrsAction` in the `PRSSEL` register
+    const PRSSEL_SEL_OFFSETS: [u32; 3] = [0, 6, 12];
+    /// Offset of the `PRSxMODE` field of each `LeTimerPrsAction` in the `PRSSEL` register
+    const PRSSEL_MODE_OFFSETS: [u32; 3] = [18, 22, 26];
+    const PRSSEL_SEL_MASK: u32 = 0xF;
+    const PRSSEL_MODE_MASK: u32 = 0x3;
+
+    /// Select the PRS `channel` and `edge` which trigger the given `action`
+    pub(crate) fn prs_trigger_set(channel: PrsChannelId, action: LeTimerPrsAction, edge: PrsEdge) {
+        let sel_offset = PRSSEL_SEL_OFFSETS[action as usize];
+        let mode_offset = PRSSEL_MODE_OFFSETS[action as usize];
+
+        timer_le().prssel().modify(|r, w| {
+            let bits = r.bits() & !(PRSSEL_SEL_MASK << sel_offset) & !(PRSSEL_MODE_MASK << mode_offset);
+            unsafe { w.bits(bits | ((channel as u32) << sel_offset) | ((edge as u32) << mode_offset)) }
+        });
+    }
+
+    /// `CMD` register mask in the `SYNCBUSY` register
+    pub(crate) const SYNCBUSY_CMD: u32 = 1 << 1;
+
+    /// Wait until the writes to the registers specified by `reg_mask` (see `SYNCBUSY_*` masks) are synchronized to the
+    /// Low Energy clock domain
+    pub(crate) fn sync(reg_mask: u32) {
+        while timer_le().syncbusy().read().bits() & reg_mask != 0 {
             nop();
         }
     }
 
+    /// Check if any register write is still being synchronized to the Low Energy clock domain
+    pub(crate) fn is_busy() -> bool {
+        timer_le().syncbusy().read().bits() != 0
+    }
+
     /// Get a reference to the Low Energy Timer register block
     pub(crate) const fn timer_le() -> &'static RegisterBlock {
         unsafe { &*Letimer0::ptr() }
@@ -255,6 +348,56 @@ where
     _pwm_pin: PhantomData<PIN>,
 }
 
+impl<const CN: u8, PIN> LeTimerPwm<CN, PIN>
+where
+    PIN: OutputPin + LeTimerPin<CN>,
+{
+    /// Check if there are register writes which have not been synchronized to the Low Energy clock domain yet.
+    ///
+    /// Writes to synchronized registers issued while the timer is busy are silently dropped.
+    pub fn is_busy(&self) -> bool {
+        mmio::is_busy()
+    }
+
+    /// Stop the PWM timer, and wait until the command has taken effect
+    pub fn stop(&mut self) {
+        mmio::cmd(mmio::Command::Stop);
+    }
+
+    /// Start the PWM timer, and wait until the command has taken effect
+    pub fn start(&mut self) {
+        mmio::cmd(mmio::Command::Start);
+    }
+
+    /// Trigger the given timer `action` from the given PRS `channel`, on the given `edge` of the PRS signal, without
+    /// any CPU involvement (e.g. to start the PWM on a CRYOTIMER event while in EM2).
+    ///
+    /// Each of the [`LeTimerPrsAction`]s (start, stop and clear) can be triggered by its own PRS channel. Using
+    /// [`PrsEdge::Off`] as `edge` disables the PRS trigger of the `action`.
+    pub fn enable_prs_trigger(&mut self, channel: PrsChannelId, action: LeTimerPrsAction, edge: PrsEdge) {
+        mmio::prs_trigger_set(channel, action, edge);
+    }
+
+    /// Stop triggering the given timer `action` from PRS
+    pub fn disable_prs_trigger(&mut self, action: LeTimerPrsAction) {
+        mmio::prs_trigger_set(PrsChannelId::Ch0, action, PrsEdge::Off);
+    }
+}
+
+/// Timer actions which can be triggered by a PRS channel (`PRSSTARTSEL`/`PRSSTOPSEL`/`PRSCLEARSEL` fields of
+/// `LETIMERn_PRSSEL`)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LeTimerPrsAction {
+    /// Start the timer
+    Start,
+    /// Stop the timer
+    Stop,
+    /// Clear the timer counter
+    Clear,
+}
+
 /// Trait for each of the LE timer channels and their sets of 32 pins
 pub trait LeTimerPin<const CN: u8> {
     /// Value to be written to LETIMERn_ROUTELOC0 register for the Pin implementing this trait
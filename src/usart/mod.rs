@@ -6,11 +6,28 @@
 //!
 //! The corresponding clock for USART0 or USART1 is only enabled when the Usart is specialised into Spi, Uart, etc, and
 //! is disabled when the Usart is freed with [`Usart::free`](`crate::usart::Usart::free`)
+//!
+//! # Pin locations
+//!
+//! On the EFM32PG1B, `USART0` and `USART1` share the same location tables (`LOC0` to `LOC31`, see
+//! [Data Sheet - page 85](../../../../doc/efm32pg1-datasheet.pdf#page=85)), so any pin which can be used as `US0_CLK`
+//! can also be used as `US1_CLK` (and the same goes for `TX`, `RX` and `CS`). The location tables are encoded in the
+//! [`UsartClkPin`], [`UsartTxPin`] and [`UsartRxPin`] traits, so a pin which cannot be routed to the requested function
+//! fails to compile.
+//!
+//! The same physical pin cannot be used for more than one function of the bus, since each `Pin` type is a singleton
+//! which is moved into the bus, and the `RX` pin must be in an input mode while `CLK` and `TX` must be in output modes.
+//!
+//! # Asynchronous (UART) mode
+//!
+//! Only the synchronous master (SPI) specialisation is implemented so far. A UART driver (and the features built on
+//! its RX path, like the RX timeout based on the USART timer `TIMECMPn` registers for idle line detection) is not
+//! available yet.
 
 use crate::{
-    pac::Cmu,
+    cmu::{gates, PeripheralClock},
     usart::{
-        spi::{Spi, UsartClkPin, UsartRxPin, UsartTxPin},
+        spi::{NoCs, Spi, UsartClkPin, UsartCsPin, UsartRxPin, UsartTxPin},
         usarts::usartx,
     },
     Sealed,
@@ -70,6 +87,8 @@ pub struct Usart<const N: u8> {
 impl<const N: u8> Usart<N> {
     /// Specialize the Usart peripheral into an SPI Master which implements the [`SpiBus`](`embedded_hal::spi::SpiBus`)
     /// trait
+    ///
+    /// The pins are checked at compile time: see [Pin locations](crate::usart#pin-locations).
     pub fn into_spi_bus<PCLK, PTX, PRX>(
         mut self,
         pin_clk: PCLK,
@@ -83,30 +102,55 @@ impl<const N: u8> Usart<N> {
         PRX: InputPin + UsartRxPin,
     {
         self.enable();
-        Spi::new(self, pin_clk, pin_tx, pin_rx, mode)
+        Spi::new(self, pin_clk, pin_tx, pin_rx, NoCs, mode)
     }
 
-    fn enable(&mut self) {
-        let cmu = unsafe { Cmu::steal() };
+    /// Specialize the Usart peripheral into an SPI Master, like [`Usart::into_spi_bus()`], which also owns its CS pin.
+    ///
+    /// The CS location is routed (`ROUTELOC0.CSLOC`), but the hardware (auto) CS is _not_ enabled: the CS pin is
+    /// still controlled by software (see [`Spi::cs_pin()`]), e.g. by an `SpiDevice` implementation built on top of the
+    /// returned bus, so no separately owned pin is needed.
+    pub fn into_spi_bus_with_cs<PCLK, PTX, PRX, PCS>(
+        mut self,
+        pin_clk: PCLK,
+        pin_tx: PTX,
+        pin_rx: PRX,
+        pin_cs: PCS,
+        mode: Mode,
+    ) -> Spi<N, Usart<N>, PCLK, PTX, PRX, PCS>
+    where
+        PCLK: OutputPin + UsartClkPin,
+        PTX: OutputPin + UsartTxPin,
+        PRX: InputPin + UsartRxPin,
+        PCS: OutputPin + UsartCsPin,
+    {
+        let cs_loc = pin_cs.loc();
 
-        cmu.hfperclken0().modify(|_, w| match N {
-            0 => w.usart0().set_bit(),
-            1 => w.usart1().set_bit(),
-            _ => unreachable!(),
-        });
+        self.enable();
+        let spi = Spi::new(self, pin_clk, pin_tx, pin_rx, pin_cs, mode);
+
+        usartx::<N>()
+            .routeloc0()
+            .modify(|_, w| unsafe { w.csloc().bits(cs_loc) });
+
+        spi
     }
 
-    fn disable(&mut self) {
-        let cmu = unsafe { Cmu::steal() };
+    pub(crate) fn enable(&mut self) {
+        gates::enable(PeripheralClock::usart(N));
+    }
 
-        cmu.hfperclken0().modify(|_, w| match N {
-            0 => w.usart0().clear_bit(),
-            1 => w.usart1().clear_bit(),
-            _ => unreachable!(),
-        });
+    pub(crate) fn disable(&mut self) {
+        gates::disable(PeripheralClock::usart(N));
     }
 
     fn reset(&mut self) {
+        // Writing the registers has no effect while the peripheral clock is disabled (e.g. after the `Usart` was
+        // released by a specialized driver, which already reset the registers)
+        if !gates::is_enabled(PeripheralClock::usart(N)) {
+            return;
+        }
+
         let usart_p = usartx::<N>();
         // Write disable commands first
         usart_p.cmd().write(|w| {
@@ -1,6 +1,8 @@
 //! Serial Peripheral Interface Bus
 //!
 //! Specialize USART peripherals into SPI peripherals
+//!
+//! The [`shared`] module shares one SPI bus between several drivers.
 
 use crate::{
     cmu::Clocks,
@@ -8,31 +10,108 @@ use crate::{
         mode::{InputMode, OutputMode},
         Pin,
     },
-    usart::{usarts::usartx, Usart},
+    pac::usart0::{status, RegisterBlock},
+    prs::PrsChannelId,
+    usart::{usarts::usartx, Usart, UsartBuild},
+    Sealed,
 };
-use core::cmp::max;
+use core::cmp::{max, min};
 use embedded_hal::{
     digital::{InputPin, OutputPin},
     spi::{Error, ErrorKind, ErrorType, Mode, Phase, Polarity, SpiBus},
 };
 pub use fugit::{HertzU32, RateExtU32};
 
+pub mod shared;
+
 /// SPI master which implements `SpiBus` trait
+///
+/// The `PCS` type is the CS pin which was given to [`Usart::into_spi_bus_with_cs()`], or [`NoCs`] if the SPI was
+/// created with [`Usart::into_spi_bus()`].
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub struct Spi<const N: u8, USART, PCLK, PTX, PRX> {
+pub struct Spi<const N: u8, USART, PCLK, PTX, PRX, PCS = NoCs> {
     usart: USART,
     pin_clk: PCLK,
     pin_tx: PTX,
     pin_rx: PRX,
+    pin_cs: PCS,
+    read_filler: u8,
+    /// Core clock cycles inserted between frames (see [`Spi::set_inter_frame_delay()`])
+    inter_frame_delay: u32,
+    /// Number of times a timed out wait is retried (see [`Spi::set_retry()`])
+    retries: u8,
 }
 
-impl<const N: u8, PCLK, PTX, PRX> Spi<N, Usart<N>, PCLK, PTX, PRX>
+/// Placeholder for the CS pin of an [`Spi`] which does not own its CS pin
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NoCs;
+
+impl<const N: u8, PCLK, PTX, PRX> Spi<N, Usart<N>, PCLK, PTX, PRX, NoCs>
 where
     PCLK: OutputPin + UsartClkPin,
     PTX: OutputPin + UsartTxPin,
     PRX: InputPin + UsartRxPin,
 {
+    /// Release the resources used to create this SPI instance
+    ///
+    /// The USART registers are reset and the USART peripheral clock is disabled, so a released SPI does not keep
+    /// drawing current. The clock is enabled again if the returned [`Usart`] is specialized again (e.g. with
+    /// [`Usart::into_spi_bus()`]).
+    pub fn free(mut self) -> (Usart<N>, PCLK, PTX, PRX) {
+        self.reset();
+        self.usart.disable();
+
+        (self.usart, self.pin_clk, self.pin_tx, self.pin_rx)
+    }
+
+    /// Release the resources used to create this SPI instance, like [`Spi::free()`], and also free the [`Usart`]
+    /// driver, returning the PAC peripheral (e.g. to use the USART for a different role).
+    ///
+    /// ```rust,no_run
+    /// let (usart0, clk, tx, rx): (pac::Usart0, _, _, _) = spi.release_usart();
+    /// ```
+    pub fn release_usart<USART>(self) -> (USART, PCLK, PTX, PRX)
+    where
+        Usart<N>: UsartBuild<N, USART>,
+    {
+        let (usart, pin_clk, pin_tx, pin_rx) = self.free();
+
+        (usart.free(), pin_clk, pin_tx, pin_rx)
+    }
+}
+
+impl<const N: u8, PCLK, PTX, PRX, PCS> Spi<N, Usart<N>, PCLK, PTX, PRX, PCS>
+where
+    PCLK: OutputPin + UsartClkPin,
+    PTX: OutputPin + UsartTxPin,
+    PRX: InputPin + UsartRxPin,
+    PCS: OutputPin + UsartCsPin,
+{
+    /// The CS pin of this SPI, which is controlled by software (e.g. by an `SpiDevice`)
+    pub fn cs_pin(&mut self) -> &mut PCS {
+        &mut self.pin_cs
+    }
+
+    /// Release the resources used to create this SPI instance, including the CS pin
+    ///
+    /// See [`Spi::free()`].
+    pub fn free_with_cs(mut self) -> (Usart<N>, PCLK, PTX, PRX, PCS) {
+        self.reset();
+        self.usart.disable();
+
+        (self.usart, self.pin_clk, self.pin_tx, self.pin_rx, self.pin_cs)
+    }
+}
+
+impl<const N: u8, PCLK, PTX, PRX, PCS> Spi<N, Usart<N>, PCLK, PTX, PRX, PCS>
+where
+    PCLK: OutputPin + UsartClkPin,
+    PTX: OutputPin + UsartTxPin,
+    PRX: InputPin + UsartRxPin,
+{
+    /// Default byte which is sent while reading
     const FILLER_BYTE: u8 = 0x00;
 
     pub(crate) fn new(
@@ -40,6 +119,7 @@ where
         pin_clk: PCLK,
         pin_tx: PTX,
         pin_rx: PRX,
+        pin_cs: PCS,
         mode: Mode,
     ) -> Self {
         let mut spi = Spi {
@@ -47,6 +127,10 @@ where
             pin_clk,
             pin_tx,
             pin_rx,
+            pin_cs,
+            read_filler: Self::FILLER_BYTE,
+            inter_frame_delay: 0,
+            retries: 0,
         };
 
         let usart_p = usartx::<N>();
@@ -62,7 +146,7 @@ where
             w.autotx().clear_bit()
         });
 
-        spi.set_mode(mode);
+        spi.set_clock_mode(mode);
 
         usart_p.frame().write(|w| {
             // 8 data bits
@@ -114,28 +198,282 @@ where
         spi
     }
 
-    /// Release the resources used to create this SPI instance
-    pub fn free(self) -> (Usart<N>, PCLK, PTX, PRX) {
-        (self.usart, self.pin_clk, self.pin_tx, self.pin_rx)
-    }
-
     /// Set the SPI loopback flag
+    ///
+    /// When loopback is enabled, the receiver is connected to the TX output internally (the `RX` pin is ignored), so
+    /// every byte written is also read back. This is mostly useful for testing the SPI peripheral without any slave
+    /// device (see [`Spi::selftest()`]).
     pub fn set_loopback(&mut self, enabled: bool) {
         let usart_p = usartx::<N>();
-        usart_p.ctrl().write(|w| match enabled {
-            true => w.loopbk().set_bit(),
-            false => w.loopbk().clear_bit(),
+        usart_p.ctrl().modify(|_, w| w.loopbk().bit(enabled));
+    }
+
+    /// Check if the SPI loopback flag is set
+    pub fn loopback(&self) -> bool {
+        let usart_p = usartx::<N>();
+        usart_p.ctrl().read().loopbk().bit_is_set()
+    }
+
+    /// Test the SPI peripheral using loopback mode: a known byte is sent and it's expected to be read back.
+    ///
+    /// Returns `true` if the byte was read back correctly. The loopback flag is restored to its previous value.
+    ///
+    /// Note: the test byte is still clocked out on the `CLK` and `TX` pins, so make sure no slave device is selected
+    ///       while running this test.
+    pub fn selftest(&mut self) -> bool {
+        const TEST_BYTE: u8 = 0xA5;
+        let usart_p = usartx::<N>();
+        let loopback = self.loopback();
+
+        self.set_loopback(true);
+
+        // Discard any stale data in the RX buffer
+        usart_p.cmd().write(|w| w.clearrx().set_bit());
+
+        let mut rx = [0u8; 1];
+        let result = self.transfer(&mut rx, &[TEST_BYTE]);
+
+        self.set_loopback(loopback);
+
+        result.is_ok() && rx[0] == TEST_BYTE
+    }
+
+    /// Keep the SPI clock running between frames (`CTRL.AUTOTX`)
+    ///
+    /// The USART has no dedicated free-running clock mode: with `AUTOTX` set, the master keeps transmitting (and thus
+    /// clocking `SCLK`) as long as the RX buffer is not full, even when there is no data to send. This means that:
+    ///
+    /// * the received bytes must be read continuously (e.g. with [`Spi::read_block()`]), otherwise the clock stops
+    ///   once the RX buffer is full;
+    /// * the transmission is never complete, so the `TXC` flag is not set and `flush()` returns an error;
+    /// * the driver doesn't use the hardware (auto) CS, so the CS pin must be kept asserted by the application for as
+    ///   long as the slave needs the clock.
+    pub fn set_continuous_clock(&mut self, enabled: bool) {
+        let usart_p = usartx::<N>();
+        usart_p.ctrl().modify(|_, w| w.autotx().bit(enabled));
+    }
+
+    /// Check if the SPI clock is kept running between frames
+    pub fn continuous_clock(&self) -> bool {
+        let usart_p = usartx::<N>();
+        usart_p.ctrl().read().autotx().bit_is_set()
+    }
+
+    /// Number of bytes waiting in the TX buffer (`STATUS.TXBUFCNT`), not counting the byte in the shift register
+    pub fn tx_fifo_count(&self) -> u8 {
+        usartx::<N>().status().read().txbufcnt().bits()
+    }
+
+    /// Check if there is at least one received byte in the RX buffer (`STATUS.RXDATAV`)
+    pub fn rx_data_available(&self) -> bool {
+        usartx::<N>().status().read().rxdatav().bit_is_set()
+    }
+
+    /// Check if the transmission is complete (`STATUS.TXC`), i.e. the TX buffer and the shift register are empty.
+    ///
+    /// Note: the flag is only set after a byte has been sent, so it is clear after reset.
+    pub fn tx_complete(&self) -> bool {
+        usartx::<N>().status().read().txc().bit_is_set()
+    }
+
+    /// Set the byte which is sent while reading, i.e. during `read()`, or during `transfer()` when the `write` slice is
+    /// shorter than the `read` slice. Default is `0x00`.
+    ///
+    /// Some slave devices expect a specific value here (e.g. SD cards require `0xFF`).
+    pub fn set_read_filler(&mut self, byte: u8) {
+        self.read_filler = byte;
+    }
+
+    /// Insert a gap of at least `cycles` core clock cycles between consecutive frames in `write()`, `read()`,
+    /// `transfer()` and `transfer_in_place()`, e.g. for slow shift register chains, or DACs which need some settling
+    /// time between bytes. Default is `0`, i.e. no gap.
+    ///
+    /// The USART has no inter-frame delay in synchronous mode, so the gap is inserted in software (see
+    /// [`Clocks::delay_cycles()`]): each frame is sent on its own, and the next one is only written once it is
+    /// complete and the delay has elapsed. This reduces the throughput, even for a small number of cycles.
+    /// [`Spi::read_block()`] and [`Spi::read_stream()`] are not affected.
+    pub fn set_inter_frame_delay(&mut self, cycles: u32) {
+        self.inter_frame_delay = cycles;
+    }
+
+    /// Get the number of core clock cycles inserted between frames
+    pub fn inter_frame_delay(&self) -> u32 {
+        self.inter_frame_delay
+    }
+
+    /// Retry each timed out wait up to `attempts` times before failing with [`SpiError::TxUnderflow`] or
+    /// [`SpiError::RxUnderflow`]. Default is `0`, i.e. the first timeout is returned as an error.
+    ///
+    /// The timeouts are bounded busy waits for room in the TX buffer, or for the end of a frame. A frame which was
+    /// written to the TX buffer has been accepted by the USART, so it is never written again: a retry only restarts the
+    /// wait, giving a slow or stalled transmission (e.g. a marginal clock line during bring-up) more time to complete,
+    /// without duplicating bytes on the bus. Other errors (e.g. [`SpiError::RxOverflow`]) are not retried.
+    pub fn set_retry(&mut self, attempts: u8) {
+        self.retries = attempts;
+    }
+
+    /// Get the number of times a timed out wait is retried
+    pub fn retries(&self) -> u8 {
+        self.retries
+    }
+
+    /// Read a block of bytes, while sending the read filler byte (see [`Spi::set_read_filler()`]).
+    ///
+    /// Unlike `read()`, which goes through the generic `transfer()` path, this streams the filler bytes in pairs using
+    /// the `txdouble` register, and collects the received pairs from `rxdouble` as soon as the RX buffer is full
+    /// (without waiting for the transmitter to become idle), which gives a better throughput for read-heavy devices.
+    pub fn read_block(&mut self, buf: &mut [u8]) -> Result<(), SpiError> {
+        let read_filler = self.read_filler;
+        let usart_p = usartx::<N>();
+
+        self.clear_rx();
+
+        for chunk in buf.chunks_mut(2) {
+            if let [b0, b1] = chunk {
+                // Clock out 2 filler bytes using the `txdouble` register
+                usart_p.txdouble().write(|w| unsafe {
+                    w.txdata0().bits(read_filler);
+                    w.txdata1().bits(read_filler)
+                });
+
+                self.wait_rx_full()?;
+
+                // Read `rxdouble` only once: each read pops both bytes from the RX buffer
+                let rxdouble = usart_p.rxdouble().read();
+                *b0 = rxdouble.rxdata0().bits();
+                *b1 = rxdouble.rxdata1().bits();
+            } else {
+                // We have only 1 byte left to read, use the `txdata` register
+                usart_p.txdata().write(|w| unsafe { w.txdata().bits(read_filler) });
+
+                self.wait_tx_complete()?;
+
+                chunk[0] = usart_p.rxdata().read().rxdata().bits();
+            }
+
+            self.check_rx()?;
+        }
+
+        Ok(())
+    }
+
+    /// Read a block of bytes with the SPI clock running continuously (`CTRL.AUTOTX`), so that there are no gaps
+    /// between the bytes, even for long reads (e.g. display or flash dumps).
+    ///
+    /// Unlike [`Spi::read_block()`], no data is written to the TX buffer: the USART transmits on its own as long as the
+    /// RX buffer has room, so the bytes sent on `MOSI` are **not** the read filler byte. Use this for devices which
+    /// ignore `MOSI` while sending their data.
+    ///
+    /// The USART only starts a new frame when the RX buffer is not full, so there are at most two received bytes which
+    /// are not read yet. The last two bytes are left in the RX buffer until it is full, which stops the clock, and
+    /// `AUTOTX` is cleared before they are read, so that no extra byte is clocked. A single byte is read with
+    /// [`Spi::read_block()`] instead, since it cannot be stopped in time.
+    ///
+    /// `AUTOTX` is always cleared when this returns, even if it was enabled with [`Spi::set_continuous_clock()`].
+    pub fn read_stream(&mut self, buf: &mut [u8]) -> Result<(), SpiError> {
+        if buf.len() < 2 {
+            return self.read_block(buf);
+        }
+
+        let usart_p = usartx::<N>();
+        let (head, tail) = buf.split_at_mut(buf.len() - 2);
+
+        self.clear_rx();
+        usart_p.ctrl().modify(|_, w| w.autotx().set_bit());
+
+        let res = self.read_stream_head(head).and_then(|_| {
+            // The clock stops once the last two bytes are in the RX buffer
+            self.wait_rx_full()?;
+            usart_p.ctrl().modify(|_, w| w.autotx().clear_bit());
+
+            let rxdouble = usart_p.rxdouble().read();
+            tail[0] = rxdouble.rxdata0().bits();
+            tail[1] = rxdouble.rxdata1().bits();
+
+            Ok(())
         });
+
+        usart_p.ctrl().modify(|_, w| w.autotx().clear_bit());
+        // Nothing was written to the TX buffer
+        usart_p.ifc().write(|w| w.txuf().set_bit());
+
+        res.and_then(|_| self.check_rx())
+    }
+
+    /// Read `buf` one byte at a time, as soon as each byte is received, while `AUTOTX` keeps the clock running
+    fn read_stream_head(&mut self, buf: &mut [u8]) -> Result<(), SpiError> {
+        let usart_p = usartx::<N>();
+
+        for byte in buf {
+            self.wait_status(|status| status.rxdatav().bit_is_set(), SpiError::RxUnderflow)?;
+
+            *byte = usart_p.rxdata().read().rxdata().bits();
+        }
+
+        Ok(())
+    }
+
+    /// Write `cmd`, then read `buf.len()` bytes while sending the read filler byte (see [`Spi::set_read_filler()`]).
+    ///
+    /// This is the usual register read of sensors and memories (e.g. a register address, followed by the register
+    /// contents), done without a `transfer()` buffer covering both phases. The bytes received while `cmd` is sent are
+    /// discarded, and the transmission is flushed once, at the end.
+    pub fn write_then_read(&mut self, cmd: &[u8], buf: &mut [u8]) -> Result<(), SpiError> {
+        self.write(cmd)?;
+
+        // `read_block()` discards the RX buffer, so all the bytes received while writing `cmd` must be in first
+        if !cmd.is_empty() {
+            self.wait_tx_complete()?;
+        }
+
+        self.read_block(buf)?;
+        self.flush()
+    }
+
+    /// Wait for the end of the transmission, and discard the bytes which were received while writing.
+    ///
+    /// This is the `flush()` of all the `SpiBus` word types, so it can be called without specifying the word type.
+    pub fn flush(&mut self) -> Result<(), SpiError> {
+        self.wait_tx_complete()?;
+
+        // The bytes received while writing are not read back, so make sure they don't end up in a later transfer
+        self.clear_rx();
+
+        self.check_bus_fault()
+    }
+
+    /// Write all the bytes produced by `iter`, keeping the TX buffer full.
+    ///
+    /// Unlike `write()`, which waits until there is room for a pair of bytes before each `txdouble` write, this writes
+    /// each byte as soon as one element of the TX buffer is free (`STATUS.TXBUFCNT`), and only polls while the buffer
+    /// is actually full. The bytes are produced lazily, so e.g. display lines can be streamed without first being
+    /// collected into a buffer.
+    ///
+    /// Like `write()`, this returns as soon as the last byte is in the TX buffer: use `flush()` to wait for the end
+    /// of the transmission.
+    pub fn write_all_iter(&mut self, iter: impl IntoIterator<Item = u8>) -> Result<(), SpiError> {
+        // TX buffer elements, not counting the shift register
+        const TX_BUFFER_SIZE: u8 = 2;
+        let usart_p = usartx::<N>();
+
+        for byte in iter {
+            self.wait_status(
+                |status| status.txbufcnt().bits() < TX_BUFFER_SIZE,
+                SpiError::TxUnderflow,
+            )?;
+
+            usart_p.txdata().write(|w| unsafe { w.txdata().bits(byte) });
+        }
+
+        Ok(())
     }
 
     /// Set the SPI baudrate
     ///
-    /// This does a best effort, so the actual calculated baudrate is returned
-    pub fn set_baudrate(
-        &mut self,
-        baudrate: HertzU32,
-        clocks: &Clocks,
-    ) -> Result<HertzU32, SpiError> {
+    /// This does a best effort, so the returned [`Baudrate`] holds both the requested and the actual baudrate. The
+    /// lowest baudrate is limited by the width of the clock divider (about `HFPERCLK / 65536`), so a lower requested
+    /// baudrate gives that minimum.
+    pub fn set_baudrate(&mut self, baudrate: HertzU32, clocks: &Clocks) -> Result<Baudrate, SpiError> {
         let usart_p = usartx::<N>();
 
         // A baudrate of 0 makes no sense
@@ -150,15 +488,20 @@ where
         // let clk_div: u32 = ((clocks.hf_per_clk / (baudrate * 2)) - 1) << 5;
         let clk_div: u32 = clocks.hf_per_clk() / (baudrate * 2);
 
-        // avoid underflow if trying to subtracting `1` from a `clk_div` of `0`
+        // avoid underflow if trying to subtracting `1` from a `clk_div` of `0`, and keep the integral part of the
+        // divider within the `DIV` field
         let clk_div = match clk_div {
             0 => 0,
-            _ => (clk_div - 1) << 5,
+            _ => (clk_div - 1).min(CLKDIV_DIV_MAX >> 5) << 5,
         };
 
         usart_p.clkdiv().write(|w| unsafe { w.div().bits(clk_div) });
 
-        Ok(Self::calculate_baudrate(clocks.hf_per_clk(), clk_div))
+        Ok(Baudrate {
+            requested: baudrate,
+            achieved: Self::calculate_baudrate(clocks.hf_per_clk(), clk_div),
+            clkdiv: clk_div,
+        })
     }
 
     /// Set the SPI mode
@@ -168,7 +511,121 @@ where
     ///   - [`MODE_1`](`embedded_hal::spi::MODE_1`): CPOL = 0, CPHA = 1
     ///   - [`MODE_2`](`embedded_hal::spi::MODE_2`): CPOL = 1, CPHA = 0
     ///   - [`MODE_3`](`embedded_hal::spi::MODE_3`): CPOL = 1, CPHA = 1
-    pub fn set_mode(&mut self, mode: Mode) {
+    ///
+    /// Changing the clock polarity or phase in the middle of a transfer corrupts the data, so the bytes which are
+    /// still in flight are shifted out first, and the receiver and transmitter are disabled while the mode is changed
+    /// (e.g. when talking to multiple devices which need different modes on the same bus). Any data left in the RX
+    /// buffer is discarded.
+    pub fn set_mode(&mut self, mode: Mode) -> Result<(), SpiError> {
+        self.with_transceiver_disabled(|spi| spi.set_clock_mode(mode))
+    }
+
+    /// Set the number of data bits in a SPI frame (`FRAME.DATABITS`). The default is [`DataBits::Eight`].
+    ///
+    /// Like [`Spi::set_mode()`], the bytes which are still in flight are shifted out first, the receiver and
+    /// transmitter are disabled while the frame is changed, and any data left in the RX buffer is discarded.
+    ///
+    /// Note: the `SpiBus<u8>` methods move one byte per frame, so frames narrower than 8 bits only use the least
+    /// significant bits of each byte. Frames of 10 to 16 bits are moved with the `SpiBus<u16>` methods (see
+    /// [`SpiWord`]), and 9 bit frames are not supported by this driver.
+    pub fn configure_frame(&mut self, bits: DataBits) -> Result<(), SpiError> {
+        self.with_transceiver_disabled(|_| {
+            usartx::<N>()
+                .frame()
+                .modify(|_, w| unsafe { w.databits().bits(bits as u8) });
+        })
+    }
+
+    /// Set the chip select setup and hold times (`TIMING.CSSETUP` and `TIMING.CSHOLD`), in bit periods. These are
+    /// the delays between CS being asserted and the first clock edge, and between the last clock edge and CS being
+    /// de-asserted. Both are `0` after [`Spi::new()`].
+    ///
+    /// The register only defines delays of `0`, `1`, `2`, `3` and `7` bit periods, any other value returns
+    /// [`SpiError::InvalidCsTiming`].
+    ///
+    /// Note: the delays are only inserted when the USART drives the CS pin itself (auto chip select, `CTRL.AUTOCS`),
+    /// they have no effect on a CS pin toggled in software (e.g. [`Usart::into_spi_bus_with_cs()`]).
+    pub fn set_cs_timing(&mut self, setup_cycles: u8, hold_cycles: u8) -> Result<(), SpiError> {
+        // check both values before writing either of them
+        cs_timing_valid(setup_cycles)?;
+        cs_timing_valid(hold_cycles)?;
+
+        usartx::<N>().timing().modify(|_, w| {
+            match setup_cycles {
+                0 => w.cssetup().zero(),
+                1 => w.cssetup().one(),
+                2 => w.cssetup().two(),
+                3 => w.cssetup().three(),
+                _ => w.cssetup().seven(),
+            };
+
+            match hold_cycles {
+                0 => w.cshold().zero(),
+                1 => w.cshold().one(),
+                2 => w.cshold().two(),
+                3 => w.cshold().three(),
+                _ => w.cshold().seven(),
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Select where the receiver takes its input from (`USARTn_INPUT`). The RX pin is used by default.
+    ///
+    /// With [`RxInput::Prs`], the data is received from a PRS channel instead of the RX pin (e.g. to decode a signal
+    /// produced by another peripheral). The clock is still generated by this (master) SPI, and the RX pin is kept but
+    /// no longer sampled.
+    pub fn set_rx_input(&mut self, source: RxInput) {
+        usartx::<N>().input().modify(|_, w| match source {
+            RxInput::Pin => w.rxprs().clear_bit(),
+            RxInput::Prs(prs_ch) => {
+                unsafe { w.rxprssel().bits(prs_ch as u8) };
+                w.rxprs().set_bit()
+            }
+        });
+    }
+
+    /// Run `f` with access to the registers of the underlying USART, e.g. to use a feature which the driver does not
+    /// wrap, and return its result.
+    ///
+    /// ```rust,no_run
+    /// // Enable the TX complete interrupt
+    /// spi.with_raw(|usart| usart.ien().modify(|_, w| w.txc().set_bit()));
+    /// ```
+    ///
+    /// The driver does not check what `f` changed: reconfiguring the frame format, the clock, the routing or the
+    /// enabled state of the receiver and transmitter (or reading `RXDATA`) may break the subsequent transfers.
+    pub fn with_raw<R>(&mut self, f: impl FnOnce(&RegisterBlock) -> R) -> R {
+        f(usartx::<N>())
+    }
+
+    /// Wait for the transmitter to become idle, then run `f` with the receiver and transmitter disabled. The RX buffer
+    /// is cleared once they are enabled again.
+    fn with_transceiver_disabled(&mut self, f: impl FnOnce(&mut Self)) -> Result<(), SpiError> {
+        let usart_p = usartx::<N>();
+
+        self.wait_tx_idle()?;
+
+        usart_p.cmd().write(|w| {
+            w.rxdis().set_bit();
+            w.txdis().set_bit()
+        });
+
+        f(self);
+
+        usart_p.cmd().write(|w| {
+            w.rxen().set_bit();
+            w.txen().set_bit()
+        });
+
+        self.clear_rx();
+
+        Ok(())
+    }
+
+    /// Set the clock polarity and phase (`CTRL.CLKPOL` and `CTRL.CLKPHA`)
+    fn set_clock_mode(&mut self, mode: Mode) {
         let usart_p = usartx::<N>();
 
         usart_p.ctrl().modify(|_, w| {
@@ -232,32 +689,195 @@ where
     }
 
     fn wait_tx_complete(&self) -> Result<(), SpiError> {
-        // TODO: maybe calculate a counter based on minimum possible baudrate.
+        self.wait_status(|status| status.txc().bit_is_set(), SpiError::TxUnderflow)
+    }
+
+    fn wait_tx_idle(&self) -> Result<(), SpiError> {
+        self.wait_status(|status| status.txidle().bit_is_set(), SpiError::TxUnderflow)
+    }
+
+    fn wait_rx_full(&self) -> Result<(), SpiError> {
+        self.wait_status(|status| status.rxfull().bit_is_set(), SpiError::RxUnderflow)
+    }
+
+    /// Poll `STATUS` until `done` returns `true`, and return `timeout` if it does not within the bail-out count. The
+    /// wait is retried as configured with [`Spi::set_retry()`].
+    fn wait_status(&self, done: impl Fn(&status::R) -> bool, timeout: SpiError) -> Result<(), SpiError> {
+        // TODO: maybe calculate a bailout counter based on minimum possible baudrate.
+        // The current counter value was determined empirically with a requested 1Hz baudrate in *Release* build
+        // (actually it's ~316 Hz, with a Peripheral clock @ 19 Mhz).
         const MAX_COUNT: u32 = 1_000_000;
-        let mut bail_countdown = MAX_COUNT;
         let usart_p = usartx::<N>();
 
-        while usart_p.status().read().txc().bit_is_clear() {
-            bail_countdown -= 1;
+        self.with_retries(|| {
+            let mut bail_countdown = MAX_COUNT;
 
-            if bail_countdown == 0 {
-                return Err(SpiError::TxUnderflow);
+            while !done(&usart_p.status().read()) {
+                bail_countdown -= 1;
+
+                if bail_countdown == 0 {
+                    return Err(timeout);
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Call the bounded `wait` again, up to [`Spi::retries()`] times, as long as it times out
+    ///
+    /// `wait` must only poll the status flags, so that calling it again never writes a frame twice.
+    fn with_retries(&self, wait: impl Fn() -> Result<(), SpiError>) -> Result<(), SpiError> {
+        let mut res = wait();
+
+        for _ in 0..self.retries {
+            match res {
+                Err(SpiError::TxUnderflow | SpiError::RxUnderflow) => res = wait(),
+                _ => break,
             }
         }
-        Ok(())
+
+        res
+    }
+
+    /// Number of words which are written to the TX buffer at once: a single one when there is an inter-frame gap
+    fn frame_chunk_len<W: SpiWord>(&self) -> usize {
+        match self.inter_frame_delay {
+            0 => W::MAX_WORDS,
+            _ => 1,
+        }
+    }
+
+    /// Wait for the inter-frame gap, if any, after a frame is complete
+    fn inter_frame_gap(&self) {
+        if self.inter_frame_delay > 0 {
+            Clocks::delay_cycles(self.inter_frame_delay);
+        }
+    }
+
+    /// Discard any stale data in the RX buffer, and clear the RX error flags.
+    ///
+    /// `write()` does not read back the received bytes, so the RX buffer may be full (and may have overflowed) before
+    /// a `transfer()`
+    fn clear_rx(&mut self) {
+        let usart_p = usartx::<N>();
+
+        usart_p.cmd().write(|w| w.clearrx().set_bit());
+        usart_p.ifc().write(|w| {
+            w.rxof().set_bit();
+            w.rxuf().set_bit()
+        });
+    }
+
+    /// Check the RX and bus error flags (and clear them)
+    fn check_rx(&mut self) -> Result<(), SpiError> {
+        let usart_p = usartx::<N>();
+        let flags = usart_p.if_().read();
+
+        if flags.rxof().bit_is_set() {
+            usart_p.ifc().write(|w| w.rxof().set_bit());
+            Err(SpiError::RxOverflow)
+        } else if flags.rxuf().bit_is_set() {
+            usart_p.ifc().write(|w| w.rxuf().set_bit());
+            Err(SpiError::RxUnderflow)
+        } else {
+            self.check_bus_fault()
+        }
+    }
+
+    /// Check the bus error flags (and clear them): the slave select input was driven low while in master mode
+    /// (`IF.SSM`, i.e. another master is driving the bus), or the transmitted data did not match the data on the bus
+    /// (`IF.CCF`, only when collision check is enabled)
+    fn check_bus_fault(&mut self) -> Result<(), SpiError> {
+        let usart_p = usartx::<N>();
+        let flags = usart_p.if_().read();
+
+        if flags.ssm().bit_is_set() || flags.ccf().bit_is_set() {
+            usart_p.ifc().write(|w| {
+                w.ssm().set_bit();
+                w.ccf().set_bit()
+            });
+            Err(SpiError::BusFault)
+        } else {
+            Ok(())
+        }
     }
 }
 
+/// Largest value of the `DIV` field in `USARTn_CLKDIV`
+const CLKDIV_DIV_MAX: u32 = (1 << 20) - 1;
+
+/// Result of [`Spi::set_baudrate()`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Baudrate {
+    /// Baudrate which was requested
+    pub requested: HertzU32,
+    /// Closest baudrate which the clock divider can generate, and which is now used
+    pub achieved: HertzU32,
+    /// Value written to the `DIV` field of `USARTn_CLKDIV`
+    pub clkdiv: u32,
+}
+
+/// Number of data bits in a SPI frame (`DATABITS` field in `USARTn_FRAME`)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DataBits {
+    /// 4 data bits
+    Four = 1,
+    /// 5 data bits
+    Five,
+    /// 6 data bits
+    Six,
+    /// 7 data bits
+    Seven,
+    /// 8 data bits
+    Eight,
+    /// 9 data bits
+    Nine,
+    /// 10 data bits
+    Ten,
+    /// 11 data bits
+    Eleven,
+    /// 12 data bits
+    Twelve,
+    /// 13 data bits
+    Thirteen,
+    /// 14 data bits
+    Fourteen,
+    /// 15 data bits
+    Fifteen,
+    /// 16 data bits
+    Sixteen,
+}
+
+/// Input of the SPI receiver (`RXPRS` and `RXPRSSEL` fields in `USARTn_INPUT`)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RxInput {
+    /// The RX (MISO) pin
+    Pin,
+    /// One of the 12 PRS channels
+    Prs(PrsChannelId),
+}
+
 /// SPI Errors
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SpiError {
     /// Invalid baud rate
     InvalidBaudrate(HertzU32),
     /// Tx underflow
     TxUnderflow,
-    /// Rx underflow
+    /// Rx underflow: a byte was read from the RX buffer while it was empty
     RxUnderflow,
+    /// Rx overflow: a byte was received while the RX buffer was full, so received data was lost
+    RxOverflow,
+    /// The number of bit periods is not supported by the chip select setup/hold timing (`0..=3` or `7`)
+    InvalidCsTiming(u8),
+    /// Mode fault or collision: the slave select input was driven low by another master, or the data on the bus did
+    /// not match the transmitted data
+    BusFault,
 }
 
 impl Error for SpiError {
@@ -266,131 +886,212 @@ impl Error for SpiError {
             SpiError::InvalidBaudrate(_) => ErrorKind::Other,
             SpiError::TxUnderflow => ErrorKind::Other,
             SpiError::RxUnderflow => ErrorKind::Other,
+            SpiError::RxOverflow => ErrorKind::Overrun,
+            SpiError::InvalidCsTiming(_) => ErrorKind::Other,
+            SpiError::BusFault => ErrorKind::ModeFault,
         }
     }
 }
 
+/// Check that `cycles` can be written to `TIMING.CSSETUP` or `TIMING.CSHOLD`
+fn cs_timing_valid(cycles: u8) -> Result<(), SpiError> {
+    match cycles {
+        0..=3 | 7 => Ok(()),
+        _ => Err(SpiError::InvalidCsTiming(cycles)),
+    }
+}
+
 // Implementations for `ErrorType` to be used by `SpiBus` `embedded-hal` trait
-impl<const N: u8, PCLK, PTX, PRX> ErrorType for Spi<N, Usart<N>, PCLK, PTX, PRX> {
+impl<const N: u8, PCLK, PTX, PRX, PCS> ErrorType for Spi<N, Usart<N>, PCLK, PTX, PRX, PCS> {
     type Error = SpiError;
 }
 
-impl<const N: u8, PCLK, PTX, PRX> SpiBus<u8> for Spi<N, Usart<N>, PCLK, PTX, PRX>
+impl<const N: u8, PCLK, PTX, PRX, PCS, W> SpiBus<W> for Spi<N, Usart<N>, PCLK, PTX, PRX, PCS>
 where
     PCLK: OutputPin + UsartClkPin,
     PTX: OutputPin + UsartTxPin,
     PRX: InputPin + UsartRxPin,
+    W: SpiWord,
 {
-    fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+    fn read(&mut self, words: &mut [W]) -> Result<(), Self::Error> {
         self.transfer(words, &[])
     }
 
-    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
-        let mut words_iter = words.iter();
+    fn write(&mut self, words: &[W]) -> Result<(), Self::Error> {
         let usart_p = usartx::<N>();
 
-        // This closure  waits until there are at least 2 (out of 3) bytes available in the TX buffer
-        // The first position in the TX Buffer is the Shift Register, which is not accessible through registers
-        // See [Reference Manual](../../../../../doc/efm32pg1-rm.pdf#page=466)
-        let wait_for_buffer_space = || {
-            // TODO: maybe calculate a bailout counter based on minimum possible baudrate.
-            // The current counter value was determined empirically with a requested 1Hz baudrate in *Release* build
-            // (actually it's ~316 Hz, with a Peripheral clock @ 19 Mhz).
-            const MAX_COUNT: u32 = 1_000_000;
-            let mut bail_countdown = MAX_COUNT;
+        if self.inter_frame_delay > 0 {
+            for chunk in words.chunks(1) {
+                W::write_tx(usart_p, chunk);
+                self.wait_tx_complete()?;
+                self.inter_frame_gap();
+            }
 
-            // Wait until there are at least 2 available bytes (out of 3) in the TX buffer.
-            while usart_p.status().read().txbufcnt().bits() > 1 {
-                bail_countdown -= 1;
+            return Ok(());
+        }
 
-                if bail_countdown == 0 {
-                    return Err(SpiError::TxUnderflow);
+        for chunk in words.chunks(W::MAX_WORDS) {
+            // Wait until there are at least 2 (out of 3) bytes available in the TX buffer. The first position in the
+            // TX Buffer is the Shift Register, which is not accessible through registers.
+            // See [Reference Manual](../../../../../doc/efm32pg1-rm.pdf#page=466)
+            self.wait_status(|status| status.txbufcnt().bits() <= 1, SpiError::TxUnderflow)?;
+            W::write_tx(usart_p, chunk);
+        }
+
+        Ok(())
+    }
+
+    fn transfer(&mut self, read: &mut [W], write: &[W]) -> Result<(), Self::Error> {
+        let max_word_count = max(read.len(), write.len());
+        let read_filler = W::from_filler(self.read_filler);
+        let usart_p = usartx::<N>();
+
+        let chunk_len = self.frame_chunk_len::<W>();
+
+        self.clear_rx();
+
+        for i in (0..max_word_count).step_by(chunk_len) {
+            let count = min(chunk_len, max_word_count - i);
+            let mut tx = [read_filler; MAX_WORDS];
+            let mut rx = [read_filler; MAX_WORDS];
+
+            // Send the filler word once the `write` slice is exhausted
+            for (j, word) in tx[..count].iter_mut().enumerate() {
+                if let Some(w) = write.get(i + j) {
+                    *word = *w;
                 }
             }
-            Ok(())
-        };
 
-        while let Some(b0) = words_iter.next() {
-            let usart_p = usartx::<N>();
-            wait_for_buffer_space()?;
+            W::write_tx(usart_p, &tx[..count]);
 
-            if let Some(b1) = words_iter.next() {
-                // We have 2 bytes to send, use the `txdouble` register
-                usart_p.txdouble().write(|w| unsafe {
-                    w.txdata0().bits(*b0);
-                    w.txdata1().bits(*b1)
-                });
-            } else {
-                // We have only 1 byte left to send, use the `txdata` register
-                usart_p.txdata().write(|w| unsafe { w.txdata().bits(*b0) });
+            self.wait_tx_complete()?;
+
+            // Words received after the `read` slice is exhausted are discarded
+            W::read_rx(usart_p, &mut rx[..count]);
+            for (j, word) in rx[..count].iter().enumerate() {
+                if let Some(r) = read.get_mut(i + j) {
+                    *r = *word;
+                }
             }
+
+            self.check_rx()?;
+            self.inter_frame_gap();
         }
 
         Ok(())
     }
 
-    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
-        let max_byte_count = max(read.len(), write.len());
-        let mut tx_iter = write.iter();
-        let mut rx_iter = read.iter_mut();
-        let mut rx_discard = 0;
+    fn transfer_in_place(&mut self, words: &mut [W]) -> Result<(), Self::Error> {
         let usart_p = usartx::<N>();
 
-        for (txo, rxo) in (0..max_byte_count).map(|_| (tx_iter.next(), rx_iter.next())) {
-            let tx_byte = match txo {
-                Some(txr) => *txr,
-                None => Self::FILLER_BYTE,
-            };
+        let chunk_len = self.frame_chunk_len::<W>();
 
-            let rx_byte = match rxo {
-                Some(rx) => rx,
-                None => &mut rx_discard,
-            };
+        self.clear_rx();
 
-            usart_p
-                .txdata()
-                .write(|w| unsafe { w.txdata().bits(tx_byte) });
+        for chunk in words.chunks_mut(chunk_len) {
+            W::write_tx(usart_p, chunk);
 
             self.wait_tx_complete()?;
 
-            *rx_byte = usart_p.rxdata().read().rxdata().bits();
+            W::read_rx(usart_p, chunk);
+
+            self.check_rx()?;
+            self.inter_frame_gap();
         }
 
         Ok(())
     }
 
-    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
-        let mut words_iter = words.iter_mut();
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Self::flush(self)
+    }
+}
 
-        while let Some(b0) = words_iter.next() {
-            let usart_p = usartx::<N>();
+/// Maximum number of words which a [`SpiWord`] moves with a single access to the TX or RX buffer
+const MAX_WORDS: usize = 2;
 
-            if let Some(b1) = words_iter.next() {
-                // We have 2 bytes to send, use the `txdouble` register
-                usart_p.txdouble().write(|w| unsafe {
-                    w.txdata0().bits(*b0);
-                    w.txdata1().bits(*b1)
-                });
+/// Word type of the `SpiBus` implementations of [`Spi`]
+///
+/// - `u8` is used for frames of up to 8 data bits. Pairs of bytes are moved with a single access to the `TXDOUBLE`
+///   and `RXDOUBLE` registers.
+/// - `u16` is used for frames of 10 to 16 data bits (see [`Spi::configure_frame()`]). Each word is moved with a single
+///   access to the `TXDOUBLE` and `RXDOUBLE` registers, which hold one frame in this case.
+///
+/// 9 bit frames need the extended data registers (`TXDATAX`, `RXDATAX`), which are not used by this driver.
+///
+/// This trait is sealed, so it cannot be implemented outside of this crate.
+pub trait SpiWord: Copy + Sealed + 'static {
+    /// Number of words which are moved with a single access to the TX or RX buffer (at most `2`)
+    const MAX_WORDS: usize;
 
-                self.wait_tx_complete()?;
+    /// Convert the read filler byte (see [`Spi::set_read_filler()`]) to a word
+    fn from_filler(filler: u8) -> Self;
 
-                *b0 = usart_p.rxdouble().read().rxdata0().bits();
-                *b1 = usart_p.rxdouble().read().rxdata1().bits();
-            } else {
-                // We have only 1 byte left to send, use the `txdata` register
-                usart_p.txdata().write(|w| unsafe { w.txdata().bits(*b0) });
+    /// Write `words` (at most [`SpiWord::MAX_WORDS`]) to the TX buffer
+    fn write_tx(usart_p: &RegisterBlock, words: &[Self]);
 
-                self.wait_tx_complete()?;
+    /// Read `words.len()` (at most [`SpiWord::MAX_WORDS`]) words from the RX buffer
+    fn read_rx(usart_p: &RegisterBlock, words: &mut [Self]);
+}
 
-                *b0 = usart_p.rxdata().read().rxdata().bits();
+impl Sealed for u8 {}
+impl Sealed for u16 {}
+
+impl SpiWord for u8 {
+    const MAX_WORDS: usize = 2;
+
+    fn from_filler(filler: u8) -> Self {
+        filler
+    }
+
+    fn write_tx(usart_p: &RegisterBlock, words: &[Self]) {
+        match words {
+            // We have 2 bytes to send, use the `txdouble` register
+            [b0, b1] => usart_p.txdouble().write(|w| unsafe {
+                w.txdata0().bits(*b0);
+                w.txdata1().bits(*b1)
+            }),
+            // We have only 1 byte left to send, use the `txdata` register
+            [b0] => usart_p.txdata().write(|w| unsafe { w.txdata().bits(*b0) }),
+            _ => unreachable!(),
+        };
+    }
+
+    fn read_rx(usart_p: &RegisterBlock, words: &mut [Self]) {
+        match words {
+            // Read `rxdouble` only once: each read pops both bytes from the RX buffer
+            [b0, b1] => {
+                let rxdouble = usart_p.rxdouble().read();
+                *b0 = rxdouble.rxdata0().bits();
+                *b1 = rxdouble.rxdata1().bits();
             }
+            [b0] => *b0 = usart_p.rxdata().read().rxdata().bits(),
+            _ => unreachable!(),
         }
+    }
+}
 
-        Ok(())
+impl SpiWord for u16 {
+    const MAX_WORDS: usize = 1;
+
+    fn from_filler(filler: u8) -> Self {
+        u16::from_le_bytes([filler, filler])
     }
 
-    fn flush(&mut self) -> Result<(), Self::Error> {
-        self.wait_tx_complete()
+    fn write_tx(usart_p: &RegisterBlock, words: &[Self]) {
+        // With frames wider than 9 bits, `txdouble` holds a single frame, least significant byte first
+        let [lo, hi] = words[0].to_le_bytes();
+
+        usart_p.txdouble().write(|w| unsafe {
+            w.txdata0().bits(lo);
+            w.txdata1().bits(hi)
+        });
+    }
+
+    fn read_rx(usart_p: &RegisterBlock, words: &mut [Self]) {
+        // Read `rxdouble` only once: each read pops the whole frame from the RX buffer
+        let rxdouble = usart_p.rxdouble().read();
+        words[0] = u16::from_le_bytes([rxdouble.rxdata0().bits(), rxdouble.rxdata1().bits()]);
     }
 }
 
@@ -398,13 +1099,13 @@ where
 ///
 /// This trait is implemented privately in this module for select pins specified in the
 /// [Data Sheet - page 85](../../../../../doc/efm32pg1-datasheet.pdf#page=85), and it is used to constrain the type of the `pin_clk`
-/// parameter passed to the `into_spi_bus()` method of the `UsartSpiExt` trait.
+/// parameter passed to the [`Usart::into_spi_bus()`](crate::usart::Usart::into_spi_bus) method.
 ///
 /// Note: if you try to create an `Spi` instance and get a compiler error like
 /// ```text
-///     the trait `efm32pg1b_hal::spi::UsartClkPin` is not implemented for
-///     `efm32pg1b_hal::gpio::Pin<'D', 8, efm32pg1b_hal::gpio::Input>`, which is required by
-///     `efm32pg1b_hal::efm32pg1b_pac::Usart1: efm32pg1b_hal::spi::UsartSpiExt<_, _, _>`
+///     the trait `efm32pg1b_hal::usart::spi::UsartClkPin` is not implemented for
+///     `efm32pg1b_hal::gpio::Pin<'D', 8, efm32pg1b_hal::gpio::OutPp>`, which is required by a bound in
+///     `efm32pg1b_hal::usart::Usart::<1>::into_spi_bus`
 /// ```
 ///
 /// then it's probably the case that you're trying to use a Pin as an SPI Clock pin when that pin is not available
@@ -470,13 +1171,13 @@ impl_clock_loc!(31, 'A', 1);
 ///
 /// This trait is implemented privately in this module for select pins specified in the
 /// [Data Sheet - page 85](../../../../../doc/efm32pg1-datasheet.pdf#page=85), and it is used to constrain the type of the `pin_tx`
-/// parameter passed to the `into_spi_bus()` method of the `UsartSpiExt` trait.
+/// parameter passed to the [`Usart::into_spi_bus()`](crate::usart::Usart::into_spi_bus) method.
 ///
 /// Note: if you try to create an `Spi` instance and get a compiler error like
 /// ```text
-///     the trait `efm32pg1b_hal::spi::UsartTxPin` is not implemented for
-///     `efm32pg1b_hal::gpio::Pin<'D', 8, efm32pg1b_hal::gpio::Input>`, which is required by
-///     `efm32pg1b_hal::efm32pg1b_pac::Usart1: efm32pg1b_hal::spi::UsartSpiExt<_, _, _>`
+///     the trait `efm32pg1b_hal::usart::spi::UsartTxPin` is not implemented for
+///     `efm32pg1b_hal::gpio::Pin<'D', 8, efm32pg1b_hal::gpio::OutPp>`, which is required by a bound in
+///     `efm32pg1b_hal::usart::Usart::<1>::into_spi_bus`
 /// ```
 ///
 /// then it's probably the case that you're trying to use a Pin as an SPI Tx pin when that pin is not available
@@ -542,13 +1243,13 @@ impl_tx_loc!(31, 'F', 7);
 ///
 /// This trait is implemented privately in this module for select pins specified in the
 /// [Data Sheet - page 86](../../../../../doc/efm32pg1-datasheet.pdf#page=86), and it is used to constrain the type of the `pin_rx`
-/// parameter passed to the `into_spi_bus()` method of the `UsartSpiExt` trait.
+/// parameter passed to the [`Usart::into_spi_bus()`](crate::usart::Usart::into_spi_bus) method.
 ///
 /// Note: if you try to create an `Spi` instance and get a compiler error like
 /// ```sh
-///     the trait `efm32pg1b_hal::spi::UsartRxPin` is not implemented for
-///     `efm32pg1b_hal::gpio::Pin<'D', 8, efm32pg1b_hal::gpio::Input>`, which is required by
-///     `efm32pg1b_hal::efm32pg1b_pac::Usart1: efm32pg1b_hal::spi::UsartSpiExt<_, _, _>`
+///     the trait `efm32pg1b_hal::usart::spi::UsartRxPin` is not implemented for
+///     `efm32pg1b_hal::gpio::Pin<'D', 8, efm32pg1b_hal::gpio::InFloat>`, which is required by a bound in
+///     `efm32pg1b_hal::usart::Usart::<1>::into_spi_bus`
 /// ```
 ///
 /// then it's probably the case that you're trying to use a Pin as an SPI Rx pin when that pin is not available
@@ -612,7 +1313,8 @@ impl_rx_loc!(31, 'A', 0);
 
 /// Marker trait to enforce which (output) pins can be used as an SPI CS output.
 ///
-/// TODO: this is not actually used when instantiating an SPI. Should it?
+/// This trait is used to constrain the type of the `pin_cs` parameter passed to the
+/// [`Usart::into_spi_bus_with_cs()`](crate::usart::Usart::into_spi_bus_with_cs) method.
 ///
 /// Please consult the [Data Sheet - page 85](../../../../../doc/efm32pg1-datasheet.pdf#page=85) (`US0_CS` or `US1_CS` Alternate
 /// Functionality) to see which pins can be used as SPI CS pins.
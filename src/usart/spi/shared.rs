@@ -0,0 +1,252 @@
+//! Shared SPI bus
+//!
+//! Share one [`Spi`] bus between several drivers on a single core, in the style of the `embedded-hal-bus`
+//! `RefCellDevice`. The bus is stored in a [`RefCell`], and each driver gets either:
+//!
+//! - a [`RefCellDevice`], which implements [`SpiDevice`] and selects its device with its own CS pin (or with the CS
+//!   pin routed to the USART, see [`BusCs`]), for drivers which expect an `SpiDevice`
+//! - a [`RefCellBus`], which implements [`SpiBus`], for drivers which take a bus and control their CS pin themselves
+//!
+//! ```rust,no_run
+//! let bus = RefCell::new(usart1.into_spi_bus_with_cs(clk, tx, rx, gpio.pc9.into_mode::<OutPp>(), MODE_0));
+//!
+//! // Driver which controls its own CS pin
+//! let mut disp = Ls013b7dh03::new(RefCellBus::new(&bus), gpio.pd14.into_mode::<OutPp>(), disp_en, &mut buffer);
+//! // Driver which takes an `SpiDevice`, selected with the CS pin owned by the bus (`PC9`)
+//! let mut sensor = Sensor::new(RefCellDevice::new(&bus, BusCs, &clocks).unwrap());
+//! ```
+//!
+//! All the drivers share the configuration of the bus (SPI mode, baud rate, etc.), so the devices must support the
+//! same configuration. The bus is only borrowed for the duration of a single call, so the drivers must not be used
+//! from interrupt handlers (a call which interrupts another one on the same bus panics).
+
+use crate::{
+    cmu::Clocks,
+    usart::{
+        spi::{Spi, UsartClkPin, UsartCsPin, UsartRxPin, UsartTxPin},
+        Usart,
+    },
+};
+use core::{cell::RefCell, fmt::Debug};
+use embedded_hal::{
+    digital::{InputPin, OutputPin},
+    spi::{Error, ErrorKind, ErrorType, Operation, SpiBus, SpiDevice},
+};
+use fugit::HertzU32;
+
+/// [`SpiBus`] on a shared bus, for drivers which control their own CS pin
+///
+/// Each method borrows the bus only while it runs.
+#[derive(Debug)]
+pub struct RefCellBus<'a, BUS> {
+    bus: &'a RefCell<BUS>,
+}
+
+impl<'a, BUS> RefCellBus<'a, BUS> {
+    /// Create a handle to the shared `bus`
+    pub fn new(bus: &'a RefCell<BUS>) -> Self {
+        Self { bus }
+    }
+}
+
+impl<BUS> ErrorType for RefCellBus<'_, BUS>
+where
+    BUS: ErrorType,
+{
+    type Error = BUS::Error;
+}
+
+impl<BUS, W> SpiBus<W> for RefCellBus<'_, BUS>
+where
+    BUS: SpiBus<W>,
+    W: Copy + 'static,
+{
+    fn read(&mut self, words: &mut [W]) -> Result<(), Self::Error> {
+        self.bus.borrow_mut().read(words)
+    }
+
+    fn write(&mut self, words: &[W]) -> Result<(), Self::Error> {
+        self.bus.borrow_mut().write(words)
+    }
+
+    fn transfer(&mut self, read: &mut [W], write: &[W]) -> Result<(), Self::Error> {
+        self.bus.borrow_mut().transfer(read, write)
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [W]) -> Result<(), Self::Error> {
+        self.bus.borrow_mut().transfer_in_place(words)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.bus.borrow_mut().flush()
+    }
+}
+
+/// [`SpiDevice`] on a shared bus
+///
+/// The device is selected (its CS pin is driven low) for the duration of a transaction, and the bus is borrowed for the
+/// whole transaction, so the transactions of different devices cannot be interleaved.
+#[derive(Debug)]
+pub struct RefCellDevice<'a, BUS, CS> {
+    bus: &'a RefCell<BUS>,
+    cs: CS,
+    /// HF Core Clock, used for the [`Operation::DelayNs`] operations
+    core_clk: HertzU32,
+}
+
+impl<'a, BUS, CS> RefCellDevice<'a, BUS, CS>
+where
+    CS: DeviceCs<BUS>,
+{
+    /// Create a device on the shared `bus`, selected with `cs`, and deselect it.
+    ///
+    /// The [`Operation::DelayNs`] operations busy wait on the HF Core Clock, so they are only accurate if the clocks
+    /// are not changed while the device is in use.
+    pub fn new(bus: &'a RefCell<BUS>, mut cs: CS, clocks: &Clocks) -> Result<Self, CS::Error> {
+        cs.set_selected(&mut *bus.borrow_mut(), false)?;
+
+        Ok(Self {
+            bus,
+            cs,
+            core_clk: clocks.hf_core_clk(),
+        })
+    }
+
+    /// Release the CS pin of this device
+    pub fn free(self) -> CS {
+        self.cs
+    }
+}
+
+impl<BUS, CS> ErrorType for RefCellDevice<'_, BUS, CS>
+where
+    BUS: ErrorType,
+    CS: DeviceCs<BUS>,
+{
+    type Error = DeviceError<BUS::Error, CS::Error>;
+}
+
+impl<BUS, CS, W> SpiDevice<W> for RefCellDevice<'_, BUS, CS>
+where
+    BUS: SpiBus<W>,
+    CS: DeviceCs<BUS>,
+    W: Copy + 'static,
+{
+    fn transaction(&mut self, operations: &mut [Operation<'_, W>]) -> Result<(), Self::Error> {
+        let bus = &mut *self.bus.borrow_mut();
+        let core_clk = self.core_clk;
+
+        self.cs.set_selected(bus, true).map_err(DeviceError::Cs)?;
+
+        let op_res = operations.iter_mut().try_for_each(|op| match op {
+            Operation::Read(words) => bus.read(words),
+            Operation::Write(words) => bus.write(words),
+            Operation::Transfer(read, write) => bus.transfer(read, write),
+            Operation::TransferInPlace(words) => bus.transfer_in_place(words),
+            Operation::DelayNs(ns) => {
+                // The delay starts once the previous frames are on the wire
+                bus.flush()?;
+                delay_ns(core_clk, *ns);
+                Ok(())
+            }
+        });
+
+        // The device is only deselected once the last frame is on the wire, even if an operation failed
+        let flush_res = bus.flush();
+        let cs_res = self.cs.set_selected(bus, false);
+
+        op_res.map_err(DeviceError::Spi)?;
+        flush_res.map_err(DeviceError::Spi)?;
+        cs_res.map_err(DeviceError::Cs)?;
+
+        Ok(())
+    }
+}
+
+/// Busy wait for at least `ns` nanoseconds, as measured by the HF Core Clock `core_clk`
+fn delay_ns(core_clk: HertzU32, ns: u32) {
+    let mut cycles = (core_clk.raw() as u64 * ns as u64).div_ceil(1_000_000_000);
+
+    while cycles > 0 {
+        let chunk = cycles.min(u32::MAX as u64);
+        Clocks::delay_cycles(chunk as u32);
+        cycles -= chunk;
+    }
+}
+
+/// Chip select of a [`RefCellDevice`] on the shared bus `BUS`
+///
+/// Implemented for all the output pins (any GPIO pin can select a device), and for [`BusCs`].
+pub trait DeviceCs<BUS> {
+    /// Error returned when the chip select cannot be driven
+    type Error: Debug;
+
+    /// Select (`true`) or deselect (`false`) the device. The chip select is active low.
+    fn set_selected(&mut self, bus: &mut BUS, selected: bool) -> Result<(), Self::Error>;
+}
+
+impl<BUS, P> DeviceCs<BUS> for P
+where
+    P: OutputPin,
+{
+    type Error = P::Error;
+
+    fn set_selected(&mut self, _bus: &mut BUS, selected: bool) -> Result<(), Self::Error> {
+        match selected {
+            true => self.set_low(),
+            false => self.set_high(),
+        }
+    }
+}
+
+/// Chip select which uses the CS pin owned by the bus, i.e. the [`UsartCsPin`] which was given to
+/// [`Usart::into_spi_bus_with_cs()`] and is routed to the USART (`ROUTELOC0.CSLOC`).
+///
+/// At most one device on the bus can be selected this way, the others need their own CS pins.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BusCs;
+
+impl<const N: u8, PCLK, PTX, PRX, PCS> DeviceCs<Spi<N, Usart<N>, PCLK, PTX, PRX, PCS>> for BusCs
+where
+    PCLK: OutputPin + UsartClkPin,
+    PTX: OutputPin + UsartTxPin,
+    PRX: InputPin + UsartRxPin,
+    PCS: OutputPin + UsartCsPin,
+{
+    type Error = PCS::Error;
+
+    fn set_selected(
+        &mut self,
+        bus: &mut Spi<N, Usart<N>, PCLK, PTX, PRX, PCS>,
+        selected: bool,
+    ) -> Result<(), Self::Error> {
+        match selected {
+            true => bus.cs_pin().set_low(),
+            false => bus.cs_pin().set_high(),
+        }
+    }
+}
+
+/// Errors of a [`RefCellDevice`]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DeviceError<BUS, CS> {
+    /// The bus returned an error
+    Spi(BUS),
+    /// The chip select could not be driven
+    Cs(CS),
+}
+
+impl<BUS, CS> Error for DeviceError<BUS, CS>
+where
+    BUS: Error,
+    CS: Debug,
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            DeviceError::Spi(e) => e.kind(),
+            DeviceError::Cs(_) => ErrorKind::ChipSelectFault,
+        }
+    }
+}
@@ -0,0 +1,359 @@
+//! GPIO tests
+//!
+//! Run with `cargo test --test gpio_test --features="defmt"`
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests(setup=rtt_target::rtt_init_defmt!())]
+mod tests {
+    use efm32pg1b_hal::{
+        bitbang,
+        cmu::CmuExt,
+        erased_pins,
+        gpio::{
+            complementary::ComplementaryPins,
+            debug::is_debug_pin,
+            dynamic::{DynamicPin, PinMode},
+            erased::{configure_all, ErasedPin},
+            pin::{pin_exists, PinId},
+            port::{DataInCtrl, PortDataInDisable, PortId},
+            Analog, Disabled, DisabledPu, Gpio, GpioError, InFilt, InFloat, InPd, InPdFilt, InPu, InPuFilt, OutOd,
+            OutOdAlt, OutOdFilt, OutOdFiltAlt, OutOdPu, OutOdPuAlt, OutOdPuFilt, OutOdPuFiltAlt, OutOs, OutOsPd, OutPp,
+            OutPpAlt, Pull,
+        },
+        pac,
+    };
+    use embedded_hal::{
+        digital::{InputPin, OutputPin, PinState, StatefulOutputPin},
+        spi::{SpiBus, MODE_3},
+    };
+    use fugit::RateExtU32;
+
+    #[test]
+    fn erased_pins_of_the_same_port_are_written_together() {
+        let p = pac::Peripherals::take().unwrap();
+        let gpio = Gpio::new(p.gpio);
+        let mut pins: [ErasedPin<OutPp>; 3] = configure_all(erased_pins![gpio.pd13, gpio.pd14, gpio.pf4]);
+
+        let port_d = unsafe { &*pac::Gpio::ptr() }.port_d();
+        let port_f = unsafe { &*pac::Gpio::ptr() }.port_f();
+
+        ErasedPin::write_all(pins.iter_mut().zip([false, false, false]));
+        assert_eq!(port_d.dout().read().pins_dout().bits() & (0b11 << 13), 0);
+        assert_eq!(port_f.dout().read().pins_dout().bits() & (1 << 4), 0);
+
+        // Both `PD13` and `PD14` are set by the same `DOUT` write
+        ErasedPin::write_all(pins.iter_mut().zip([true, true, false]));
+        assert_eq!(port_d.dout().read().pins_dout().bits() & (0b11 << 13), 0b11 << 13);
+        assert_eq!(port_f.dout().read().pins_dout().bits() & (1 << 4), 0);
+
+        // `PinState` can be used instead of `bool`, in any pin order
+        let [pd13, pd14, pf4] = &mut pins;
+        ErasedPin::write_all([(pd13, PinState::Low), (pf4, PinState::High), (pd14, PinState::High)]);
+        assert_eq!(port_d.dout().read().pins_dout().bits() & (0b11 << 13), 0b10 << 13);
+        assert_eq!(port_f.dout().read().pins_dout().bits() & (1 << 4), 1 << 4);
+    }
+
+    #[test]
+    fn complementary_pins_are_driven_to_opposite_levels() {
+        let p = pac::Peripherals::take().unwrap();
+        let gpio = Gpio::new(p.gpio);
+
+        let port_d = unsafe { &*pac::Gpio::ptr() }.port_d();
+        let port_f = unsafe { &*pac::Gpio::ptr() }.port_f();
+
+        // Same port: both pins are written with a single `DOUT` write
+        let mut pair = ComplementaryPins::new(gpio.pd13.into_mode::<OutPp>(), gpio.pd14.into_mode::<OutPp>()).unwrap();
+
+        pair.set(PinState::High);
+        assert_eq!(port_d.dout().read().pins_dout().bits() & (0b11 << 13), 0b01 << 13);
+        pair.set(PinState::Low);
+        assert_eq!(port_d.dout().read().pins_dout().bits() & (0b11 << 13), 0b10 << 13);
+        pair.set_both(PinState::High);
+        assert_eq!(port_d.dout().read().pins_dout().bits() & (0b11 << 13), 0b11 << 13);
+        pair.set_both(PinState::Low);
+        assert_eq!(port_d.dout().read().pins_dout().bits() & (0b11 << 13), 0);
+
+        // Different ports
+        let (pd13, _pd14) = pair.free();
+        let mut pair = ComplementaryPins::new(pd13, gpio.pf4.into_mode::<OutPp>()).unwrap();
+
+        pair.set(PinState::Low);
+        assert_eq!(port_d.dout().read().pins_dout().bits() & (1 << 13), 0);
+        assert_eq!(port_f.dout().read().pins_dout().bits() & (1 << 4), 1 << 4);
+        pair.set(PinState::High);
+        assert_eq!(port_d.dout().read().pins_dout().bits() & (1 << 13), 1 << 13);
+        assert_eq!(port_f.dout().read().pins_dout().bits() & (1 << 4), 0);
+
+        // Input pins cannot be paired
+        let (pd13, _pf4) = pair.free();
+        let pd15 = gpio.pd15.into_dynamic_pin().into_mode::<InFloat>();
+        assert!(ComplementaryPins::new(pd13, pd15).is_err());
+    }
+
+    #[test]
+    fn set_pull_keeps_the_input_filter() {
+        let p = pac::Peripherals::take().unwrap();
+        let gpio = Gpio::new(p.gpio);
+        let mut pd15 = gpio.pd15.into_mode::<InPuFilt>();
+
+        let port_d = unsafe { &*pac::Gpio::ptr() }.port_d();
+        let mode = || (port_d.modeh().read().bits() >> ((15 - 8) * 4)) & 0xF;
+        let dout = || port_d.dout().read().pins_dout().bits() & (1 << 15) != 0;
+
+        // `INPUTPULLFILTER`, pull-up
+        assert_eq!((mode(), dout()), (3, true));
+
+        pd15.set_pull(Pull::Down);
+        assert_eq!((mode(), dout()), (3, false));
+
+        // `INPUT`, with `DOUT` enabling the filter
+        pd15.set_pull(Pull::Floating);
+        assert_eq!((mode(), dout()), (1, true));
+
+        pd15.set_pull(Pull::Up);
+        assert_eq!((mode(), dout()), (3, true));
+
+        // without the filter
+        pd15.set_filter(false);
+        pd15.set_pull(Pull::Floating);
+        assert_eq!((mode(), dout()), (1, false));
+
+        pd15.set_pull(Pull::Down);
+        assert_eq!((mode(), dout()), (2, false));
+    }
+
+    #[test]
+    fn disable_all_keeps_the_excluded_pins() {
+        let p = pac::Peripherals::take().unwrap();
+        let gpio = Gpio::new(p.gpio);
+        let _pd13 = gpio.pd13.into_output_with_state::<OutPp>(PinState::High);
+        let pd14 = gpio.pd14.into_mode::<InPu>();
+
+        let port_d = unsafe { &*pac::Gpio::ptr() }.port_d();
+        let mode = |pin: u32| (port_d.modeh().read().bits() >> ((pin - 8) * 4)) & 0xF;
+        let dout = |pin: u32| port_d.dout().read().pins_dout().bits() & (1 << pin) != 0;
+
+        Gpio::disable_all(&[&pd14]);
+
+        // `DISABLED`, without pull-up
+        assert_eq!((mode(13), dout(13)), (0, false));
+        // `INPUTPULL`, pull-up
+        assert_eq!((mode(14), dout(14)), (2, true));
+    }
+
+    #[test]
+    fn output_latch_and_pin_level_of_a_push_pull_pin() {
+        let p = pac::Peripherals::take().unwrap();
+        let gpio = Gpio::new(p.gpio);
+        let mut pd13 = gpio.pd13.into_output_with_state::<OutPp>(PinState::Low);
+
+        assert_eq!((pd13.output_latch(), pd13.pin_level().unwrap()), (false, false));
+
+        pd13.set_high().unwrap();
+        assert_eq!((pd13.output_latch(), pd13.pin_level().unwrap()), (true, true));
+        assert!(pd13.is_set_high().unwrap());
+    }
+
+    #[test]
+    fn pin_level_of_an_alternate_output_pin_uses_the_alternate_data_in() {
+        let p = pac::Peripherals::take().unwrap();
+        let mut gpio = Gpio::new(p.gpio);
+        let mut pd13 = gpio.pd13.into_output_with_state::<OutPpAlt>(PinState::High);
+
+        // Only the primary Data In is disabled, which is not used by the _Alternate Output_ modes
+        gpio.port_d.set_din_dis(DataInCtrl::Disabled);
+        assert!(pd13.pin_level().unwrap());
+
+        gpio.port_d.set_din_dis(DataInCtrl::Enabled);
+        gpio.port_d.set_din_dis_alt(DataInCtrl::Disabled);
+        assert!(matches!(pd13.pin_level(), Err(GpioError::DataInDisabled)));
+        assert!(pd13.output_latch());
+
+        gpio.port_d.set_din_dis_alt(DataInCtrl::Enabled);
+    }
+
+    #[test]
+    fn output_only_port_can_still_toggle_its_pins() {
+        let p = pac::Peripherals::take().unwrap();
+        let mut gpio = Gpio::new(p.gpio);
+        let mut pd13 = gpio.pd13.into_output_with_state::<OutPp>(PinState::Low);
+
+        gpio.port_d.set_output_only(DataInCtrl::Disabled);
+        assert!(gpio.port_d.din_dis() && gpio.port_d.din_dis_alt());
+
+        // The output latch is still readable, the input buffer is not
+        pd13.toggle().unwrap();
+        assert!(pd13.is_set_high().unwrap());
+        assert!(matches!(pd13.pin_level(), Err(GpioError::DataInDisabled)));
+
+        gpio.port_d.set_output_only(DataInCtrl::Enabled);
+        assert!(pd13.pin_level().unwrap());
+    }
+
+    #[test]
+    fn dynamic_pin_reads_depend_on_the_mode() {
+        // Mode, readable with `is_high()` (input modes), readable with `read_output_level()` (output modes)
+        const MODES: [(PinMode, bool, bool); 21] = [
+            (PinMode::Disabled, false, false),
+            (PinMode::DisabledPu, false, false),
+            (PinMode::Analog, false, false),
+            (PinMode::InFloat, true, false),
+            (PinMode::InFilt, true, false),
+            (PinMode::InPu, true, false),
+            (PinMode::InPuFilt, true, false),
+            (PinMode::InPd, true, false),
+            (PinMode::InPdFilt, true, false),
+            (PinMode::OutPp, false, true),
+            (PinMode::OutOs, false, true),
+            (PinMode::OutOsPd, false, true),
+            (PinMode::OutOd, false, true),
+            (PinMode::OutOdFilt, false, true),
+            (PinMode::OutOdPu, false, true),
+            (PinMode::OutOdPuFilt, false, true),
+            (PinMode::OutPpAlt, false, true),
+            (PinMode::OutOdAlt, false, true),
+            (PinMode::OutOdFiltAlt, false, true),
+            (PinMode::OutOdPuAlt, false, true),
+            (PinMode::OutOdPuFiltAlt, false, true),
+        ];
+
+        fn into_dynamic_mode(pin: DynamicPin, mode: PinMode) -> DynamicPin {
+            match mode {
+                PinMode::Disabled => pin.into_mode::<Disabled>(),
+                PinMode::DisabledPu => pin.into_mode::<DisabledPu>(),
+                PinMode::Analog => pin.into_mode::<Analog>(),
+                PinMode::InFloat => pin.into_mode::<InFloat>(),
+                PinMode::InFilt => pin.into_mode::<InFilt>(),
+                PinMode::InPu => pin.into_mode::<InPu>(),
+                PinMode::InPuFilt => pin.into_mode::<InPuFilt>(),
+                PinMode::InPd => pin.into_mode::<InPd>(),
+                PinMode::InPdFilt => pin.into_mode::<InPdFilt>(),
+                PinMode::OutPp => pin.into_mode::<OutPp>(),
+                PinMode::OutOs => pin.into_mode::<OutOs>(),
+                PinMode::OutOsPd => pin.into_mode::<OutOsPd>(),
+                PinMode::OutOd => pin.into_mode::<OutOd>(),
+                PinMode::OutOdFilt => pin.into_mode::<OutOdFilt>(),
+                PinMode::OutOdPu => pin.into_mode::<OutOdPu>(),
+                PinMode::OutOdPuFilt => pin.into_mode::<OutOdPuFilt>(),
+                PinMode::OutPpAlt => pin.into_mode::<OutPpAlt>(),
+                PinMode::OutOdAlt => pin.into_mode::<OutOdAlt>(),
+                PinMode::OutOdFiltAlt => pin.into_mode::<OutOdFiltAlt>(),
+                PinMode::OutOdPuAlt => pin.into_mode::<OutOdPuAlt>(),
+                PinMode::OutOdPuFiltAlt => pin.into_mode::<OutOdPuFiltAlt>(),
+            }
+        }
+
+        let p = pac::Peripherals::take().unwrap();
+        let gpio = Gpio::new(p.gpio);
+        let mut pin = gpio.pd13.into_dynamic_pin();
+
+        for (mode, input, output) in MODES {
+            pin = into_dynamic_mode(pin, mode);
+            assert_eq!(pin.mode(), mode);
+
+            match pin.is_high() {
+                Ok(_) => assert!(input, "{} is readable", mode.name()),
+                Err(GpioError::InvalidMode(m)) => assert!(!input && m == mode, "{} is not readable", mode.name()),
+                Err(_) => panic!("{}: unexpected error", mode.name()),
+            }
+
+            match pin.read_output_level() {
+                Ok(_) => assert!(output, "{} output level is readable", mode.name()),
+                Err(GpioError::InvalidMode(m)) => {
+                    assert!(!output && m == mode, "{} output level is not readable", mode.name())
+                }
+                Err(_) => panic!("{}: unexpected error", mode.name()),
+            }
+        }
+
+        let _ = pin.into_mode::<Disabled>();
+    }
+
+    #[test]
+    fn bitbang_spi_clock_idles_at_its_polarity() {
+        let p = pac::Peripherals::take().unwrap();
+        let clocks = p.cmu.split();
+        let gpio = Gpio::new(p.gpio);
+
+        let clk = gpio.pd13.into_mode::<OutPp>();
+        let mosi = gpio.pd14.into_mode::<OutPp>();
+        // Nothing drives `MISO`, so only its pull-up is read
+        let miso = gpio.pd15.into_mode::<InPu>();
+
+        let port_d = unsafe { &*pac::Gpio::ptr() }.port_d();
+        let dout = |pin: u32| port_d.dout().read().pins_dout().bits() & (1 << pin) != 0;
+
+        let mut spi = bitbang::Spi::new(clk, mosi, miso, MODE_3, 100.kHz(), &clocks).unwrap();
+        assert!(dout(13));
+
+        let mut buf = [0x00u8, 0x01];
+        spi.transfer_in_place(&mut buf).unwrap();
+        assert_eq!(buf, [0xFF, 0xFF]);
+
+        // The clock is back to idle, and `MOSI` holds the last bit
+        assert_eq!((dout(13), dout(14)), (true, true));
+    }
+
+    #[test]
+    fn bitbang_spi_rejects_a_baudrate_above_half_the_core_clock() {
+        let p = pac::Peripherals::take().unwrap();
+        let clocks = p.cmu.split();
+        let gpio = Gpio::new(p.gpio);
+
+        let clk = gpio.pd13.into_mode::<OutPp>();
+        let mosi = gpio.pd14.into_mode::<OutPp>();
+        let miso = gpio.pd15.into_mode::<InPu>();
+
+        // `2 * baudrate` does not fit in a `u32`
+        let res = bitbang::Spi::new(clk, mosi, miso, MODE_3, u32::MAX.Hz(), &clocks);
+        assert!(matches!(res, Err(bitbang::BitbangError::InvalidBaudrate(_))));
+    }
+
+    #[test]
+    fn pin_capabilities_follow_the_package() {
+        // Available in all the packages
+        assert!(pin_exists(PortId::A, 0));
+        assert!(pin_exists(PortId::D, 15));
+        assert!(pin_exists(PortId::F, 0));
+
+        // Not bonded out in any package
+        assert!(!pin_exists(PortId::B, 0));
+        assert!(!pin_exists(PortId::D, 8));
+        assert!(!pin_exists(PortId::F, 8));
+        assert!(!pin_exists(PortId::A, 16));
+
+        assert_eq!(
+            pin_exists(PortId::F, 4),
+            cfg!(any(feature = "qfn32", feature = "qfn48"))
+        );
+        assert_eq!(pin_exists(PortId::A, 5), cfg!(feature = "qfn48"));
+
+        assert!(is_debug_pin(PortId::F, 3));
+        assert!(!is_debug_pin(PortId::F, 4));
+        assert!(!is_debug_pin(PortId::A, 0));
+    }
+
+    #[test]
+    fn dump_config_decodes_the_pin_modes() {
+        let p = pac::Peripherals::take().unwrap();
+        let gpio = Gpio::new(p.gpio);
+        let _pd13 = gpio.pd13.into_output_with_state::<OutPp>(PinState::High);
+        let _pd14 = gpio.pd14.into_mode::<InPu>();
+        let _pd15 = gpio.pd15.into_mode::<InPuFilt>();
+
+        let config = Gpio::dump_config();
+        let pin = |id: PinId| config.pins.iter().find(|c| c.port == PortId::D && c.pin == id).unwrap();
+
+        assert_eq!((pin(PinId::Pin13).mode, pin(PinId::Pin13).dout), (PinMode::OutPp, true));
+        assert_eq!(pin(PinId::Pin14).mode, PinMode::InPu);
+        assert_eq!(pin(PinId::Pin15).mode, PinMode::InPuFilt);
+        assert_eq!(pin(PinId::Pin9).mode, PinMode::Disabled);
+
+        // Only the pins of the package are listed
+        assert!(config.pins.iter().all(|c| pin_exists(c.port, c.pin as u8)));
+        assert_eq!(config.ports[3].port, PortId::D);
+    }
+}
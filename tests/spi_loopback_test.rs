@@ -0,0 +1,392 @@
+//! SPI tests using the USART loopback mode, so no slave device needs to be connected
+//!
+//! Run with `cargo test --test spi_loopback_test --features="defmt qfn48"`
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests(setup=rtt_target::rtt_init_defmt!())]
+mod tests {
+    use core::{
+        cell::{Cell, RefCell},
+        convert::Infallible,
+    };
+    use cortex_m::peripheral::DWT;
+    use efm32pg1b_hal::{
+        gpio::pin::Pin,
+        pac::{interrupt, Interrupt, NVIC},
+        prelude::*,
+        usart::spi::{
+            shared::{RefCellBus, RefCellDevice},
+            DataBits,
+        },
+    };
+    use embedded_hal::{
+        digital::ErrorType,
+        spi::{Operation, SpiDevice},
+    };
+
+    type LoopbackSpi = Spi<
+        0,
+        Usart<0>,
+        Pin<'C', 8, OutPp>,
+        Pin<'C', 6, OutPp>,
+        Pin<'C', 7, InFilt>,
+    >;
+
+    #[init]
+    fn init() -> LoopbackSpi {
+        let p = pac::Peripherals::take().unwrap();
+        let clocks = p.cmu.split();
+        let gpio = Gpio::new(p.gpio);
+
+        let tx = gpio.pc6.into_mode::<OutPp>();
+        let rx = gpio.pc7.into_mode::<InFilt>();
+        let clk = gpio.pc8.into_mode::<OutPp>();
+
+        let mut spi = Usart::new(p.usart0).into_spi_bus(clk, tx, rx, spi::MODE_0);
+        spi.set_baudrate(1.MHz(), &clocks).unwrap();
+        spi.set_loopback(true);
+
+        spi
+    }
+
+    #[test]
+    fn loopback_transfer(mut spi: LoopbackSpi) {
+        let write = [1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+        let mut read = [0; 11];
+
+        spi.transfer(&mut read, &write).unwrap();
+        assert_eq!(read, write);
+    }
+
+    #[test]
+    fn loopback_transfer_shorter_write_sends_filler(mut spi: LoopbackSpi) {
+        spi.set_read_filler(0xFF);
+
+        let write = [1u8, 2, 3];
+        let mut read = [0; 6];
+
+        spi.transfer(&mut read, &write).unwrap();
+        assert_eq!(read, [1, 2, 3, 0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn loopback_transfer_shorter_read(mut spi: LoopbackSpi) {
+        let write = [1u8, 2, 3, 4, 5];
+        let mut read = [0; 2];
+
+        spi.transfer(&mut read, &write).unwrap();
+        assert_eq!(read, [1, 2]);
+    }
+
+    #[test]
+    fn loopback_read_block_returns_filler(mut spi: LoopbackSpi) {
+        spi.set_read_filler(0xA5);
+
+        let mut read = [0; 7];
+
+        spi.read_block(&mut read).unwrap();
+        assert_eq!(read, [0xA5; 7]);
+    }
+
+    #[test]
+    fn loopback_read_stream_stops_after_the_last_byte(mut spi: LoopbackSpi) {
+        let mut read = [0; 16];
+
+        spi.read_stream(&mut read).unwrap();
+        assert!(!spi.continuous_clock());
+
+        // Wait for more than one frame (8 us at 1 MHz): no extra byte is clocked after the last one
+        cortex_m::asm::delay(1_000);
+        assert!(!spi.rx_data_available());
+    }
+
+    #[test]
+    fn loopback_read_stream_longer_than_the_buffers_leaves_no_stale_data(mut spi: LoopbackSpi) {
+        let mut read = [0; 64];
+
+        spi.read_stream(&mut read).unwrap();
+        assert!(!spi.continuous_clock());
+        assert!(!spi.rx_data_available());
+
+        // The bus is left usable for the next read
+        spi.set_read_filler(0xC3);
+        let mut read = [0; 4];
+        spi.read_block(&mut read).unwrap();
+        assert_eq!(read, [0xC3; 4]);
+    }
+
+    #[test]
+    fn loopback_read_stream_is_not_slower_than_read_block(mut spi: LoopbackSpi) {
+        let mut cp = cortex_m::Peripherals::take().unwrap();
+        cp.DCB.enable_trace();
+        cp.DWT.enable_cycle_counter();
+
+        let mut read = [0; 64];
+
+        let t0 = DWT::cycle_count();
+        spi.read_block(&mut read).unwrap();
+        let block = DWT::cycle_count().wrapping_sub(t0);
+
+        let t0 = DWT::cycle_count();
+        spi.read_stream(&mut read).unwrap();
+        let stream = DWT::cycle_count().wrapping_sub(t0);
+
+        // Both are bound by the bus (64 frames at 1 MHz), so only allow some headroom for the setup of each read
+        assert!(
+            stream <= block + block / 10,
+            "64 bytes: read_block {} cycles, read_stream {} cycles",
+            block,
+            stream
+        );
+    }
+
+    #[test]
+    fn loopback_transfer_with_inter_frame_delay(mut spi: LoopbackSpi) {
+        let mut cp = cortex_m::Peripherals::take().unwrap();
+        cp.DCB.enable_trace();
+        cp.DWT.enable_cycle_counter();
+
+        let write = [1u8, 2, 3, 4, 5];
+        let mut read = [0; 5];
+
+        let t0 = DWT::cycle_count();
+        spi.transfer(&mut read, &write).unwrap();
+        let no_gap = DWT::cycle_count().wrapping_sub(t0);
+
+        spi.set_inter_frame_delay(10_000);
+        assert_eq!(spi.inter_frame_delay(), 10_000);
+
+        let t0 = DWT::cycle_count();
+        spi.transfer(&mut read, &write).unwrap();
+        let gap = DWT::cycle_count().wrapping_sub(t0);
+        assert_eq!(read, write);
+
+        // At least one gap between each of the 5 frames
+        assert!(gap >= no_gap + 4 * 10_000);
+
+        let mut words = write;
+        spi.transfer_in_place(&mut words).unwrap();
+        assert_eq!(words, write);
+    }
+
+    #[test]
+    fn loopback_write_then_read_discards_the_command(mut spi: LoopbackSpi) {
+        spi.set_read_filler(0x5A);
+
+        let mut read = [0; 3];
+
+        spi.write_then_read(&[0x80, 0x81, 0x82], &mut read).unwrap();
+        assert_eq!(read, [0x5A; 3]);
+    }
+
+    #[test]
+    fn loopback_release_usart_can_be_specialized_again(spi: LoopbackSpi) {
+        let (usart0, clk, tx, rx): (pac::Usart0, _, _, _) = spi.release_usart();
+
+        let mut spi = Usart::new(usart0).into_spi_bus(clk, tx, rx, spi::MODE_0);
+        spi.set_loopback(true);
+
+        let mut words = [0x12u8, 0x34, 0x56];
+        spi.transfer_in_place(&mut words).unwrap();
+        assert_eq!(words, [0x12, 0x34, 0x56]);
+    }
+
+    #[test]
+    fn loopback_transfer_in_place(mut spi: LoopbackSpi) {
+        let write = [1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+        let mut words = write;
+
+        spi.transfer_in_place(&mut words).unwrap();
+        assert_eq!(words, write);
+    }
+
+    #[test]
+    fn loopback_transfer_in_place_keeps_the_order_of_a_pair(mut spi: LoopbackSpi) {
+        // A single pair goes through `txdouble`/`rxdouble`, and both bytes come from the same `rxdouble` read
+        let mut words = [0xA5u8, 0x3C];
+
+        spi.transfer_in_place(&mut words).unwrap();
+        assert_eq!(words, [0xA5, 0x3C]);
+    }
+
+    #[test]
+    fn loopback_bus_fault_is_reported_and_cleared(mut spi: LoopbackSpi) {
+        let usart0 = unsafe { pac::Usart0::steal() };
+        let write = [1u8, 2, 3];
+        let mut read = [0; 3];
+
+        // Pretend that another master drove the slave select input low
+        usart0.ifs().write(|w| w.ssm().set_bit());
+
+        assert!(matches!(spi.transfer(&mut read, &write), Err(SpiError::BusFault)));
+        assert!(usart0.if_().read().ssm().bit_is_clear());
+
+        spi.transfer(&mut read, &write).unwrap();
+        assert_eq!(read, write);
+    }
+
+    #[test]
+    fn loopback_overflow_is_not_reported_as_stale_data(mut spi: LoopbackSpi) -> Result<(), SpiError> {
+        // `write()` never reads back the received bytes, so this overflows the RX buffer
+        spi.write(&[0xAAu8; 8])?;
+        spi.flush()?;
+
+        // The overflowed bytes must not leak into (or fail) the following transfer
+        let write = [0x55u8, 0x66, 0x77];
+        let mut read = [0; 3];
+        spi.transfer(&mut read, &write)?;
+        assert_eq!(read, write);
+
+        Ok(())
+    }
+
+    /// Send 2 more frames as soon as the first frame of a transfer is received (only once)
+    #[interrupt]
+    fn USART0_RX() {
+        let usart0 = unsafe { pac::Usart0::steal() };
+
+        usart0.ien().modify(|_, w| w.rxdatav().clear_bit());
+        usart0.txdouble().write(|w| unsafe {
+            w.txdata0().bits(0xEE);
+            w.txdata1().bits(0xEE)
+        });
+    }
+
+    #[test]
+    fn loopback_overflow_during_a_transfer_is_reported_and_cleared(mut spi: LoopbackSpi) {
+        let usart0 = unsafe { pac::Usart0::steal() };
+        let write = [0x11u8, 0x22];
+        let mut read = [0; 2];
+
+        // The transfer only reads the RX buffer (2 frames, plus 1 in the shift register) once its 2 frames are sent,
+        // so the 2 frames sent by `USART0_RX` in the meantime overflow it
+        usart0.ien().modify(|_, w| w.rxdatav().set_bit());
+        unsafe { NVIC::unmask(Interrupt::USART0_RX) };
+
+        let res = spi.transfer(&mut read, &write);
+
+        NVIC::mask(Interrupt::USART0_RX);
+        usart0.ien().modify(|_, w| w.rxdatav().clear_bit());
+
+        assert!(matches!(res, Err(SpiError::RxOverflow)));
+        assert!(usart0.if_().read().rxof().bit_is_clear());
+
+        spi.transfer(&mut read, &write).unwrap();
+        assert_eq!(read, write);
+    }
+
+    #[test]
+    fn loopback_narrow_frames(mut spi: LoopbackSpi) {
+        spi.configure_frame(DataBits::Six).unwrap();
+
+        // Only the 6 least significant bits of each byte are sent
+        let write = [0x3Fu8, 0xC1, 0x15];
+        let mut read = [0; 3];
+        spi.transfer(&mut read, &write).unwrap();
+        assert_eq!(read.map(|b| b & 0x3F), [0x3F, 0x01, 0x15]);
+
+        spi.configure_frame(DataBits::Eight).unwrap();
+    }
+
+    #[test]
+    fn loopback_sixteen_bit_frames(mut spi: LoopbackSpi) {
+        spi.configure_frame(DataBits::Sixteen).unwrap();
+
+        let write = [0x1234u16, 0xABCD, 0x00FF, 0xFF00, 0x5AA5];
+        let mut read = [0u16; 5];
+        spi.transfer(&mut read, &write).unwrap();
+        assert_eq!(read, write);
+
+        let mut words = write;
+        spi.transfer_in_place(&mut words).unwrap();
+        assert_eq!(words, write);
+
+        // The read filler byte is repeated in both halves of the word
+        spi.set_read_filler(0xA5);
+        let mut read = [0u16; 3];
+        spi.read(&mut read).unwrap();
+        assert_eq!(read, [0xA5A5; 3]);
+
+        spi.write(&write).unwrap();
+        spi.flush().unwrap();
+
+        spi.configure_frame(DataBits::Eight).unwrap();
+    }
+
+    #[test]
+    fn loopback_shared_bus_selects_one_device_per_transaction(spi: LoopbackSpi) {
+        // CS pin which records its level, and how many times it was driven low
+        struct RecordingCs<'a> {
+            high: &'a Cell<bool>,
+            selections: &'a Cell<u8>,
+        }
+
+        impl ErrorType for RecordingCs<'_> {
+            type Error = Infallible;
+        }
+
+        impl OutputPin for RecordingCs<'_> {
+            fn set_low(&mut self) -> Result<(), Self::Error> {
+                self.high.set(false);
+                self.selections.set(self.selections.get() + 1);
+                Ok(())
+            }
+
+            fn set_high(&mut self) -> Result<(), Self::Error> {
+                self.high.set(true);
+                Ok(())
+            }
+        }
+
+        // `split()` only reads the clock tree, the USART clock is left as configured by `init()`
+        let clocks = unsafe { pac::Cmu::steal() }.split();
+        let bus = RefCell::new(spi);
+        let (high_a, selections_a) = (Cell::new(false), Cell::new(0));
+        let (high_b, selections_b) = (Cell::new(false), Cell::new(0));
+
+        let cs_a = RecordingCs {
+            high: &high_a,
+            selections: &selections_a,
+        };
+        let cs_b = RecordingCs {
+            high: &high_b,
+            selections: &selections_b,
+        };
+        let mut dev_a = RefCellDevice::new(&bus, cs_a, &clocks).unwrap();
+        let mut dev_b = RefCellDevice::new(&bus, cs_b, &clocks).unwrap();
+        assert!(high_a.get() && high_b.get());
+
+        let mut read = [0u8; 3];
+        dev_a.transfer(&mut read, &[1, 2, 3]).unwrap();
+        assert_eq!(read, [1, 2, 3]);
+        assert_eq!((selections_a.get(), selections_b.get()), (1, 0));
+
+        // All the operations of a transaction are done with a single selection
+        let mut words = [4u8, 5, 6];
+        dev_b
+            .transaction(&mut [
+                Operation::Write(&[0x80]),
+                Operation::DelayNs(1_000),
+                Operation::TransferInPlace(&mut words),
+            ])
+            .unwrap();
+        assert_eq!(words, [4, 5, 6]);
+        assert_eq!((selections_a.get(), selections_b.get()), (1, 1));
+        assert!(high_a.get() && high_b.get());
+
+        // A driver which controls its own CS pin uses the same bus
+        let mut words = [7u8, 8];
+        RefCellBus::new(&bus).transfer_in_place(&mut words).unwrap();
+        assert_eq!(words, [7, 8]);
+    }
+
+    #[test]
+    fn loopback_selftest(mut spi: LoopbackSpi) {
+        spi.set_loopback(false);
+        assert!(spi.selftest());
+        assert!(!spi.loopback());
+    }
+}
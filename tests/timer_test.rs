@@ -0,0 +1,677 @@
+//! Timer tests
+//!
+//! Run with `cargo test --test timer_test --features="defmt"`
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests(setup=rtt_target::rtt_init_defmt!())]
+mod tests {
+    use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+    use cortex_m::peripheral::{DWT, NVIC};
+    use efm32pg1b_hal::{
+        cmu::{CmuExt, HfPerPrescaler},
+        gpio::{Gpio, InPd, OutPp},
+        pac::{self, interrupt},
+        systick::SysTickDelay,
+        timer::{CompareAction, PwmPolarity, Timer, TimerChannelDelay, TimerDivider, TimerExt, TimerPwm},
+    };
+    use embedded_hal::{delay::DelayNs, pwm::SetDutyCycle};
+    use fugit::RateExtU32;
+
+    #[test]
+    fn pwm_channels_keep_their_route_locations() {
+        let p = pac::Peripherals::take().unwrap();
+        let gpio = Gpio::new(p.gpio);
+        let timer = p.timer0.into_timer(TimerDivider::Div1);
+        let (tim0ch0, tim0ch1, _tim0ch2, _tim0ch3) = timer.into_channels();
+
+        // `pd13` is `CC0` location 21, `pd14` is `CC1` location 21
+        let _pwm0 = tim0ch0.into_pwm(gpio.pd13.into_mode::<OutPp>());
+        let _pwm1 = tim0ch1.into_pwm(gpio.pd14.into_mode::<OutPp>());
+
+        let routeloc0 = unsafe { pac::Timer0::steal() }.routeloc0().read();
+        assert_eq!(routeloc0.cc0loc().bits(), 21);
+        assert_eq!(routeloc0.cc1loc().bits(), 21);
+    }
+
+    #[test]
+    fn pwm_remap_keeps_the_other_route_locations() {
+        let p = pac::Peripherals::take().unwrap();
+        let gpio = Gpio::new(p.gpio);
+        let timer = p.timer0.into_timer(TimerDivider::Div1);
+        let (tim0ch0, tim0ch1, _tim0ch2, _tim0ch3) = timer.into_channels();
+
+        let pwm0 = tim0ch0.into_pwm(gpio.pd13.into_mode::<OutPp>());
+        let _pwm1 = tim0ch1.into_pwm(gpio.pd15.into_mode::<OutPp>());
+
+        // `pd14` is `CC0` location 22
+        let _pwm0 = pwm0.remap(gpio.pd14.into_mode::<OutPp>());
+
+        let timer0 = unsafe { pac::Timer0::steal() };
+        let routeloc0 = timer0.routeloc0().read();
+        assert_eq!(routeloc0.cc0loc().bits(), 22);
+        assert_eq!(routeloc0.cc1loc().bits(), 22);
+
+        let routepen = timer0.routepen().read();
+        assert!(routepen.cc0pen().bit_is_set() && routepen.cc1pen().bit_is_set());
+    }
+
+    #[test]
+    fn compare_match_calls_the_handler_of_its_channel() {
+        static MATCHES: AtomicU8 = AtomicU8::new(0);
+
+        fn alarm(channel: u8) {
+            MATCHES.fetch_add(1 << (channel * 4), Ordering::SeqCst);
+        }
+
+        let p = pac::Peripherals::take().unwrap();
+        let gpio = Gpio::new(p.gpio);
+        let timer = p.timer0.into_timer(TimerDivider::Div1);
+        let (tim0ch0, tim0ch1, _tim0ch2, _tim0ch3) = timer.into_channels();
+
+        let mut ch0 = tim0ch0.into_output_compare(gpio.pd13.into_mode::<OutPp>(), CompareAction::None);
+        let mut ch1 = tim0ch1.into_output_compare(gpio.pd14.into_mode::<OutPp>(), CompareAction::None);
+
+        // The NVIC interrupt is unmasked by `on_match()`, so the handler is called here instead of the `TIMER0` vector
+        cortex_m::interrupt::free(|_| {
+            ch0.on_match(1_000, alarm);
+            ch1.on_match(u16::MAX, alarm);
+
+            // `u16::MAX` is above `TOP`, so it is wrapped around the timer period
+            assert_eq!(ch1.compare(), 0);
+
+            // Wait for more than one timer period (`u16::MAX` ticks)
+            cortex_m::asm::delay(2 * u16::MAX as u32);
+            Timer::<0>::on_compare_interrupt();
+            assert_eq!(MATCHES.load(Ordering::SeqCst), 0x11);
+
+            ch1.cancel_match();
+            cortex_m::asm::delay(2 * u16::MAX as u32);
+            Timer::<0>::on_compare_interrupt();
+            assert_eq!(MATCHES.load(Ordering::SeqCst), 0x12);
+
+            ch0.cancel_match();
+            NVIC::mask(pac::Interrupt::TIMER0);
+        });
+    }
+
+    #[test]
+    fn delay_does_not_keep_the_timer_interrupt_of_a_compare_match_pending() {
+        static MATCHES: AtomicU8 = AtomicU8::new(0);
+
+        fn alarm(_channel: u8) {
+            MATCHES.fetch_add(1, Ordering::SeqCst);
+        }
+
+        let p = pac::Peripherals::take().unwrap();
+        let clocks = p.cmu.split();
+        let gpio = Gpio::new(p.gpio);
+        let timer = p.timer0.into_timer(TimerDivider::Div1);
+        let (tim0ch0, tim0ch1, _tim0ch2, _tim0ch3) = timer.into_channels();
+
+        let mut delay = tim0ch0.into_delay(&clocks);
+        let mut ch1 = tim0ch1.into_output_compare(gpio.pd14.into_mode::<OutPp>(), CompareAction::None);
+
+        cortex_m::interrupt::free(|_| {
+            ch1.on_match(1_000, alarm);
+
+            // Longer than one timer period, so the alarm matches while the delay is waiting
+            delay.delay_ms(5);
+            Timer::<0>::on_compare_interrupt();
+            assert_eq!(MATCHES.load(Ordering::SeqCst), 1);
+
+            // Once the alarm is handled and cancelled, nothing raises the (still unmasked) `TIMER0` interrupt again
+            ch1.cancel_match();
+            NVIC::unpend(pac::Interrupt::TIMER0);
+            assert!(!NVIC::is_pending(pac::Interrupt::TIMER0));
+
+            NVIC::mask(pac::Interrupt::TIMER0);
+        });
+    }
+
+    #[test]
+    fn short_delays_wait_at_least_the_requested_ticks() {
+        let p = pac::Peripherals::take().unwrap();
+        let clocks = p.cmu.split();
+        let timer = p.timer0.into_timer(TimerDivider::Div1);
+        let (counter, tim0ch0, _tim0ch1, _tim0ch2, _tim0ch3) = timer.into_channels_with_counter();
+        let tick_freq = counter.tick_frequency(&clocks).raw() as u64;
+        let mut delay = tim0ch0.into_delay(&clocks);
+
+        // `TOP` is `u16::MAX - 1`, so the counter wraps every `u16::MAX` ticks
+        let period = u16::MAX as u32;
+
+        for ns in [1, 10, 50, 100, 250, 500, 999, 1_000, 1_500, 5_000] {
+            let min_ticks = (tick_freq * ns as u64).div_ceil(1_000_000_000) as u32;
+
+            let start = counter.counter() as u32;
+            delay.delay_ns(ns);
+            let end = counter.counter() as u32;
+            let elapsed = (end + period - start) % period;
+
+            assert!(
+                elapsed >= min_ticks,
+                "delay_ns({}) waited {} ticks, expected at least {}",
+                ns,
+                elapsed,
+                min_ticks
+            );
+        }
+    }
+
+    #[test]
+    fn systick_delay_longer_than_the_reload_value() {
+        let p = pac::Peripherals::take().unwrap();
+        let mut cp = cortex_m::Peripherals::take().unwrap();
+        let clocks = p.cmu.split();
+        cp.DCB.enable_trace();
+        cp.DWT.enable_cycle_counter();
+
+        let mut delay = SysTickDelay::new(cp.SYST, &clocks);
+        let core_clk = clocks.hf_core_clk().raw();
+
+        // More than one 24 bit SysTick period at any core clock above 16.8 MHz
+        for us in [1, 100, 1_100_000] {
+            let min_cycles = (core_clk as u64 * us as u64 / 1_000_000) as u32;
+
+            let start = DWT::cycle_count();
+            delay.delay_us(us);
+            let elapsed = DWT::cycle_count().wrapping_sub(start);
+
+            assert!(
+                elapsed >= min_cycles,
+                "delay_us({}) waited {} cycles, expected at least {}",
+                us,
+                elapsed,
+                min_cycles
+            );
+        }
+    }
+
+    #[test]
+    fn pwm_buffered_and_immediate_duty_cycle() {
+        let p = pac::Peripherals::take().unwrap();
+        let gpio = Gpio::new(p.gpio);
+        let timer = p.timer0.into_timer(TimerDivider::Div1024);
+        let (tim0ch0, _tim0ch1, _tim0ch2, _tim0ch3) = timer.into_channels();
+        let mut pwm = tim0ch0.into_pwm(gpio.pd13.into_mode::<OutPp>());
+
+        let timer0 = unsafe { pac::Timer0::steal() };
+
+        // Stop the timer, so that no overflow copies `CCVB` into `CCV` during the test
+        timer0.cmd().write(|w| w.stop().set_bit());
+        pwm.set_duty_cycle_immediate(0);
+
+        // The buffered value is only written to `CCVB`
+        pwm.set_duty_cycle(100).unwrap();
+        assert_eq!(timer0.cc0_ccvb().read().ccvb().bits(), 100);
+        assert_eq!(timer0.cc0_ccv().read().ccv().bits(), 0);
+
+        // The immediate value is written to `CCV` (and `CCVB`)
+        pwm.set_duty_cycle_immediate(200);
+        assert_eq!(timer0.cc0_ccv().read().ccv().bits(), 200);
+        assert_eq!(timer0.cc0_ccvb().read().ccvb().bits(), 200);
+    }
+
+    #[test]
+    fn pwm_duty_percent_extremes_are_exact() {
+        let p = pac::Peripherals::take().unwrap();
+        let gpio = Gpio::new(p.gpio);
+        let timer = p.timer0.into_timer(TimerDivider::Div1024);
+        let (tim0ch0, _tim0ch1, _tim0ch2, _tim0ch3) = timer.into_channels();
+        let mut pwm = tim0ch0.into_pwm(gpio.pd13.into_mode::<OutPp>());
+
+        let timer0 = unsafe { pac::Timer0::steal() };
+        let top = timer0.top().read().top().bits();
+
+        pwm.set_duty_percent(100);
+        assert_eq!(timer0.cc0_ccvb().read().ccvb().bits(), top + 1);
+
+        pwm.set_duty_percent(0);
+        assert_eq!(timer0.cc0_ccvb().read().ccvb().bits(), 0);
+
+        // Values above 100% saturate
+        pwm.set_duty_percent(150);
+        assert_eq!(timer0.cc0_ccvb().read().ccvb().bits(), top + 1);
+    }
+
+    #[test]
+    fn pwm_polarity_inverts_the_output() {
+        let p = pac::Peripherals::take().unwrap();
+        let gpio = Gpio::new(p.gpio);
+        let timer = p.timer0.into_timer(TimerDivider::Div1024);
+        let (_tim0ch0, tim0ch1, _tim0ch2, _tim0ch3) = timer.into_channels();
+        let mut pwm = tim0ch1.into_pwm(gpio.pd14.into_mode::<OutPp>());
+
+        let timer0 = unsafe { pac::Timer0::steal() };
+
+        assert_eq!(pwm.polarity(), PwmPolarity::ActiveHigh);
+        assert!(timer0.cc1_ctrl().read().outinv().bit_is_clear());
+
+        pwm.set_polarity(PwmPolarity::ActiveLow);
+        assert_eq!(pwm.polarity(), PwmPolarity::ActiveLow);
+        assert!(timer0.cc1_ctrl().read().outinv().bit_is_set());
+
+        // The PWM mode is not changed
+        assert!(timer0.cc1_ctrl().read().mode().is_pwm());
+    }
+
+    #[test]
+    fn delay_recalibrate_follows_the_hf_per_clock() {
+        let p = pac::Peripherals::take().unwrap();
+        let clocks = p.cmu.split();
+        let timer = p.timer0.into_timer(TimerDivider::Div1);
+        let (tim0ch0, _tim0ch1, _tim0ch2, _tim0ch3) = timer.into_channels();
+        let mut delay = tim0ch0.into_delay(&clocks);
+
+        assert_eq!(delay.tick_frequency(), clocks.hf_per_clk());
+
+        let clocks = clocks.set_hf_per_prescaler(HfPerPrescaler::new(2).unwrap());
+        delay.recalibrate(&clocks);
+        assert_eq!(delay.tick_frequency(), clocks.hf_per_clk());
+
+        // restore the HF Peripheral Clock for the other tests
+        let _clocks = clocks.set_hf_per_prescaler(HfPerPrescaler::new(1).unwrap());
+    }
+
+    #[test]
+    fn chained_timers_count_the_overflows_of_timer0() {
+        let p = pac::Peripherals::take().unwrap();
+        let timer0 = p.timer0.into_timer(TimerDivider::Div1);
+        let timer1 = p.timer1.into_timer(TimerDivider::Div1024);
+        let mut timer32 = Timer::chain(timer0, timer1);
+
+        // Just below the first overflow of `Timer0`
+        timer32.set_count(0xFFF0);
+        assert_eq!(timer32.count(), 0xFFF0);
+
+        timer32.start();
+
+        let mut bail_countdown = 1_000_000;
+        while timer32.count() < 0x0001_0000 {
+            bail_countdown -= 1;
+            assert!(bail_countdown > 0);
+        }
+
+        timer32.stop();
+
+        let timer1 = unsafe { pac::Timer1::steal() };
+        assert_eq!(timer1.cnt().read().cnt().bits(), 1);
+    }
+
+    #[test]
+    fn pulse_counter_counts_the_rising_edges_of_its_pin() {
+        let p = pac::Peripherals::take().unwrap();
+        let gpio = Gpio::new(p.gpio);
+        let timer = p.timer1.into_timer(TimerDivider::Div1);
+
+        // `pd14` is `CC1` location 21
+        let mut pulses = timer.into_pulse_counter(gpio.pd14.into_mode::<InPd>());
+        assert_eq!(pulses.count(), 0);
+
+        // Toggle the pull direction of the input (`DOUT`), to generate edges without any external wiring
+        let port_d = unsafe { &*pac::Gpio::ptr() }.port_d();
+        let set_pin = |high: bool| {
+            port_d.dout().modify(|r, w| unsafe {
+                let bits = r.pins_dout().bits() & !(1 << 14);
+                w.pins_dout().bits(bits | ((high as u16) << 14))
+            });
+
+            let mut bail_countdown = 1_000_000;
+            while (port_d.din().read().pins_din().bits() & (1 << 14) != 0) != high {
+                bail_countdown -= 1;
+                assert!(bail_countdown > 0);
+            }
+        };
+
+        for _ in 0..5 {
+            set_pin(true);
+            set_pin(false);
+        }
+        assert_eq!(pulses.count(), 5);
+
+        // Falling edges are not counted
+        set_pin(true);
+        pulses.reset();
+        set_pin(false);
+        assert_eq!(pulses.count(), 0);
+
+        let (timer, _pd14) = pulses.free();
+        assert!(!timer.is_running());
+    }
+
+    #[test]
+    fn with_frequency_picks_the_closest_slower_divider() {
+        let p = pac::Peripherals::take().unwrap();
+        let clocks = p.cmu.split();
+        let mut timer = p.timer0.into_timer(TimerDivider::Div1);
+
+        assert_eq!(clocks.hf_per_clk(), 19.MHz::<1, 1>());
+
+        let cases = [
+            (50.MHz(), TimerDivider::Div1),
+            (19.MHz(), TimerDivider::Div1),
+            (10.MHz(), TimerDivider::Div2),
+            (1.MHz(), TimerDivider::Div32),
+            (100.kHz(), TimerDivider::Div256),
+            (18_554.Hz(), TimerDivider::Div1024),
+            // Slower than the largest divider
+            (1.kHz(), TimerDivider::Div1024),
+        ];
+
+        for (target, divider) in cases {
+            let tick_freq;
+            (timer, tick_freq) = timer.with_frequency(target, &clocks);
+
+            let presc = unsafe { pac::Timer0::steal() }.ctrl().read().presc().variant();
+            assert_eq!(presc, Some(divider));
+            assert_eq!(tick_freq, timer.tick_frequency(&clocks));
+        }
+    }
+
+    #[test]
+    fn delay_keeps_the_interrupt_enables_of_the_other_channels() {
+        let p = pac::Peripherals::take().unwrap();
+        let clocks = p.cmu.split();
+        let gpio = Gpio::new(p.gpio);
+        let timer = p.timer0.into_timer(TimerDivider::Div1);
+        let (tim0ch0, tim0ch1, _tim0ch2, _tim0ch3) = timer.into_channels();
+        let mut delay = tim0ch0.into_delay(&clocks);
+        let _pwm = tim0ch1.into_pwm(gpio.pd14.into_mode::<OutPp>());
+
+        let timer0 = unsafe { pac::Timer0::steal() };
+
+        // Stands in for an interrupt enabled by the user of the PWM channel (the NVIC interrupt stays masked), and for
+        // a `CC0` interrupt left enabled before the delay
+        timer0.ien().modify(|_, w| w.cc1().set_bit().cc0().set_bit());
+
+        delay.delay_us(100);
+
+        // The delay clears its own interrupt enable and flag, and keeps the one of the other channel
+        let ien = timer0.ien().read();
+        assert!(ien.cc1().bit_is_set());
+        assert!(ien.cc0().bit_is_clear());
+        assert!(timer0.ifl().read().cc0().bit_is_clear());
+
+        timer0.ien().reset();
+    }
+
+    #[test]
+    fn timer_pwm_duty_cycles_latch_on_the_same_overflow() {
+        let p = pac::Peripherals::take().unwrap();
+        let gpio = Gpio::new(p.gpio);
+        let timer = p.timer0.into_timer(TimerDivider::Div1024);
+        let (mut counter, tim0ch0, tim0ch1, _tim0ch2, _tim0ch3) = timer.into_channels_with_counter();
+
+        let timer0 = unsafe { pac::Timer0::steal() };
+
+        // Stop the timer, so that the buffered values can be checked before the overflow
+        counter.stop();
+
+        let mut pwm = TimerPwm::new(counter)
+            .with_channel(tim0ch0.into_pwm(gpio.pd13.into_mode::<OutPp>()))
+            .with_channel(tim0ch1.into_pwm(gpio.pd14.into_mode::<OutPp>()));
+        assert!(pwm.has_channel(1) && !pwm.has_channel(2));
+
+        pwm.set_period(99);
+        pwm.set_duty_cycles([Some(25), Some(75), Some(50), None]);
+        assert_eq!(timer0.topb().read().topb().bits(), 99);
+        assert_eq!(timer0.cc0_ccvb().read().ccvb().bits(), 25);
+        assert_eq!(timer0.cc1_ccvb().read().ccvb().bits(), 75);
+        // Channel 2 is not part of the group
+        assert_ne!(timer0.cc2_ccvb().read().ccvb().bits(), 50);
+
+        // Only the channels of the group can be disabled
+        pwm.disable(1);
+        pwm.disable(2);
+        assert!(pwm.is_enabled(0) && !pwm.is_enabled(1));
+        pwm.enable(1);
+        assert!(pwm.is_enabled(1));
+
+        // Overflow on the next tick, which copies all the buffered values
+        let mut counter = pwm.free();
+        counter.set_counter(counter.top());
+        counter.start();
+        while timer0.top().read().top().bits() != 99 {}
+        counter.stop();
+
+        assert_eq!(timer0.cc0_ccv().read().ccv().bits(), 25);
+        assert_eq!(timer0.cc1_ccv().read().ccv().bits(), 75);
+    }
+
+    #[test]
+    fn tone_frequency_is_set_from_the_top_value() {
+        let p = pac::Peripherals::take().unwrap();
+        let clocks = p.cmu.split();
+        let gpio = Gpio::new(p.gpio);
+        let pin = gpio.pd13.into_mode::<OutPp>();
+
+        let timer0 = unsafe { pac::Timer0::steal() };
+        let port_d = unsafe { &*pac::Gpio::ptr() }.port_d();
+
+        // 19 MHz / (2 * 1 kHz) = 9500 ticks per half period
+        let (mut tone, freq) = p.timer0.into_timer(TimerDivider::Div1).into_tone(pin, 1.kHz(), &clocks);
+        assert_eq!(freq, 1.kHz::<1, 1>());
+        assert_eq!(timer0.top().read().top().bits(), 9499);
+        assert!(timer0.cc0_ctrl().read().mode().is_outputcompare());
+
+        // A half period longer than the 16 bit counter needs a larger divider
+        let freq = tone.set_frequency(100.Hz(), &clocks);
+        assert_eq!(freq, 100.Hz::<1, 1>());
+        assert_eq!(timer0.ctrl().read().presc().variant(), Some(TimerDivider::Div2));
+        assert_eq!(timer0.top().read().top().bits(), 47_499);
+
+        tone.mute().unwrap();
+        assert!(tone.is_muted());
+        assert!(timer0.routepen().read().cc0pen().bit_is_clear());
+        assert_eq!(port_d.dout().read().pins_dout().bits() & (1 << 13), 0);
+
+        tone.unmute();
+        assert!(!tone.is_muted());
+        assert_eq!(tone.frequency(), 100.Hz::<1, 1>());
+    }
+
+    #[test]
+    fn delay_channels_are_tracked_until_freed() {
+        let p = pac::Peripherals::take().unwrap();
+        let clocks = p.cmu.split();
+        let timer = p.timer0.into_timer(TimerDivider::Div1);
+        let (counter, _tim0ch0, tim0ch1, _tim0ch2, tim0ch3) = timer.into_channels_with_counter();
+
+        assert_eq!(counter.delay_channels(), 0);
+
+        let delay1 = tim0ch1.into_delay(&clocks);
+        let delay3 = tim0ch3.into_delay(&clocks);
+        assert_eq!(counter.delay_channels(), 0b1010);
+
+        let _tim0ch1 = delay1.free();
+        assert_eq!(counter.delay_channels(), 0b1000);
+
+        let _tim0ch3 = delay3.free();
+        assert_eq!(counter.delay_channels(), 0);
+    }
+
+    #[test]
+    fn counter_can_be_set_before_the_timer_is_split() {
+        let p = pac::Peripherals::take().unwrap();
+        let mut timer = p.timer0.into_timer(TimerDivider::Div1);
+
+        // The timer is not started before it is split, so the counter keeps its value
+        timer.set_counter(1234);
+        assert_eq!(timer.counter(), 1234);
+
+        timer.reset_counter();
+        assert_eq!(timer.counter(), 0);
+    }
+
+    #[test]
+    fn delay_longer_than_the_timer_period_starting_at_top() {
+        let p = pac::Peripherals::take().unwrap();
+        let mut cp = cortex_m::Peripherals::take().unwrap();
+        let clocks = p.cmu.split();
+        cp.DCB.enable_trace();
+        cp.DWT.enable_cycle_counter();
+
+        let timer = p.timer0.into_timer(TimerDivider::Div1024);
+        let (mut counter, tim0ch0, _tim0ch1, _tim0ch2, _tim0ch3) = timer.into_channels_with_counter();
+        let tick_freq = counter.tick_frequency(&clocks).raw() as u64;
+        let core_clk = clocks.hf_core_clk().raw() as u64;
+        let mut delay = tim0ch0.into_delay(&clocks);
+
+        // 3.5 counter periods of 100 ticks
+        let top = counter.set_top(99);
+        let ticks = 350;
+
+        // Start when `CNT == TOP`, i.e. right before the counter wraps around
+        counter.set_counter(top);
+        let start = DWT::cycle_count();
+        delay.delay_ns((ticks * 1_000_000_000 / tick_freq) as u32);
+        let elapsed = DWT::cycle_count().wrapping_sub(start) as u64 * tick_freq / core_clk;
+
+        // The delay starts somewhere within the tick in which `CNT` is read
+        assert!(
+            (ticks - 1..=ticks + 1).contains(&elapsed),
+            "waited {} ticks, expected {}",
+            elapsed,
+            ticks
+        );
+    }
+
+    #[test]
+    fn timer_can_be_started_and_stopped_before_it_is_split() {
+        let p = pac::Peripherals::take().unwrap();
+        let mut timer = p.timer0.into_timer(TimerDivider::Div1);
+
+        assert!(!timer.is_running());
+
+        timer.start();
+        assert!(timer.is_running());
+        cortex_m::asm::delay(1_000);
+        assert_ne!(timer.counter(), 0);
+
+        timer.stop();
+        assert!(!timer.is_running());
+    }
+
+    #[interrupt]
+    fn TIMER0() {
+        Timer::<0>::on_compare_interrupt();
+    }
+
+    #[test]
+    fn delay_is_cancelled_from_the_alarm_of_another_channel() {
+        fn cancel_delay(_channel: u8) {
+            TimerChannelDelay::<0, 0>::cancel();
+        }
+
+        let p = pac::Peripherals::take().unwrap();
+        let mut cp = cortex_m::Peripherals::take().unwrap();
+        let clocks = p.cmu.split();
+        let gpio = Gpio::new(p.gpio);
+        cp.DCB.enable_trace();
+        cp.DWT.enable_cycle_counter();
+
+        let timer = p.timer0.into_timer(TimerDivider::Div1);
+        let (tim0ch0, tim0ch1, _tim0ch2, _tim0ch3) = timer.into_channels();
+        let mut delay = tim0ch0.into_delay(&clocks);
+        let mut ch1 = tim0ch1.into_output_compare(gpio.pd14.into_mode::<OutPp>(), CompareAction::None);
+
+        // The alarm is handled by the `TIMER0` interrupt, while the delay is waiting
+        ch1.on_match(1_000, cancel_delay);
+
+        let start = DWT::cycle_count();
+        delay.delay_ms(1_000);
+        let elapsed = DWT::cycle_count().wrapping_sub(start);
+
+        ch1.cancel_match();
+        NVIC::mask(pac::Interrupt::TIMER0);
+
+        // The alarm matches within one timer period (`u16::MAX` ticks, i.e. HF Peripheral Clock cycles)
+        let max_cycles = 2 * u16::MAX as u32 * (clocks.hf_core_clk().raw() / clocks.hf_per_clk().raw());
+        assert!(
+            elapsed < max_cycles,
+            "the delay waited {} cycles, expected less than {}",
+            elapsed,
+            max_cycles
+        );
+    }
+
+    #[test]
+    fn top_can_be_set_before_the_timer_is_split() {
+        let p = pac::Peripherals::take().unwrap();
+        let mut timer = p.timer0.into_timer(TimerDivider::Div1);
+
+        assert_eq!(timer.top(), u16::MAX - 1);
+
+        assert_eq!(timer.set_top(999), 999);
+        assert_eq!(timer.top(), 999);
+
+        // Capped, so that a PWM channel can still reach 100% duty cycle
+        assert_eq!(timer.set_top(u16::MAX), u16::MAX - 1);
+        assert_eq!(timer.top(), u16::MAX - 1);
+    }
+
+    #[test]
+    fn pulse_longer_than_the_timer_period_is_never_cut_short() {
+        static HOLD_UP: AtomicBool = AtomicBool::new(false);
+
+        /// Hold the CPU for longer than a compare step of the pulse (half a timer period), once
+        fn hold_up(_channel: u8) {
+            if HOLD_UP.swap(false, Ordering::SeqCst) {
+                cortex_m::asm::delay(1_500);
+            }
+        }
+
+        let p = pac::Peripherals::take().unwrap();
+        let mut cp = cortex_m::Peripherals::take().unwrap();
+        let clocks = p.cmu.split();
+        let gpio = Gpio::new(p.gpio);
+        cp.DCB.enable_trace();
+        cp.DWT.enable_cycle_counter();
+
+        let timer = p.timer0.into_timer(TimerDivider::Div1);
+        let (mut counter, mut tim0ch0, tim0ch1, _tim0ch2, _tim0ch3) = timer.into_channels_with_counter();
+        let mut pin = gpio.pd13.into_mode::<OutPp>();
+        let mut ch1 = tim0ch1.into_output_compare(gpio.pd14.into_mode::<OutPp>(), CompareAction::None);
+
+        // 10 counter periods of 1000 ticks
+        counter.set_top(999);
+        let tick_freq = counter.tick_frequency(&clocks).raw() as u64;
+        let core_clk = clocks.hf_core_clk().raw() as u64;
+        let ticks = 10_000;
+        let width_ns = (ticks * 1_000_000_000 / tick_freq) as u32;
+        let min_cycles = (ticks * core_clk / tick_freq) as u32;
+
+        let mut pulse = || {
+            let start = DWT::cycle_count();
+            tim0ch0.pulse(&mut pin, width_ns, &clocks);
+            DWT::cycle_count().wrapping_sub(start)
+        };
+
+        // The pulse starts a few ticks after it is programmed
+        let elapsed = pulse();
+        assert!(
+            (min_cycles..min_cycles + 2_000).contains(&elapsed),
+            "the pulse took {} cycles, expected {}",
+            elapsed,
+            min_cycles
+        );
+
+        // The `TIMER0` interrupt holds the CPU up past a compare step in the middle of the pulse
+        ch1.on_match(500, hold_up);
+        HOLD_UP.store(true, Ordering::SeqCst);
+        let elapsed = pulse();
+        ch1.cancel_match();
+        NVIC::mask(pac::Interrupt::TIMER0);
+
+        assert!(!HOLD_UP.load(Ordering::SeqCst));
+        assert!(
+            elapsed >= min_cycles,
+            "the pulse took {} cycles, expected at least {}",
+            elapsed,
+            min_cycles
+        );
+    }
+}